@@ -1,11 +1,14 @@
 mod client;
 mod contest;
+#[cfg(test)]
+mod fixtures;
 mod problem;
 mod submission;
 mod types;
 
-pub use client::AtCoderClient;
+pub use client::{AtCoderClient, AtCoderClientBuilder, DEFAULT_REQUESTS_PER_SECOND};
 pub use types::{
-    AtCoderContest, AtCoderProblem, AtCoderSubmission, AtCoderSubmissionListResponse,
-    ContestTypeSpecifier,
+    AtCoderContest, AtCoderContestResult, AtCoderProblem, AtCoderProblemDetail, AtCoderStandings,
+    AtCoderStandingsRow, AtCoderStandingsTask, AtCoderSubmission, AtCoderSubmissionListResponse,
+    AtCoderTaskResult, AtCoderTotalResult, ContestTypeSpecifier,
 };
@@ -1,19 +1,160 @@
-use crate::util;
+use crate::{util, AtCoderClientError, RateLimiter};
 use anyhow::Result;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use std::future::Future;
+use std::time::Duration;
 
 use super::*;
 
-const ATCODER_PREFIX: &str = "https://atcoder.jp";
+const DEFAULT_BASE_URL: &str = "https://atcoder.jp";
+const DEFAULT_HIDDEN_CONTESTS_URL: &str =
+    "https://kenkoooo.com/atcoder/static_data/backend/hidden_contests.json";
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 5.0;
 
-pub struct AtCoderClient;
+#[derive(Clone)]
+pub struct AtCoderClient {
+    base_url: String,
+    hidden_contests_url: String,
+    client: reqwest::Client,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    rate_limiter: RateLimiter,
+}
 
 impl Default for AtCoderClient {
     fn default() -> Self {
-        Self
+        Self::builder()
+            .build()
+            .expect("Failed to build the default HTTP client")
+    }
+}
+
+#[derive(Default)]
+pub struct AtCoderClientBuilder {
+    base_url: Option<String>,
+    hidden_contests_url: Option<String>,
+    client: Option<reqwest::Client>,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl AtCoderClientBuilder {
+    /// The origin of AtCoder, `https://atcoder.jp` by default.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn hidden_contests_url(mut self, url: &str) -> Self {
+        self.hidden_contests_url = Some(url.to_string());
+        self
+    }
+
+    /// Shares the connection pool of `client`. `connect_timeout` is ignored then.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn user_agent(self, user_agent: &str) -> Result<Self> {
+        self.header(USER_AGENT.as_str(), user_agent)
+    }
+
+    /// Adds a header sent with every request.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self> {
+        self.headers.insert(
+            HeaderName::from_bytes(name.as_bytes())?,
+            HeaderValue::from_str(value)?,
+        );
+        Ok(self)
+    }
+
+    /// The timeout of a whole request, from connecting until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits the requests to AtCoder, `DEFAULT_REQUESTS_PER_SECOND` by default.
+    pub fn requests_per_second(mut self, requests_per_second: f64) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(requests_per_second));
+        self
+    }
+
+    /// Shares `rate_limiter` with other clients so that their total rate is limited.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn build(self) -> Result<AtCoderClient> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder().gzip(true);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+        Ok(AtCoderClient {
+            base_url: self
+                .base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            hidden_contests_url: self
+                .hidden_contests_url
+                .unwrap_or_else(|| DEFAULT_HIDDEN_CONTESTS_URL.to_string()),
+            client,
+            headers: self.headers,
+            timeout: self.timeout,
+            rate_limiter: self
+                .rate_limiter
+                .unwrap_or_else(|| RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND)),
+        })
     }
 }
 
 impl AtCoderClient {
+    pub fn builder() -> AtCoderClientBuilder {
+        AtCoderClientBuilder::default()
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url).headers(self.headers.clone());
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    async fn get_html(&self, url: &str) -> Result<String> {
+        self.limited(util::get_html(self.get(url))).await
+    }
+
+    /// Sends a request to AtCoder under the rate limit, and pauses the limiter if throttled.
+    async fn limited<T>(&self, request: impl Future<Output = Result<T>>) -> Result<T> {
+        self.rate_limiter.acquire().await;
+        let result = request.await;
+        if let Err(e) = &result {
+            if let Some(AtCoderClientError::Throttled {
+                retry_after: Some(retry_after),
+                ..
+            }) = e.downcast_ref::<AtCoderClientError>()
+            {
+                self.rate_limiter.pause(*retry_after).await;
+            }
+        }
+        result
+    }
+
     pub async fn fetch_atcoder_contests(
         &self,
         spf: ContestTypeSpecifier,
@@ -26,23 +167,24 @@ impl AtCoderClient {
     }
 
     async fn fetch_atcoder_normal_contests(&self, page: u32) -> Result<Vec<AtCoderContest>> {
-        let url = format!("{}/contests/archive?lang=ja&page={}", ATCODER_PREFIX, page);
-        let (html, _) = util::get_html(&url).await?;
+        let url = format!("{}/contests/archive?lang=ja&page={}", self.base_url, page);
+        let html = self.get_html(&url).await?;
         contest::scrape_normal(&html)
     }
 
     async fn fetch_atcoder_permanent_contests(&self) -> Result<Vec<AtCoderContest>> {
-        let url = format!("{}/contests/?lang=ja", ATCODER_PREFIX);
-        let (html, _) = util::get_html(&url).await?;
+        let url = format!("{}/contests/?lang=ja", self.base_url);
+        let html = self.get_html(&url).await?;
         contest::scrape_permanent(&html)
     }
 
     async fn fetch_atcoder_hidden_contests(&self) -> Result<Vec<AtCoderContest>> {
-        let uri = "https://kenkoooo.com/atcoder/static_data/backend/hidden_contests.json";
-        util::get_json(uri).await
+        util::get_json(self.get(&self.hidden_contests_url)).await
     }
 
     /// Fetch a list of submissions.
+    ///
+    /// A page beyond the last one results in `AtCoderClientError::NotFound`.
     pub async fn fetch_atcoder_submission_list(
         &self,
         contest_id: &str,
@@ -51,89 +193,343 @@ impl AtCoderClient {
         let page = page.unwrap_or(1);
         let url = format!(
             "{}/contests/{}/submissions?page={}",
-            ATCODER_PREFIX, contest_id, page
+            self.base_url, contest_id, page
         );
-        let (html, status) = util::get_html(&url).await?;
-
-        if status.is_success() {
-            let submissions = submission::scrape(&html, contest_id)?;
-            let max_page = submission::scrape_submission_page_count(&html)?;
-            Ok(AtCoderSubmissionListResponse {
-                max_page,
-                submissions,
-            })
-        } else if status == reqwest::StatusCode::NOT_FOUND {
-            log::warn!("404: {}", url);
-            Ok(AtCoderSubmissionListResponse {
-                max_page: 0,
-                submissions: Vec::new(),
-            })
-        } else {
-            Err(anyhow::anyhow!(
-                "Failed to fetch {}: status={} body={}",
-                url,
-                status,
-                html
-            ))
-        }
+        let html = self.get_html(&url).await?;
+        let submissions = submission::scrape(&html, contest_id)?;
+        let max_page = submission::scrape_submission_page_count(&html)?;
+        Ok(AtCoderSubmissionListResponse {
+            max_page,
+            submissions,
+        })
     }
 
     pub async fn fetch_problem_list(&self, contest_id: &str) -> Result<Vec<AtCoderProblem>> {
-        let url = format!("{}/contests/{}/tasks", ATCODER_PREFIX, contest_id);
-        let (html, _) = util::get_html(&url).await?;
+        let url = format!("{}/contests/{}/tasks", self.base_url, contest_id);
+        let html = self.get_html(&url).await?;
         problem::scrape(&html, contest_id)
     }
+
+    /// Fetches the score and the limits from the page of a problem.
+    pub async fn fetch_problem_detail(
+        &self,
+        contest_id: &str,
+        problem_id: &str,
+    ) -> Result<AtCoderProblemDetail> {
+        let url = format!(
+            "{}/contests/{}/tasks/{}",
+            self.base_url, contest_id, problem_id
+        );
+        let html = self.get_html(&url).await?;
+        problem::scrape_detail(&html)
+    }
+
+    pub async fn fetch_contest_standings(&self, contest_id: &str) -> Result<AtCoderStandings> {
+        let url = format!("{}/contests/{}/standings/json", self.base_url, contest_id);
+        self.limited(util::get_json(self.get(&url))).await
+    }
+
+    /// Fetches the results of the rated contests which the user has participated in.
+    pub async fn fetch_user_rating_history(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AtCoderContestResult>> {
+        let url = format!("{}/users/{}/history/json", self.base_url, user_id);
+        self.limited(util::get_json(self.get(&url))).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::AtCoderClientError;
+    use httpmock::prelude::*;
+    use std::fs;
+
+    fn fixture(name: &str) -> String {
+        fs::read_to_string(format!("test_resources/{}", name)).unwrap()
+    }
+
+    fn client(server: &MockServer) -> AtCoderClient {
+        AtCoderClient::builder()
+            .base_url(&server.base_url())
+            .hidden_contests_url(&server.url("/hidden_contests.json"))
+            .user_agent("atcoder-problems-test")
+            .unwrap()
+            .header("x-test-header", "value")
+            .unwrap()
+            .timeout(Duration::from_secs(10))
+            .requests_per_second(1000.0)
+            .build()
+            .unwrap()
+    }
 
-    #[test]
-    fn test_fetch_contest_list() {
-        let client = AtCoderClient::default();
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let contests = rt
-            .block_on(client.fetch_atcoder_contests(ContestTypeSpecifier::Normal { page: 1 }))
+    #[tokio::test]
+    async fn test_fetch_contest_list() {
+        let server = MockServer::start_async().await;
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/contests/archive")
+                    .query_param("page", "1")
+                    .header("user-agent", "atcoder-problems-test")
+                    .header("x-test-header", "value");
+                then.status(200).body(fixture("contests_normal"));
+            })
+            .await;
+        let contests = client(&server)
+            .fetch_atcoder_contests(ContestTypeSpecifier::Normal { page: 1 })
+            .await
             .unwrap();
+        mock.assert_async().await;
         assert_eq!(contests.len(), 50);
+
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/");
+                then.status(200).body(fixture("contests_permanent"));
+            })
+            .await;
+        let contests = client(&server)
+            .fetch_atcoder_contests(ContestTypeSpecifier::Permanent)
+            .await
+            .unwrap();
+        assert_eq!(contests.len(), 4);
     }
 
-    #[test]
-    fn test_fetch_hidden_contest() {
-        let client = AtCoderClient::default();
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let contests = rt
-            .block_on(client.fetch_atcoder_contests(ContestTypeSpecifier::Hidden))
+    #[tokio::test]
+    async fn test_fetch_hidden_contest() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/hidden_contests.json");
+                then.status(200).body(
+                    r#"[{"id":"hidden","start_epoch_second":0,"duration_second":100,"title":"Hidden","rate_change":"-"}]"#,
+                );
+            })
+            .await;
+        let contests = client(&server)
+            .fetch_atcoder_contests(ContestTypeSpecifier::Hidden)
+            .await
             .unwrap();
-        assert!(!contests.is_empty());
+        assert_eq!(contests.len(), 1);
+        assert_eq!(contests[0].id, "hidden");
     }
 
-    #[test]
-    fn test_fetch_problem_list() {
-        let client = AtCoderClient::default();
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let problems = rt.block_on(client.fetch_problem_list("abc107")).unwrap();
+    #[tokio::test]
+    async fn test_fetch_problem_list() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/abc107/tasks");
+                then.status(200).body(fixture("abc107_tasks"));
+            })
+            .await;
+        let problems = client(&server).fetch_problem_list("abc107").await.unwrap();
         assert_eq!(problems.len(), 4);
     }
 
-    #[test]
-    fn test_fetch_submission_list() {
-        let client = AtCoderClient::default();
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let response = rt
-            .block_on(client.fetch_atcoder_submission_list("xmascon17", None))
+    #[tokio::test]
+    async fn test_fetch_problem_detail() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/abc107/tasks/abc107_a");
+                then.status(200).body(fixture("abc107_a_task"));
+            })
+            .await;
+        let detail = client(&server)
+            .fetch_problem_detail("abc107", "abc107_a")
+            .await
+            .unwrap();
+        assert_eq!(detail.score, Some(100.0));
+        assert_eq!(detail.time_limit_ms, Some(2000));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_submission_list() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/contests/abc107/submissions")
+                    .query_param("page", "1");
+                then.status(200).body(fixture("abc107_submissions"));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/contests/abc107/submissions")
+                    .query_param("page", "2209");
+                then.status(404).body(fixture("not_found"));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/contests/abc107/submissions")
+                    .query_param("page", "2210");
+                then.status(500);
+            })
+            .await;
+
+        let client = client(&server);
+        let response = client
+            .fetch_atcoder_submission_list("abc107", None)
+            .await
             .unwrap();
         assert_eq!(response.submissions.len(), 20);
+        assert_eq!(response.max_page, 2208);
+
+        let response = client
+            .fetch_atcoder_submission_list("abc107", Some(2209))
+            .await;
+        assert!(matches!(
+            response.err().unwrap().downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::NotFound { .. })
+        ));
+
+        let response = client
+            .fetch_atcoder_submission_list("abc107", Some(2210))
+            .await;
+        assert!(matches!(
+            response.err().unwrap().downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::UnexpectedStatus { .. })
+        ));
+    }
 
-        let response = rt
-            .block_on(client.fetch_atcoder_submission_list("xmascon17", Some(response.max_page)))
+    #[tokio::test]
+    async fn test_fetch_errors() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/throttled/tasks");
+                then.status(429).header("retry-after", "1");
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/changed/tasks");
+                then.status(200).body("<html><body></body></html>");
+            })
+            .await;
+        let client = client(&server);
+
+        let error = client.fetch_problem_list("changed").await.unwrap_err();
+        match error.downcast_ref::<AtCoderClientError>() {
+            Some(AtCoderClientError::LayoutChanged { selector, .. }) => {
+                assert_eq!(*selector, "tbody");
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        let unreachable = AtCoderClient::builder()
+            .base_url("http://127.0.0.1:1")
+            .build()
             .unwrap();
-        assert!(!response.submissions.is_empty());
+        let error = unreachable.fetch_problem_list("abc107").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::Network(_))
+        ));
+
+        let error = client.fetch_problem_list("throttled").await.unwrap_err();
+        match error.downcast_ref::<AtCoderClientError>() {
+            Some(AtCoderClientError::Throttled {
+                status,
+                retry_after,
+                ..
+            }) => {
+                assert_eq!(status.as_u16(), 429);
+                assert_eq!(*retry_after, Some(Duration::from_secs(1)));
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
 
-        let response = rt.block_on(
-            client.fetch_atcoder_submission_list("xmascon17", Some(response.max_page + 1)),
+        let now = std::time::Instant::now();
+        client.fetch_problem_list("changed").await.unwrap_err();
+        assert!(
+            now.elapsed() >= Duration::from_millis(900),
+            "The limiter should wait for Retry-After."
         );
-        assert!(response.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_contest_standings() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/abc107/standings/json");
+                then.status(200).body(fixture("abc107_standings.json"));
+            })
+            .await;
+        let standings = client(&server)
+            .fetch_contest_standings("abc107")
+            .await
+            .unwrap();
+        assert!(standings.fixed);
+        assert_eq!(
+            standings
+                .task_info
+                .iter()
+                .map(|task| task.task_screen_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["abc107_a", "abc107_b"]
+        );
+        assert_eq!(standings.standings_data.len(), 3);
+
+        let row = &standings.standings_data[1];
+        assert_eq!(row.rank, 2);
+        assert_eq!(row.user_screen_name, "kenkoooo");
+        assert!(row.is_rated);
+        assert_eq!(row.old_rating, 1850);
+        assert_eq!(row.total_result.score, 10000);
+        assert_eq!(row.total_result.penalty, 1);
+        assert_eq!(row.total_result.elapsed, 360_000_000_000);
+        assert_eq!(row.task_results["abc107_a"].submission_id, Some(3046050));
+        assert!(standings.standings_data[2].task_results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_user_rating_history() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/users/kenkoooo/history/json");
+                then.status(200).body(fixture("user_history.json"));
+            })
+            .await;
+        let history = client(&server)
+            .fetch_user_rating_history("kenkoooo")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].contest_id(), "abc107");
+        assert_eq!(history[0].end_epoch_second(), Some(1535204400));
+        assert_eq!(history[0].place, 1024);
+        assert_eq!(history[0].new_rating, 345);
+        assert_eq!(history[0].performance, 1234);
+        assert!(!history[1].is_rated);
+        assert_eq!(history[1].contest_id(), "arc102");
+    }
+
+    #[tokio::test]
+    async fn test_shared_client() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/contests/abc107/tasks");
+                then.status(200).body(fixture("abc107_tasks"));
+            })
+            .await;
+        let shared = reqwest::Client::new();
+        let client = AtCoderClient::builder()
+            .base_url(&format!("{}/", server.base_url()))
+            .client(shared)
+            .build()
+            .unwrap();
+        let problems = client.fetch_problem_list("abc107").await.unwrap();
+        assert_eq!(problems.len(), 4);
     }
 }
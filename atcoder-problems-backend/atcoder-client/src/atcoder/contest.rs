@@ -1,67 +1,51 @@
-use anyhow::{anyhow, Result};
-
 use super::AtCoderContest;
+use crate::AtCoderClientError;
 
+use anyhow::Result;
 use chrono::DateTime;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
 const PERMANENT_CONTEST_DURATION_SECOND: u64 = 100 * 365 * 24 * 3600;
 
 pub(super) fn scrape_normal(html: &str) -> Result<Vec<AtCoderContest>> {
+    let err = AtCoderClientError::layout_changed;
     Html::parse_document(html)
         .select(&Selector::parse("tbody").unwrap())
         .next()
-        .ok_or_else(|| anyhow!("Failed to parse html."))?
+        .ok_or_else(|| err("contest table", "tbody"))?
         .select(&Selector::parse("tr").unwrap())
         .map(|tr| {
             let selector = Selector::parse("td").unwrap();
             let mut tds = tr.select(&selector);
             let start = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let start = DateTime::parse_from_str(start, "%Y-%m-%d %H:%M:%S%z")?;
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("contest start time", "td"))?;
+            let start = DateTime::parse_from_str(start, "%Y-%m-%d %H:%M:%S%z")
+                .map_err(|_| err("contest start time", "td"))?;
             let start = start.timestamp() as u64;
 
-            let contest = tds.next().ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_title = contest
-                .select(&Selector::parse("a").unwrap())
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_link = contest
-                .select(&Selector::parse("a").unwrap())
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_id = contest_link
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
+            let contest = tds.next().ok_or_else(|| err("contest", "td"))?;
+            let (contest_id, contest_title) = scrape_contest_link(contest)?;
 
-            let duration = tds.next().unwrap().text().next().unwrap();
+            let duration = tds
+                .next()
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("contest duration", "td"))?;
             let mut duration = duration.split(':');
             let hours = duration
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .parse::<u64>()?;
+                .and_then(|hours| hours.parse::<u64>().ok())
+                .ok_or_else(|| err("contest duration", "td"))?;
             let minutes = duration
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .parse::<u64>()?;
+                .and_then(|minutes| minutes.parse::<u64>().ok())
+                .ok_or_else(|| err("contest duration", "td"))?;
             let duration = hours * 3600 + minutes * 60;
             let rated = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("contest rate change", "td"))?;
             Ok(AtCoderContest {
                 id: contest_id.to_owned(),
                 start_epoch_second: start,
@@ -74,44 +58,26 @@ pub(super) fn scrape_normal(html: &str) -> Result<Vec<AtCoderContest>> {
 }
 
 pub(super) fn scrape_permanent(html: &str) -> Result<Vec<AtCoderContest>> {
+    let err = AtCoderClientError::layout_changed;
     Html::parse_document(html)
         .select(&Selector::parse("#contest-table-permanent").unwrap())
         .next()
-        .ok_or_else(|| anyhow!("Failed to parse html."))?
+        .ok_or_else(|| err("permanent contest table", "#contest-table-permanent"))?
         .select(&Selector::parse("tbody").unwrap())
         .next()
-        .ok_or_else(|| anyhow!("Failed to parse html."))?
+        .ok_or_else(|| err("permanent contest table", "#contest-table-permanent tbody"))?
         .select(&Selector::parse("tr").unwrap())
         .map(|tr| {
             let selector = Selector::parse("td").unwrap();
             let mut tds = tr.select(&selector);
 
-            let contest = tds.next().ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_title = contest
-                .select(&Selector::parse("a").unwrap())
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_link = contest
-                .select(&Selector::parse("a").unwrap())
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let contest_id = contest_link
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
+            let contest = tds.next().ok_or_else(|| err("contest", "td"))?;
+            let (contest_id, contest_title) = scrape_contest_link(contest)?;
 
             let rated = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("contest rate change", "td"))?;
             Ok(AtCoderContest {
                 id: contest_id.to_owned(),
                 start_epoch_second: 0,
@@ -123,9 +89,29 @@ pub(super) fn scrape_permanent(html: &str) -> Result<Vec<AtCoderContest>> {
         .collect()
 }
 
+/// Returns the contest id and the title from the link in the cell.
+fn scrape_contest_link(td: ElementRef<'_>) -> Result<(&str, &str), AtCoderClientError> {
+    let err = AtCoderClientError::layout_changed;
+    let link = td
+        .select(&Selector::parse("a").unwrap())
+        .next()
+        .ok_or_else(|| err("contest link", "td a"))?;
+    let title = link
+        .text()
+        .next()
+        .ok_or_else(|| err("contest title", "td a"))?;
+    let id = link
+        .value()
+        .attr("href")
+        .and_then(|href| href.rsplit('/').next())
+        .ok_or_else(|| err("contest id", "td a[href]"))?;
+    Ok((id, title))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::atcoder::fixtures;
     use std::fs::File;
     use std::io::Read;
 
@@ -148,4 +134,29 @@ mod tests {
         let contests = scrape_permanent(&contents).unwrap();
         assert_eq!(contests.len(), 4);
     }
+
+    #[test]
+    fn test_scrape_golden() {
+        let contests = scrape_normal(&fixtures::read("contests_normal")).unwrap();
+        fixtures::assert_golden("contests_normal", &contests);
+        let contests = scrape_permanent(&fixtures::read("contests_permanent")).unwrap();
+        fixtures::assert_golden("contests_permanent", &contests);
+    }
+
+    #[test]
+    fn test_scrape_not_found() {
+        let html = fixtures::read("not_found");
+        for result in [scrape_normal(&html), scrape_permanent(&html)] {
+            assert!(matches!(
+                result.unwrap_err().downcast_ref::<AtCoderClientError>(),
+                Some(AtCoderClientError::LayoutChanged { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_scrape_broken_pages() {
+        fixtures::assert_no_panic(&fixtures::read("contests_normal"), scrape_normal);
+        fixtures::assert_no_panic(&fixtures::read("contests_permanent"), scrape_permanent);
+    }
 }
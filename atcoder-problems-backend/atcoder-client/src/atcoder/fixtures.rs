@@ -0,0 +1,60 @@
+//! Saved pages of AtCoder and the expected outputs of the parsers.
+//!
+//! Run the tests with `UPDATE_GOLDEN=1` to rewrite the golden files after changing a parser.
+
+use serde::Serialize;
+use std::fs;
+use std::panic;
+
+pub(super) fn read(name: &str) -> String {
+    fs::read_to_string(format!("test_resources/{}", name)).unwrap()
+}
+
+/// Compares `actual` with `test_resources/golden/<name>.json`.
+pub(super) fn assert_golden<T: Serialize>(name: &str, actual: &T) {
+    let path = format!("test_resources/golden/{}.json", name);
+    let actual = serde_json::to_value(actual).unwrap();
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+    let expected: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(actual, expected, "The output differs from {}", path);
+}
+
+/// Runs `parse` on broken variants of `html`, and checks that it never panics.
+///
+/// The variants are the prefixes of the page, and the page with one of its cells removed.
+pub(super) fn assert_no_panic<T>(html: &str, parse: impl Fn(&str) -> T + panic::RefUnwindSafe) {
+    const PREFIX_NUM: usize = 50;
+    const REMOVED_CELL_NUM: usize = 30;
+
+    let mut variants = (0..PREFIX_NUM)
+        .map(|i| {
+            let mut end = html.len() * i / PREFIX_NUM;
+            while !html.is_char_boundary(end) {
+                end -= 1;
+            }
+            html[..end].to_string()
+        })
+        .collect::<Vec<_>>();
+    let cell_starts = html
+        .match_indices("<td")
+        .map(|(i, _)| i)
+        .take(REMOVED_CELL_NUM);
+    for start in cell_starts {
+        let end = match html[start..].find("</td>") {
+            Some(end) => start + end + "</td>".len(),
+            None => continue,
+        };
+        variants.push(format!("{}{}", &html[..start], &html[end..]));
+    }
+
+    for variant in variants {
+        let result = panic::catch_unwind(|| {
+            parse(&variant);
+        });
+        assert!(result.is_ok(), "Panicked while parsing:\n{}", variant);
+    }
+}
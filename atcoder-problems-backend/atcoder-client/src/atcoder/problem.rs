@@ -1,55 +1,127 @@
-use anyhow::{anyhow, Result};
-
-use super::AtCoderProblem;
+use super::{AtCoderProblem, AtCoderProblemDetail};
+use crate::AtCoderClientError;
 
+use anyhow::Result;
 use scraper::{Html, Selector};
 
 pub(super) fn scrape(html: &str, contest_id: &str) -> Result<Vec<AtCoderProblem>> {
+    let err = AtCoderClientError::layout_changed;
     Html::parse_document(html)
         .select(&Selector::parse("tbody").unwrap())
         .next()
-        .ok_or_else(|| anyhow!("Failed to parse html."))?
+        .ok_or_else(|| err("problem table", "tbody"))?
         .select(&Selector::parse("tr").unwrap())
         .map(|tr| {
             let selector = Selector::parse("td").unwrap();
             let mut tds = tr.select(&selector);
             let position = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("problem position", "td"))?
                 .to_owned();
-            let problem = tds.next().ok_or_else(|| anyhow!("Failed to parse html."))?;
+            let problem = tds.next().ok_or_else(|| err("problem", "td"))?;
             let id = problem
                 .select(&Selector::parse("a").unwrap())
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|href| href.rsplit('/').next())
+                .ok_or_else(|| err("problem id", "td a[href]"))?
                 .to_owned();
             let title = problem
                 .text()
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .ok_or_else(|| err("problem title", "td"))?
                 .to_owned();
+            let time_limit_ms = tds
+                .next()
+                .and_then(|td| parse_time_limit(&td.text().collect::<String>()));
+            let memory_limit_kb = tds
+                .next()
+                .and_then(|td| parse_memory_limit(&td.text().collect::<String>()));
             Ok(AtCoderProblem {
                 id,
                 contest_id: contest_id.to_owned(),
                 title,
                 position,
+                time_limit_ms,
+                memory_limit_kb,
             })
         })
         .collect()
 }
 
+/// Scrapes the score and the limits from the page of a problem.
+pub(super) fn scrape_detail(html: &str) -> Result<AtCoderProblemDetail> {
+    let err = AtCoderClientError::layout_changed;
+    let document = Html::parse_document(html);
+    let statement = document
+        .select(&Selector::parse("#task-statement").unwrap())
+        .next()
+        .ok_or_else(|| err("task statement", "#task-statement"))?;
+
+    let score = statement
+        .select(&Selector::parse("p").unwrap())
+        .find(|p| {
+            let text = p.text().collect::<String>();
+            let text = text.trim_start();
+            text.starts_with("Score") || text.starts_with("配点")
+        })
+        .and_then(|p| p.select(&Selector::parse("var").unwrap()).next())
+        .and_then(|var| var.text().collect::<String>().trim().parse::<f64>().ok());
+
+    // e.g. "Time Limit: 2 sec / Memory Limit: 1024 MB"
+    let limits = document
+        .select(&Selector::parse("p").unwrap())
+        .map(|p| p.text().collect::<String>())
+        .find(|text| text.contains("Time Limit") || text.contains("実行時間制限"));
+    let mut limits = limits
+        .iter()
+        .flat_map(|text| text.split('/'))
+        .map(|limit| limit.split_once(':').map_or("", |(_, value)| value));
+    let time_limit_ms = limits.next().and_then(parse_time_limit);
+    let memory_limit_kb = limits.next().and_then(parse_memory_limit);
+
+    Ok(AtCoderProblemDetail {
+        score,
+        time_limit_ms,
+        memory_limit_kb,
+    })
+}
+
+/// Parses a time limit such as `2 sec` or `500 msec` into milliseconds.
+fn parse_time_limit(text: &str) -> Option<u32> {
+    let (value, unit) = split_value_and_unit(text)?;
+    let millis = match unit {
+        "sec" | "s" => value * 1000.0,
+        "msec" | "ms" => value,
+        _ => return None,
+    };
+    Some(millis.round() as u32)
+}
+
+/// Parses a memory limit such as `1024 MB` into kilobytes.
+fn parse_memory_limit(text: &str) -> Option<u32> {
+    let (value, unit) = split_value_and_unit(text)?;
+    let kilobytes = match unit {
+        "KB" | "KiB" => value,
+        "MB" | "MiB" => value * 1024.0,
+        "GB" | "GiB" => value * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(kilobytes.round() as u32)
+}
+
+fn split_value_and_unit(text: &str) -> Option<(f64, &str)> {
+    let mut words = text.split_whitespace();
+    let value = words.next()?.parse::<f64>().ok()?;
+    let unit = words.next()?;
+    Some((value, unit))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::atcoder::fixtures;
     use std::fs::File;
     use std::io::prelude::*;
 
@@ -66,27 +138,114 @@ mod tests {
                     id: "abc107_a".to_owned(),
                     contest_id: "abc107".to_owned(),
                     title: "Train".to_owned(),
-                    position: "A".to_owned()
+                    position: "A".to_owned(),
+                    time_limit_ms: Some(2000),
+                    memory_limit_kb: Some(1048576),
                 },
                 AtCoderProblem {
                     id: "abc107_b".to_owned(),
                     contest_id: "abc107".to_owned(),
                     title: "Grid Compression".to_owned(),
-                    position: "B".to_owned()
+                    position: "B".to_owned(),
+                    time_limit_ms: Some(2000),
+                    memory_limit_kb: Some(1048576),
                 },
                 AtCoderProblem {
                     id: "arc101_a".to_owned(),
                     contest_id: "abc107".to_owned(),
                     title: "Candles".to_owned(),
-                    position: "C".to_owned()
+                    position: "C".to_owned(),
+                    time_limit_ms: Some(2000),
+                    memory_limit_kb: Some(1048576),
                 },
                 AtCoderProblem {
                     id: "arc101_b".to_owned(),
                     contest_id: "abc107".to_owned(),
                     title: "Median of Medians".to_owned(),
-                    position: "D".to_owned()
+                    position: "D".to_owned(),
+                    time_limit_ms: Some(2000),
+                    memory_limit_kb: Some(1048576),
                 }
             ]
         );
     }
+
+    #[test]
+    fn test_scrape_golden() {
+        let problems = scrape(&fixtures::read("abc107_tasks"), "abc107").unwrap();
+        fixtures::assert_golden("abc107_tasks", &problems);
+    }
+
+    #[test]
+    fn test_scrape_without_limits() {
+        let html = r"<table><tbody><tr>
+            <td><a href='/contests/practice/tasks/practice_1'>A</a></td>
+            <td><a href='/contests/practice/tasks/practice_1'>Welcome to AtCoder</a></td>
+        </tr></tbody></table>";
+        let problems = scrape(html, "practice").unwrap();
+        assert_eq!(problems[0].id, "practice_1");
+        assert_eq!(problems[0].time_limit_ms, None);
+        assert_eq!(problems[0].memory_limit_kb, None);
+    }
+
+    #[test]
+    fn test_scrape_detail() {
+        let detail = scrape_detail(&fixtures::read("abc107_a_task")).unwrap();
+        assert_eq!(
+            detail,
+            AtCoderProblemDetail {
+                score: Some(100.0),
+                time_limit_ms: Some(2000),
+                memory_limit_kb: Some(1048576),
+            }
+        );
+
+        let detail = scrape_detail(
+            r#"<p>実行時間制限: 5.25 sec / メモリ制限: 64 MB</p>
+            <div id="task-statement"><p>問題文</p></div>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            detail,
+            AtCoderProblemDetail {
+                score: None,
+                time_limit_ms: Some(5250),
+                memory_limit_kb: Some(65536),
+            }
+        );
+
+        let result = scrape_detail(&fixtures::read("not_found"));
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::LayoutChanged { .. })
+        ));
+        fixtures::assert_no_panic(&fixtures::read("abc107_a_task"), scrape_detail);
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        assert_eq!(parse_time_limit(" 2 sec "), Some(2000));
+        assert_eq!(parse_time_limit("500 msec"), Some(500));
+        assert_eq!(parse_time_limit("2"), None);
+        assert_eq!(parse_time_limit("two sec"), None);
+        assert_eq!(parse_memory_limit("256 MB"), Some(262144));
+        assert_eq!(parse_memory_limit("1 GB"), Some(1048576));
+        assert_eq!(parse_memory_limit("1024 bytes"), None);
+    }
+
+    #[test]
+    fn test_scrape_not_found() {
+        let result = scrape(&fixtures::read("not_found"), "abc107");
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::LayoutChanged { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scrape_broken_pages() {
+        fixtures::assert_no_panic(&fixtures::read("abc107_tasks"), |html| {
+            scrape(html, "abc107")
+        });
+    }
 }
@@ -1,22 +1,25 @@
 use super::AtCoderSubmission;
+use crate::AtCoderClientError;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 
 use chrono::DateTime;
 use regex::Regex;
 use scraper::{Html, Selector};
 
+/// Returns the last page in the pagination, which is not shown if there is only one page.
 pub(super) fn scrape_submission_page_count(html: &str) -> Result<u32> {
     let selector = Selector::parse("a").unwrap();
     let re = Regex::new(r"page=\d+$").unwrap();
-    Html::parse_document(html)
+    let max_page = Html::parse_document(html)
         .select(&selector)
         .flat_map(|el| el.value().attr("href"))
         .filter(|href| re.is_match(href))
         .flat_map(|href| href.rsplit('=').next())
         .flat_map(str::parse)
         .max()
-        .ok_or_else(|| anyhow!("Failed to parse html."))
+        .unwrap_or(1);
+    Ok(max_page)
 }
 
 pub(super) fn scrape(html_text: &str, contest_id: &str) -> Result<Vec<AtCoderSubmission>> {
@@ -25,50 +28,38 @@ pub(super) fn scrape(html_text: &str, contest_id: &str) -> Result<Vec<AtCoderSub
     let td_selector = Selector::parse("td").unwrap();
     let a_selector = Selector::parse("a").unwrap();
     let re = Regex::new(r"submissions/\d+$").unwrap();
+    let err = AtCoderClientError::layout_changed;
 
     Html::parse_document(html_text)
         .select(&tbody_selector)
         .next()
-        .ok_or_else(|| anyhow!("Failed to parse html."))?
+        .ok_or_else(|| err("submission table", "tbody"))?
         .select(&tr_selector)
         .map(|tr| {
             let mut tds = tr.select(&td_selector);
 
             let time = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?;
-            let time = DateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%z")?;
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("submission time", "td"))?;
+            let time = DateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%z")
+                .map_err(|_| err("submission time", "td"))?;
             let epoch_second = time.timestamp() as u64;
 
             let problem_id = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .select(&a_selector)
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .and_then(|td| td.select(&a_selector).next())
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|href| href.rsplit('/').next())
+                .ok_or_else(|| err("problem id", "td a[href]"))?
                 .to_owned();
 
             let user_id = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .select(&a_selector)
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .and_then(|td| td.select(&a_selector).next())
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|href| href.rsplit('/').next())
+                .ok_or_else(|| err("user id", "td a[href]"))?
                 .to_owned();
 
             let language = tds
@@ -79,28 +70,20 @@ pub(super) fn scrape(html_text: &str, contest_id: &str) -> Result<Vec<AtCoderSub
 
             let point: f64 = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .parse()?;
+                .and_then(|td| td.text().next())
+                .and_then(|point| point.parse().ok())
+                .ok_or_else(|| err("point", "td"))?;
 
             let length = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .replace("Byte", "")
-                .trim()
-                .parse::<u64>()?;
+                .and_then(|td| td.text().next())
+                .and_then(|length| length.replace("Byte", "").trim().parse::<u64>().ok())
+                .ok_or_else(|| err("code length", "td"))?;
 
             let result = tds
                 .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .text()
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
+                .and_then(|td| td.text().next())
+                .ok_or_else(|| err("result", "td"))?
                 .to_owned();
 
             let execution_time = tds
@@ -115,15 +98,10 @@ pub(super) fn scrape(html_text: &str, contest_id: &str) -> Result<Vec<AtCoderSub
                     Some(href) => re.is_match(href),
                     None => false,
                 })
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .value()
-                .attr("href")
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .rsplit('/')
-                .next()
-                .ok_or_else(|| anyhow!("Failed to parse html."))?
-                .trim()
-                .parse::<u64>()?;
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|href| href.rsplit('/').next())
+                .and_then(|id| id.trim().parse::<u64>().ok())
+                .ok_or_else(|| err("submission id", "a[href$=submissions/N]"))?;
             Ok(AtCoderSubmission {
                 id,
                 epoch_second,
@@ -143,6 +121,7 @@ pub(super) fn scrape(html_text: &str, contest_id: &str) -> Result<Vec<AtCoderSub
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::atcoder::fixtures;
     use std::fs::File;
     use std::io::prelude::*;
 
@@ -158,4 +137,56 @@ mod tests {
         let max_page = scrape_submission_page_count(&contents).unwrap();
         assert_eq!(max_page, 2208);
     }
+
+    fn scrape_page(html: &str, contest_id: &str) -> Result<serde_json::Value> {
+        let submissions = scrape(html, contest_id)?;
+        let max_page = scrape_submission_page_count(html)?;
+        Ok(serde_json::json!({
+            "max_page": max_page,
+            "submissions": submissions,
+        }))
+    }
+
+    #[test]
+    fn test_scrape_golden() {
+        let page = scrape_page(&fixtures::read("abc107_submissions"), "abc107").unwrap();
+        fixtures::assert_golden("abc107_submissions", &page);
+        let page = scrape_page(
+            &fixtures::read("practice_submissions_single_page"),
+            "practice",
+        )
+        .unwrap();
+        fixtures::assert_golden("practice_submissions_single_page", &page);
+    }
+
+    #[test]
+    fn test_scrape_judging() {
+        let html = fixtures::read("practice_submissions_single_page");
+        let submissions = scrape(&html, "practice").unwrap();
+        let results = submissions
+            .iter()
+            .map(|s| (s.result.as_str(), s.execution_time))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![("WJ", None), ("3/10", None), ("AC", Some(7))]);
+        assert_eq!(scrape_submission_page_count(&html).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_scrape_not_found() {
+        let result = scrape(&fixtures::read("not_found"), "abc107");
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<AtCoderClientError>(),
+            Some(AtCoderClientError::LayoutChanged { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scrape_broken_pages() {
+        for name in ["abc107_submissions", "practice_submissions_single_page"] {
+            fixtures::assert_no_panic(&fixtures::read(name), |html| {
+                let _ = scrape(html, "abc107");
+                scrape_submission_page_count(html)
+            });
+        }
+    }
 }
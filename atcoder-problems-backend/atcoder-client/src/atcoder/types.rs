@@ -1,5 +1,7 @@
 use crate::util::Problem;
+use chrono::DateTime;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub enum ContestTypeSpecifier {
     Normal { page: u32 },
@@ -21,7 +23,7 @@ pub struct AtCoderContest {
     pub rate_change: String,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AtCoderSubmission {
     pub id: u64,
     pub epoch_second: u64,
@@ -35,12 +37,110 @@ pub struct AtCoderSubmission {
     pub execution_time: Option<u64>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AtCoderProblem {
     pub id: String,
     pub title: String,
     pub position: String,
     pub contest_id: String,
+    pub time_limit_ms: Option<u32>,
+    pub memory_limit_kb: Option<u32>,
+}
+
+/// The metadata shown on the page of a problem.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AtCoderProblemDetail {
+    /// `None` for the old problems which do not state their scores.
+    pub score: Option<f64>,
+    pub time_limit_ms: Option<u32>,
+    pub memory_limit_kb: Option<u32>,
+}
+
+/// The response of `/contests/{contest_id}/standings/json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderStandings {
+    /// False until the final results are fixed.
+    pub fixed: bool,
+    pub task_info: Vec<AtCoderStandingsTask>,
+    pub standings_data: Vec<AtCoderStandingsRow>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderStandingsTask {
+    pub assignment: String,
+    pub task_name: String,
+    pub task_screen_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderStandingsRow {
+    pub rank: u32,
+    pub user_screen_name: String,
+    pub is_rated: bool,
+    /// The rating before the contest.
+    pub old_rating: i32,
+    pub competitions: u32,
+    /// Keyed by the problem id.
+    pub task_results: BTreeMap<String, AtCoderTaskResult>,
+    pub total_result: AtCoderTotalResult,
+}
+
+/// The result of a problem, whose score is multiplied by 100 and elapsed time is in nanoseconds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderTaskResult {
+    pub count: u32,
+    pub penalty: u32,
+    pub score: i64,
+    pub elapsed: i64,
+    pub pending: bool,
+    #[serde(rename = "SubmissionID")]
+    pub submission_id: Option<u64>,
+}
+
+/// The total result, whose score is multiplied by 100 and elapsed time is in nanoseconds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderTotalResult {
+    pub count: u32,
+    pub accepted: u32,
+    pub penalty: u32,
+    pub score: i64,
+    pub elapsed: i64,
+}
+
+/// An entry of `/users/{user_id}/history/json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtCoderContestResult {
+    pub is_rated: bool,
+    pub place: u32,
+    pub old_rating: i32,
+    pub new_rating: i32,
+    pub performance: i32,
+    pub inner_performance: i32,
+    /// The old host name of the contest, e.g. `abc107.contest.atcoder.jp`.
+    pub contest_screen_name: String,
+    pub contest_name: String,
+    pub end_time: String,
+}
+
+impl AtCoderContestResult {
+    pub fn contest_id(&self) -> &str {
+        self.contest_screen_name
+            .split('.')
+            .next()
+            .unwrap_or(&self.contest_screen_name)
+    }
+
+    pub fn end_epoch_second(&self) -> Option<i64> {
+        DateTime::parse_from_rfc3339(&self.end_time)
+            .ok()
+            .map(|time| time.timestamp())
+    }
 }
 
 impl Problem for AtCoderProblem {
@@ -62,6 +162,8 @@ mod tests {
             title: "".to_string(),
             position: "".to_string(),
             contest_id: "arc102".to_string(),
+            time_limit_ms: None,
+            memory_limit_kb: None,
         };
         assert_eq!(
             "https://atcoder.jp/contests/arc102/tasks/arc102_c".to_string(),
@@ -0,0 +1,90 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Failures of fetching or scraping AtCoder pages.
+///
+/// The fetch methods return `anyhow::Result`, and this error can be taken out of it with
+/// `downcast_ref::<AtCoderClientError>()`.
+#[derive(Debug)]
+pub enum AtCoderClientError {
+    /// The page does not exist (404).
+    NotFound { url: String },
+    /// The server is rate limiting or temporarily unavailable (429 or 503).
+    Throttled {
+        url: String,
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+    },
+    /// The server returned any other non-successful status.
+    UnexpectedStatus {
+        url: String,
+        status: reqwest::StatusCode,
+    },
+    /// The page was fetched, but `field` could not be scraped with `selector`.
+    LayoutChanged {
+        field: &'static str,
+        selector: &'static str,
+    },
+    /// The request failed before a response was received.
+    Network(reqwest::Error),
+}
+
+impl AtCoderClientError {
+    pub(crate) fn layout_changed(field: &'static str, selector: &'static str) -> Self {
+        AtCoderClientError::LayoutChanged { field, selector }
+    }
+
+    pub(crate) fn from_status(
+        url: &str,
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        let url = url.to_string();
+        match status {
+            reqwest::StatusCode::NOT_FOUND => AtCoderClientError::NotFound { url },
+            reqwest::StatusCode::TOO_MANY_REQUESTS | reqwest::StatusCode::SERVICE_UNAVAILABLE => {
+                AtCoderClientError::Throttled {
+                    url,
+                    status,
+                    retry_after,
+                }
+            }
+            _ => AtCoderClientError::UnexpectedStatus { url, status },
+        }
+    }
+}
+
+impl fmt::Display for AtCoderClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtCoderClientError::NotFound { url } => write!(f, "{} is not found.", url),
+            AtCoderClientError::Throttled {
+                url,
+                status,
+                retry_after,
+            } => write!(
+                f,
+                "{} is throttled: status={} retry_after={:?}",
+                url, status, retry_after
+            ),
+            AtCoderClientError::UnexpectedStatus { url, status } => {
+                write!(f, "Failed to fetch {}: status={}", url, status)
+            }
+            AtCoderClientError::LayoutChanged { field, selector } => write!(
+                f,
+                "Failed to parse {} with the selector `{}`. The page layout may have changed.",
+                field, selector
+            ),
+            AtCoderClientError::Network(e) => write!(f, "Connection error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AtCoderClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AtCoderClientError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
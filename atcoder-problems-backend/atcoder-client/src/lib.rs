@@ -1,7 +1,16 @@
 pub(crate) mod atcoder;
 pub use atcoder::{
-    AtCoderClient, AtCoderContest, AtCoderProblem, AtCoderSubmission,
-    AtCoderSubmissionListResponse, ContestTypeSpecifier,
+    AtCoderClient, AtCoderClientBuilder, AtCoderContest, AtCoderContestResult, AtCoderProblem,
+    AtCoderProblemDetail, AtCoderStandings, AtCoderStandingsRow, AtCoderStandingsTask,
+    AtCoderSubmission, AtCoderSubmissionListResponse, AtCoderTaskResult, AtCoderTotalResult,
+    ContestTypeSpecifier, DEFAULT_REQUESTS_PER_SECOND,
 };
 
+mod error;
+pub use error::AtCoderClientError;
+
+mod rate_limiter;
+pub use rate_limiter::{RateLimiter, SharedTokenBucket};
+
 pub(crate) mod util;
+pub use util::Problem;
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A token bucket kept outside the process, e.g. in a database, so that the requests of several
+/// processes share one rate.
+pub trait SharedTokenBucket: Debug + Send + Sync {
+    /// Takes a token and returns how long to wait before sending the request.
+    fn reserve(&self) -> Pin<Box<dyn Future<Output = Result<Duration>> + Send + '_>>;
+    /// Makes the following reservations wait until `duration` passes.
+    fn pause(&self, duration: Duration) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>>;
+}
+
+/// A token bucket which limits the rate of requests.
+///
+/// Clones share the same bucket, so a limiter passed to several clients bounds their total rate.
+/// The bucket lives in this process unless a `SharedTokenBucket` is attached.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    state: Arc<Mutex<State>>,
+    shared: Option<Arc<dyn SharedTokenBucket>>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    updated_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        Self::with_burst(requests_per_second, 1)
+    }
+
+    /// Allows `burst` requests to be sent at once after the limiter has been idle.
+    pub fn with_burst(requests_per_second: f64, burst: u32) -> Self {
+        assert!(requests_per_second > 0.0, "The rate must be positive.");
+        assert!(burst > 0, "The burst must be positive.");
+        let burst = f64::from(burst);
+        Self {
+            requests_per_second,
+            burst,
+            state: Arc::new(Mutex::new(State {
+                tokens: burst,
+                updated_at: Instant::now(),
+                paused_until: None,
+            })),
+            shared: None,
+        }
+    }
+
+    /// Also takes a token from `shared` for every request, which bounds the total rate of every
+    /// process using the same bucket.
+    pub fn with_shared_bucket(mut self, shared: Arc<dyn SharedTokenBucket>) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    /// Waits until a request is allowed to be sent.
+    ///
+    /// If the shared bucket is unavailable, only the bucket in this process limits the rate.
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+        if let Some(shared) = &self.shared {
+            match shared.reserve().await {
+                Ok(wait) => tokio::time::sleep(wait).await,
+                Err(e) => log::warn!("Failed to take a token from the shared bucket: {:?}", e),
+            }
+        }
+    }
+
+    /// Stops every request until `duration` passes, e.g. as told by `Retry-After`.
+    ///
+    /// The shared bucket is paused as well, so that the other processes also stop.
+    pub async fn pause(&self, duration: Duration) {
+        {
+            let until = Instant::now() + duration;
+            let mut state = self.state.lock().unwrap();
+            state.paused_until = match state.paused_until {
+                Some(paused_until) if paused_until > until => Some(paused_until),
+                _ => Some(until),
+            };
+        }
+        if let Some(shared) = &self.shared {
+            if let Err(e) = shared.pause(duration).await {
+                log::warn!("Failed to pause the shared bucket: {:?}", e);
+            }
+        }
+    }
+
+    /// Takes a token and returns `None`, or returns how long to wait for the next token.
+    fn try_acquire(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(paused_until) = state.paused_until {
+            if paused_until > now {
+                return Some(paused_until - now);
+            }
+            state.paused_until = None;
+            state.updated_at = paused_until;
+        }
+
+        if now > state.updated_at {
+            let elapsed = (now - state.updated_at).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * self.requests_per_second).min(self.burst);
+            state.updated_at = now;
+        }
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            let wait = (1.0 - state.tokens) / self.requests_per_second;
+            Some(Duration::from_secs_f64(wait))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_acquire() {
+        let limiter = RateLimiter::with_burst(2.0, 2);
+        let now = Instant::now();
+        assert_eq!(limiter.try_acquire(now), None);
+        assert_eq!(limiter.try_acquire(now), None);
+        assert_eq!(limiter.try_acquire(now), Some(Duration::from_millis(500)));
+
+        let cloned = limiter.clone();
+        let now = now + Duration::from_millis(500);
+        assert_eq!(cloned.try_acquire(now), None);
+        assert_eq!(limiter.try_acquire(now), Some(Duration::from_millis(500)));
+
+        let now = now + Duration::from_secs(10);
+        assert_eq!(limiter.try_acquire(now), None);
+        assert_eq!(limiter.try_acquire(now), None);
+        assert!(limiter.try_acquire(now).is_some());
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingBucket {
+        count: Mutex<u32>,
+        pauses: Mutex<Vec<Duration>>,
+    }
+
+    impl SharedTokenBucket for CountingBucket {
+        fn reserve(&self) -> Pin<Box<dyn Future<Output = Result<Duration>> + Send + '_>> {
+            Box::pin(async move {
+                *self.count.lock().unwrap() += 1;
+                Ok(Duration::ZERO)
+            })
+        }
+        fn pause(
+            &self,
+            duration: Duration,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+            Box::pin(async move {
+                self.pauses.lock().unwrap().push(duration);
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn test_shared_bucket() {
+        let shared = Arc::new(CountingBucket::default());
+        let limiter = RateLimiter::with_burst(100.0, 100).with_shared_bucket(shared.clone());
+        let cloned = limiter.clone();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            limiter.acquire().await;
+            cloned.acquire().await;
+        });
+        assert_eq!(*shared.count.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_pause() {
+        let shared = Arc::new(CountingBucket::default());
+        let limiter = RateLimiter::new(100.0).with_shared_bucket(shared.clone());
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(limiter.pause(Duration::from_secs(30)));
+        let wait = limiter.try_acquire(Instant::now()).unwrap();
+        assert!(wait > Duration::from_secs(29));
+
+        let now = Instant::now() + Duration::from_secs(31);
+        assert_eq!(limiter.try_acquire(now), None);
+        assert_eq!(
+            *shared.pauses.lock().unwrap(),
+            vec![Duration::from_secs(30)],
+            "The shared bucket should be paused for the other processes."
+        );
+    }
+}
@@ -1,39 +1,50 @@
-use anyhow::{anyhow, Result};
+use crate::AtCoderClientError;
+use anyhow::Result;
 
+use reqwest::header::RETRY_AFTER;
 use serde::de::DeserializeOwned;
+use std::time::Duration;
 
-pub(crate) async fn get_html(url: &str) -> Result<(String, reqwest::StatusCode)> {
-    let response = reqwest::Client::builder()
-        .gzip(true)
-        .build()?
-        .get(url)
-        .header("accept", "text/html")
+/// Sends the request and turns a non-successful status into an `AtCoderClientError`.
+async fn send(request: reqwest::RequestBuilder, accept: &str) -> Result<reqwest::Response> {
+    let response = request
+        .header("accept", accept)
         .send()
         .await
-        .map_err(|e| anyhow!("Connection error: {:?}", e))?;
+        .map_err(AtCoderClientError::Network)?;
     let status = response.status();
     if !status.is_success() {
         log::error!("{:?}", response);
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        return Err(
+            AtCoderClientError::from_status(response.url().as_str(), status, retry_after).into(),
+        );
     }
-    let body = response
+    Ok(response)
+}
+
+pub(crate) async fn get_html(request: reqwest::RequestBuilder) -> Result<String> {
+    let body = send(request, "text/html")
+        .await?
         .text()
         .await
-        .map_err(|e| anyhow!("Failed to parse HTTP body: {:?}", e))?;
-    Ok((body, status))
+        .map_err(AtCoderClientError::Network)?;
+    Ok(body)
 }
 
-pub(crate) async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T> {
-    reqwest::Client::builder()
-        .gzip(true)
-        .build()?
-        .get(url)
-        .header("accept", "application/json")
-        .send()
-        .await
-        .map_err(|_| anyhow!("Failed to get json from {}", url))?
-        .json::<T>()
+pub(crate) async fn get_json<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+    let body = send(request, "application/json")
+        .await?
+        .bytes()
         .await
-        .map_err(|_| anyhow!("Failed to parse json from {}", url))
+        .map_err(AtCoderClientError::Network)?;
+    serde_json::from_slice(&body)
+        .map_err(|_| AtCoderClientError::layout_changed("JSON body", "$").into())
 }
 
 pub trait Problem {
@@ -1,9 +1,7 @@
--- comment out for now because of the sqlx issue:
--- https://github.com/launchbadge/sqlx/issues/484
--- SET client_encoding = 'UTF8';
+-- The schema before the versioned migrations were introduced.
+-- Every statement is idempotent so that existing databases can adopt it as is.
 
-DROP TABLE IF EXISTS submissions;
-CREATE TABLE submissions (
+CREATE TABLE IF NOT EXISTS submissions (
   id            BIGINT NOT NULL,
   epoch_second  BIGINT NOT NULL,
   problem_id    VARCHAR(255) NOT NULL,
@@ -16,22 +14,20 @@ CREATE TABLE submissions (
   execution_time  INT,
   PRIMARY KEY (id)
 );
-CREATE INDEX ON submissions (user_id);
-CREATE INDEX ON submissions (LOWER(user_id));
-CREATE INDEX ON submissions (epoch_second);
-CREATE INDEX ON submissions (user_id, epoch_second ASC);
-CREATE INDEX ON submissions (LOWER(user_id), epoch_second ASC);
+CREATE INDEX IF NOT EXISTS submissions_user_id_idx ON submissions (user_id);
+CREATE INDEX IF NOT EXISTS submissions_lower_idx ON submissions (LOWER(user_id));
+CREATE INDEX IF NOT EXISTS submissions_epoch_second_idx ON submissions (epoch_second);
+CREATE INDEX IF NOT EXISTS submissions_user_id_epoch_second_idx ON submissions (user_id, epoch_second ASC);
+CREATE INDEX IF NOT EXISTS submissions_lower_epoch_second_idx ON submissions (LOWER(user_id), epoch_second ASC);
 
-DROP TABLE IF EXISTS problems;
-CREATE TABLE problems (
+CREATE TABLE IF NOT EXISTS problems (
   id            VARCHAR(255) NOT NULL,
   contest_id    VARCHAR(255) NOT NULL,
   title         VARCHAR(255) NOT NULL,
   PRIMARY KEY (id)
 );
 
-DROP TABLE IF EXISTS contests;
-CREATE TABLE contests (
+CREATE TABLE IF NOT EXISTS contests (
   id                    VARCHAR(255) NOT NULL,
   start_epoch_second    BIGINT       NOT NULL,
   duration_second       BIGINT       NOT NULL,
@@ -40,123 +36,102 @@ CREATE TABLE contests (
   PRIMARY KEY (id)
 );
 
-DROP TABLE IF EXISTS solver;
-CREATE TABLE solver (
+CREATE TABLE IF NOT EXISTS solver (
   problem_id            VARCHAR(255)  NOT NULL,
   user_count               INT NOT NULL,
   PRIMARY KEY (problem_id)
 );
 
-DROP TABLE IF EXISTS shortest;
-CREATE TABLE shortest (
+CREATE TABLE IF NOT EXISTS shortest (
   contest_id    VARCHAR(255)  NOT NULL,
   problem_id    VARCHAR(255)  NOT NULL,
   submission_id BIGINT  NOT NULL,
   PRIMARY KEY (problem_id)
 );
 
-DROP TABLE IF EXISTS fastest;
-CREATE TABLE fastest (
+CREATE TABLE IF NOT EXISTS fastest (
   contest_id    VARCHAR(255)  NOT NULL,
   problem_id    VARCHAR(255)  NOT NULL,
   submission_id BIGINT  NOT NULL,
   PRIMARY KEY (problem_id)
 );
 
-DROP TABLE IF EXISTS first;
-CREATE TABLE first (
+CREATE TABLE IF NOT EXISTS first (
   contest_id    VARCHAR(255)  NOT NULL,
   problem_id    VARCHAR(255)  NOT NULL,
   submission_id BIGINT  NOT NULL,
   PRIMARY KEY (problem_id)
 );
 
-DROP TABLE IF EXISTS accepted_count;
-CREATE TABLE accepted_count (
+CREATE TABLE IF NOT EXISTS accepted_count (
   user_id       VARCHAR(255)  NOT NULL,
   problem_count INT           NOT NULL,
   PRIMARY KEY (user_id)
 );
 
-DROP TABLE IF EXISTS points;
-CREATE TABLE points (
+CREATE TABLE IF NOT EXISTS points (
   problem_id            VARCHAR(255) NOT NULL,
   point                 DOUBLE PRECISION,
   predict                 DOUBLE PRECISION,
   PRIMARY KEY (problem_id)
 );
 
-DROP TABLE IF EXISTS rated_point_sum;
-CREATE TABLE rated_point_sum (
+CREATE TABLE IF NOT EXISTS rated_point_sum (
   user_id         VARCHAR(255) NOT NULL,
   point_sum       BIGINT NOT NULL,
   PRIMARY KEY (user_id)
 );
 
-DROP TABLE IF EXISTS language_count;
-CREATE TABLE language_count (
+CREATE TABLE IF NOT EXISTS language_count (
   user_id               VARCHAR(255) NOT NULL,
   simplified_language   VARCHAR(255) NOT NULL,
   problem_count         INT NOT NULL,
   PRIMARY KEY (user_id, simplified_language)
 );
 
-DROP TABLE IF EXISTS predicted_rating;
-CREATE TABLE predicted_rating (
+CREATE TABLE IF NOT EXISTS predicted_rating (
   user_id               VARCHAR(255) NOT NULL,
   rating                DOUBLE PRECISION,
   PRIMARY KEY (user_id)
 );
 
-DROP TABLE IF EXISTS contest_problem;
-CREATE TABLE contest_problem (
+CREATE TABLE IF NOT EXISTS contest_problem (
   contest_id            VARCHAR(255) NOT NULL,
   problem_id            VARCHAR(255) NOT NULL,
   PRIMARY KEY (contest_id, problem_id)
 );
 
-DROP TABLE IF EXISTS max_streaks;
-CREATE TABLE max_streaks (
+CREATE TABLE IF NOT EXISTS max_streaks (
   user_id               VARCHAR(255) NOT NULL,
   streak                BIGINT NOT NULL,
   PRIMARY KEY (user_id)
 );
 
 -- For internal services:
-DROP TABLE IF EXISTS internal_problem_list_items;
-DROP TABLE IF EXISTS internal_problem_lists;
 
-DROP TABLE IF EXISTS internal_virtual_contest_participants;
-DROP TABLE IF EXISTS internal_virtual_contest_items;
-DROP TABLE IF EXISTS internal_virtual_contests;
-
-DROP TABLE IF EXISTS internal_progress_reset;
-
-DROP TABLE IF EXISTS internal_users;
-
-CREATE TABLE internal_users (
+CREATE TABLE IF NOT EXISTS internal_users (
   internal_user_id      VARCHAR(255) NOT NULL,
   atcoder_user_id       VARCHAR(255) DEFAULT NULL,
   PRIMARY KEY (internal_user_id)
 );
 
-CREATE TABLE internal_problem_lists (
+CREATE TABLE IF NOT EXISTS internal_problem_lists (
   internal_list_id      VARCHAR(255) NOT NULL,
   internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
   internal_list_name    VARCHAR(255) DEFAULT '',
   PRIMARY KEY (internal_list_id)
 );
-CREATE INDEX ON internal_problem_lists (internal_user_id);
+CREATE INDEX IF NOT EXISTS internal_problem_lists_internal_user_id_idx ON internal_problem_lists (internal_user_id);
 
-CREATE TABLE internal_problem_list_items (
+CREATE TABLE IF NOT EXISTS internal_problem_list_items (
   internal_list_id      VARCHAR(255) REFERENCES internal_problem_lists ON DELETE CASCADE ON UPDATE CASCADE,
   problem_id            VARCHAR(255) NOT NULL,
   memo                  VARCHAR(255) DEFAULT '',
   PRIMARY KEY (internal_list_id, problem_id)
 );
-CREATE INDEX ON internal_problem_list_items (internal_list_id);
+CREATE INDEX IF NOT EXISTS internal_problem_list_items_internal_list_id_idx ON internal_problem_list_items (internal_list_id);
 
-CREATE TABLE internal_virtual_contests (
+CREATE TABLE IF NOT EXISTS internal_virtual_contests (
   id        VARCHAR(255) NOT NULL,
   title     VARCHAR(255) DEFAULT '',
   memo      VARCHAR(255) DEFAULT '',
@@ -168,29 +143,29 @@ CREATE TABLE internal_virtual_contests (
   penalty_second   BIGINT NOT NULL DEFAULT 0,
   PRIMARY KEY (id)
 );
-CREATE INDEX ON internal_virtual_contests (internal_user_id);
-CREATE INDEX ON internal_virtual_contests (start_epoch_second);
+CREATE INDEX IF NOT EXISTS internal_virtual_contests_internal_user_id_idx ON internal_virtual_contests (internal_user_id);
+CREATE INDEX IF NOT EXISTS internal_virtual_contests_start_epoch_second_idx ON internal_virtual_contests (start_epoch_second);
 
-CREATE TABLE internal_virtual_contest_items (
+CREATE TABLE IF NOT EXISTS internal_virtual_contest_items (
   problem_id    VARCHAR(255) NOT NULL,
   internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
   user_defined_point    BIGINT DEFAULT NULL,
   user_defined_order    BIGINT DEFAULT NULL,
   PRIMARY KEY (problem_id, internal_virtual_contest_id)
 );
-CREATE INDEX ON internal_virtual_contest_items (internal_virtual_contest_id);
+CREATE INDEX IF NOT EXISTS internal_virtual_contest_items_internal_virtual_contest_id_idx ON internal_virtual_contest_items (internal_virtual_contest_id);
 
-CREATE TABLE internal_virtual_contest_participants (
+CREATE TABLE IF NOT EXISTS internal_virtual_contest_participants (
   internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
   internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
   PRIMARY KEY (internal_virtual_contest_id, internal_user_id)  
 );
-CREATE INDEX ON internal_virtual_contest_participants (internal_user_id);
+CREATE INDEX IF NOT EXISTS internal_virtual_contest_participants_internal_user_id_idx ON internal_virtual_contest_participants (internal_user_id);
 
-CREATE TABLE internal_progress_reset (
+CREATE TABLE IF NOT EXISTS internal_progress_reset (
   internal_user_id    VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
   problem_id          VARCHAR(255) NOT NULL,
   reset_epoch_second  BIGINT NOT NULL,
   PRIMARY KEY (internal_user_id, problem_id)
 );
-CREATE INDEX ON internal_progress_reset (internal_user_id);
+CREATE INDEX IF NOT EXISTS internal_progress_reset_internal_user_id_idx ON internal_progress_reset (internal_user_id);
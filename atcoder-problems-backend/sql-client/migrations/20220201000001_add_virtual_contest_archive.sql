@@ -0,0 +1,2 @@
+ALTER TABLE internal_virtual_contests
+  ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT FALSE;
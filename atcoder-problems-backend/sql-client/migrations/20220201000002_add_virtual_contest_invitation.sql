@@ -0,0 +1,3 @@
+ALTER TABLE internal_virtual_contests
+  ADD COLUMN invite_token VARCHAR(255) DEFAULT NULL,
+  ADD COLUMN is_registration_closed BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,20 @@
+CREATE TABLE internal_virtual_contest_teams (
+  id                VARCHAR(255) NOT NULL,
+  name              VARCHAR(255) NOT NULL,
+  internal_user_id  VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_virtual_contest_teams (internal_user_id);
+
+CREATE TABLE internal_virtual_contest_team_members (
+  team_id           VARCHAR(255) REFERENCES internal_virtual_contest_teams(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  atcoder_user_id   VARCHAR(255) NOT NULL,
+  PRIMARY KEY (team_id, atcoder_user_id)
+);
+
+CREATE TABLE internal_virtual_contest_team_participants (
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  team_id           VARCHAR(255) REFERENCES internal_virtual_contest_teams(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (internal_virtual_contest_id, team_id)
+);
+CREATE INDEX ON internal_virtual_contest_team_participants (team_id);
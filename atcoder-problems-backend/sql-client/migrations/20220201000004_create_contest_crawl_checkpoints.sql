@@ -0,0 +1,6 @@
+CREATE TABLE contest_crawl_checkpoints (
+  contest_id            VARCHAR(255) NOT NULL,
+  last_page             INTEGER NOT NULL,
+  finished_epoch_second BIGINT,
+  PRIMARY KEY (contest_id)
+);
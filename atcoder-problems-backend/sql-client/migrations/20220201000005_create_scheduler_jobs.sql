@@ -0,0 +1,8 @@
+CREATE TABLE scheduler_jobs (
+  job_name                  VARCHAR(255) NOT NULL,
+  last_started_epoch_second BIGINT,
+  last_success_epoch_second BIGINT,
+  last_error_epoch_second   BIGINT,
+  last_error                TEXT,
+  PRIMARY KEY (job_name)
+);
@@ -0,0 +1,25 @@
+CREATE TABLE contest_standings (
+  contest_id        VARCHAR(255) NOT NULL,
+  user_id           VARCHAR(255) NOT NULL,
+  rank              INTEGER NOT NULL,
+  score             DOUBLE PRECISION NOT NULL,
+  penalty           INTEGER NOT NULL,
+  elapsed_second    BIGINT NOT NULL,
+  is_rated          BOOLEAN NOT NULL,
+  old_rating        INTEGER NOT NULL,
+  PRIMARY KEY (contest_id, user_id)
+);
+CREATE INDEX ON contest_standings (user_id);
+
+CREATE TABLE rating_history (
+  user_id           VARCHAR(255) NOT NULL,
+  contest_id        VARCHAR(255) NOT NULL,
+  is_rated          BOOLEAN NOT NULL,
+  place             INTEGER NOT NULL,
+  old_rating        INTEGER NOT NULL,
+  new_rating        INTEGER NOT NULL,
+  performance       INTEGER NOT NULL,
+  inner_performance INTEGER NOT NULL,
+  end_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (user_id, contest_id)
+);
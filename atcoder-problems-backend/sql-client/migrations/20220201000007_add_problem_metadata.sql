@@ -0,0 +1,8 @@
+ALTER TABLE problems
+  ADD COLUMN problem_index   VARCHAR(255) NOT NULL DEFAULT '',
+  ADD COLUMN score           DOUBLE PRECISION,
+  ADD COLUMN time_limit_ms   INTEGER,
+  ADD COLUMN memory_limit_kb INTEGER;
+
+-- The titles have been stored in the form of "A. Title".
+UPDATE problems SET problem_index = split_part(title, '. ', 1) WHERE title LIKE '%. %';
@@ -0,0 +1,21 @@
+-- The audit log of the verdicts changed by rejudges.
+CREATE TABLE submission_rejudges (
+  submission_id         BIGINT NOT NULL,
+  detected_epoch_second BIGINT NOT NULL,
+  contest_id            VARCHAR(255) NOT NULL,
+  problem_id            VARCHAR(255) NOT NULL,
+  user_id               VARCHAR(255) NOT NULL,
+  old_result            VARCHAR(255) NOT NULL,
+  new_result            VARCHAR(255) NOT NULL,
+  old_point             DOUBLE PRECISION NOT NULL,
+  new_point             DOUBLE PRECISION NOT NULL,
+  PRIMARY KEY (submission_id, detected_epoch_second)
+);
+CREATE INDEX ON submission_rejudges (detected_epoch_second);
+
+-- The users whose aggregates have to be recomputed.
+CREATE TABLE users_to_recompute (
+  user_id               VARCHAR(255) NOT NULL,
+  marked_epoch_second   BIGINT NOT NULL,
+  PRIMARY KEY (user_id)
+);
@@ -0,0 +1,24 @@
+-- Stamps every inserted or changed submission, since the crawlers do not insert submissions in
+-- the order of their ids. The existing rows are left NULL and covered by the first full rebuild.
+CREATE SEQUENCE submission_update_seq;
+ALTER TABLE submissions ADD COLUMN update_seq BIGINT;
+ALTER TABLE submissions ALTER COLUMN update_seq SET DEFAULT nextval('submission_update_seq');
+-- The index on update_seq is built by `migrate` with CONCURRENTLY, not to block the crawlers.
+
+-- The first AC submission of each user, problem and simplified language.
+CREATE TABLE accepted_problems (
+  user_id             VARCHAR(255) NOT NULL,
+  problem_id          VARCHAR(255) NOT NULL,
+  simplified_language VARCHAR(255) NOT NULL,
+  submission_id       BIGINT NOT NULL,
+  epoch_second        BIGINT NOT NULL,
+  point               DOUBLE PRECISION NOT NULL,
+  PRIMARY KEY (user_id, problem_id, simplified_language)
+);
+CREATE INDEX ON accepted_problems (submission_id);
+
+CREATE TABLE aggregate_watermarks (
+  name                VARCHAR(255) NOT NULL,
+  last_update_seq     BIGINT NOT NULL,
+  PRIMARY KEY (name)
+);
@@ -0,0 +1,10 @@
+-- The latest run of consecutive days in JST with a new AC of each user, where the days are counted
+-- from the epoch. It is the current streak while its last day is today or yesterday.
+CREATE TABLE latest_streaks (
+  user_id             VARCHAR(255) NOT NULL,
+  streak              BIGINT NOT NULL,
+  start_day           BIGINT NOT NULL,
+  last_day            BIGINT NOT NULL,
+  PRIMARY KEY (user_id)
+);
+CREATE INDEX ON latest_streaks (last_day);
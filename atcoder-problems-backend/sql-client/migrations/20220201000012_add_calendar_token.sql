@@ -0,0 +1,5 @@
+-- A secret token in the URL of the calendar feed of each user, since calendar applications cannot
+-- send the login cookie.
+ALTER TABLE internal_users
+  ADD COLUMN calendar_token VARCHAR(255) DEFAULT NULL;
+CREATE UNIQUE INDEX ON internal_users (calendar_token);
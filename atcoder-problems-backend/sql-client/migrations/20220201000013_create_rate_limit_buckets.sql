@@ -0,0 +1,8 @@
+-- Token buckets shared by every process, e.g. the one which bounds the total request rate of the
+-- crawlers to AtCoder. `tokens` goes negative while the tokens are reserved in advance.
+CREATE TABLE rate_limit_buckets (
+  name                VARCHAR(255) NOT NULL,
+  tokens              FLOAT8 NOT NULL,
+  updated_at          TIMESTAMPTZ NOT NULL,
+  PRIMARY KEY (name)
+);
@@ -0,0 +1,6 @@
+-- The problems whose score or limits are missing are fetched again by the problem detail crawler.
+-- `detail_fetched_epoch_second` is set once the page of the problem is scraped, and
+-- `detail_failed_epoch_second` rotates the problems which keep failing to the end of the queue.
+ALTER TABLE problems
+  ADD COLUMN detail_fetched_epoch_second BIGINT,
+  ADD COLUMN detail_failed_epoch_second  BIGINT;
@@ -0,0 +1,5 @@
+CREATE TABLE internal_virtual_contest_kicked_users (
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  internal_user_id  VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (internal_virtual_contest_id, internal_user_id)
+);
@@ -0,0 +1,66 @@
+use crate::models::DailyActivity;
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use std::ops::Range;
+
+#[async_trait]
+pub trait ActivityClient {
+    /// Counts the submissions of the user for each day in JST in the range, where the days are
+    /// counted from the epoch as `streak::jst_day`. The days without submissions are omitted.
+    async fn load_daily_activities(
+        &self,
+        user_id: &str,
+        day_range: Range<i64>,
+    ) -> Result<Vec<DailyActivity>>;
+}
+
+#[async_trait]
+impl ActivityClient for PgPool {
+    async fn load_daily_activities(
+        &self,
+        user_id: &str,
+        day_range: Range<i64>,
+    ) -> Result<Vec<DailyActivity>> {
+        // A new AC is the first AC of the problem, which may be before the range.
+        let activities = sqlx::query(
+            r"
+            SELECT
+                day,
+                COUNT(*) AS submission_count,
+                COUNT(*) FILTER (WHERE result = 'AC') AS accepted_count,
+                COUNT(*) FILTER (WHERE is_new_accepted) AS new_accepted_count
+            FROM (
+                SELECT
+                    (epoch_second + 9 * 3600) / 86400 AS day,
+                    result,
+                    result = 'AC' AND ROW_NUMBER() OVER (
+                        PARTITION BY problem_id, result = 'AC'
+                        ORDER BY epoch_second, id
+                    ) = 1 AS is_new_accepted
+                FROM submissions
+                WHERE LOWER(user_id) = LOWER($1)
+            ) AS days
+            WHERE day >= $2 AND day < $3
+            GROUP BY day
+            ORDER BY day
+            ",
+        )
+        .bind(user_id)
+        .bind(day_range.start)
+        .bind(day_range.end)
+        .try_map(|row: PgRow| {
+            Ok(DailyActivity {
+                day: row.try_get("day")?,
+                submission_count: row.try_get("submission_count")?,
+                accepted_count: row.try_get("accepted_count")?,
+                new_accepted_count: row.try_get("new_accepted_count")?,
+            })
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(activities)
+    }
+}
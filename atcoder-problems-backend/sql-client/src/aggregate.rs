@@ -0,0 +1,382 @@
+use crate::submission_client::UPDATE_SEQ_LOCK;
+use crate::{PgPool, FIRST_AGC_EPOCH_SECOND, UNRATED_STATE};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, Row, Transaction};
+use std::collections::BTreeSet;
+
+const WATERMARK_NAME: &str = "accepted_problems";
+
+/// The same simplification as `language_count::simplify_language`.
+const SIMPLIFIED_LANGUAGE: &str = r"
+    CASE
+        WHEN language LIKE 'Perl6%' THEN 'Raku'
+        ELSE regexp_replace(language, '\d*\s*\(.*\)', '')
+    END";
+
+/// Maintains `accepted_count`, `rated_point_sum`, `language_count`, `max_streaks` and
+/// `latest_streaks` without loading all the AC submissions.
+///
+/// Every inserted or changed submission is stamped with `update_seq`. The AC submissions above the
+/// watermark are folded into `accepted_problems`, and the aggregates of their users are recomputed
+/// from it in place. The stamps are not committed in order, so the watermark only advances up to
+/// the stamps whose writers have finished.
+#[async_trait]
+pub trait AggregateClient {
+    /// Processes the submissions stamped after the watermark in batches of `batch_size`, and
+    /// returns the number of the processed stamps. The first call rebuilds everything.
+    async fn update_aggregates(&self, excluded_user_ids: &[&str], batch_size: i64)
+        -> Result<usize>;
+
+    /// Recomputes the aggregates of the users from all of their submissions.
+    async fn rebuild_user_aggregates(&self, user_ids: &[String]) -> Result<()>;
+
+    /// Recomputes all the aggregates from all the submissions, and moves the watermark to the
+    /// latest stamp.
+    async fn rebuild_all_aggregates(&self, excluded_user_ids: &[&str]) -> Result<()>;
+
+    async fn load_aggregate_watermark(&self) -> Result<Option<i64>>;
+}
+
+#[async_trait]
+impl AggregateClient for PgPool {
+    async fn update_aggregates(
+        &self,
+        excluded_user_ids: &[&str],
+        batch_size: i64,
+    ) -> Result<usize> {
+        let latest = load_committed_update_seq(self).await?;
+        let mut processed = 0;
+        loop {
+            let mut tx = self.begin().await?;
+            let watermark = sqlx::query(
+                r"
+                SELECT last_update_seq FROM aggregate_watermarks
+                WHERE name = $1
+                FOR UPDATE
+                ",
+            )
+            .bind(WATERMARK_NAME)
+            .try_map(|row: PgRow| row.try_get::<i64, _>("last_update_seq"))
+            .fetch_optional(&mut tx)
+            .await?;
+            let watermark = match watermark {
+                Some(watermark) => watermark,
+                None => {
+                    tx.rollback().await?;
+                    self.rebuild_all_aggregates(excluded_user_ids).await?;
+                    return Ok(processed);
+                }
+            };
+            if latest <= watermark {
+                tx.rollback().await?;
+                return Ok(processed);
+            }
+            let upper = latest.min(watermark + batch_size);
+
+            // An AC submission which is the first one of a user may have been rejudged.
+            let rejudged_user_ids = sqlx::query(
+                r"
+                SELECT DISTINCT s.user_id FROM submissions AS s
+                JOIN accepted_problems AS a ON a.submission_id = s.id
+                WHERE s.update_seq > $1 AND s.update_seq <= $2 AND s.result != 'AC'
+                ",
+            )
+            .bind(watermark)
+            .bind(upper)
+            .try_map(|row: PgRow| row.try_get::<String, _>("user_id"))
+            .fetch_all(&mut tx)
+            .await?;
+            let accepted_user_ids = sqlx::query(
+                r"
+                SELECT DISTINCT user_id FROM submissions
+                WHERE update_seq > $1 AND update_seq <= $2 AND result = 'AC'
+                AND user_id != ALL($3)
+                ",
+            )
+            .bind(watermark)
+            .bind(upper)
+            .bind(excluded_user_ids)
+            .try_map(|row: PgRow| row.try_get::<String, _>("user_id"))
+            .fetch_all(&mut tx)
+            .await?;
+
+            let filter = "update_seq > $2 AND update_seq <= $3";
+            sqlx::query(&insert_accepted_problems_query(filter))
+                .bind(excluded_user_ids)
+                .bind(watermark)
+                .bind(upper)
+                .execute(&mut tx)
+                .await?;
+            if !rejudged_user_ids.is_empty() {
+                refill_accepted_problems(&mut tx, &rejudged_user_ids).await?;
+            }
+
+            let user_ids = accepted_user_ids
+                .into_iter()
+                .chain(rejudged_user_ids)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            recompute_aggregates(&mut tx, Some(&user_ids)).await?;
+            save_watermark(&mut tx, upper).await?;
+            tx.commit().await?;
+
+            processed += (upper - watermark) as usize;
+        }
+    }
+
+    async fn rebuild_user_aggregates(&self, user_ids: &[String]) -> Result<()> {
+        let mut tx = self.begin().await?;
+        refill_accepted_problems(&mut tx, user_ids).await?;
+        recompute_aggregates(&mut tx, Some(user_ids)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn rebuild_all_aggregates(&self, excluded_user_ids: &[&str]) -> Result<()> {
+        let mut tx = self.begin().await?;
+        // Lock the watermark row as `update_aggregates` does, so that they never run at once.
+        // The row is created if missing, since there is nothing to lock before the first rebuild.
+        sqlx::query(
+            r"
+            INSERT INTO aggregate_watermarks (name, last_update_seq)
+            VALUES ($1, 0)
+            ON CONFLICT (name) DO UPDATE SET last_update_seq = aggregate_watermarks.last_update_seq
+            ",
+        )
+        .bind(WATERMARK_NAME)
+        .execute(&mut tx)
+        .await?;
+
+        // The submissions stamped after this are processed again by the next update.
+        let latest = load_committed_update_seq(self).await?;
+        sqlx::query("DELETE FROM accepted_problems")
+            .execute(&mut tx)
+            .await?;
+        sqlx::query(&insert_accepted_problems_query("TRUE"))
+            .bind(excluded_user_ids)
+            .execute(&mut tx)
+            .await?;
+        recompute_aggregates(&mut tx, None).await?;
+        save_watermark(&mut tx, latest).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_aggregate_watermark(&self) -> Result<Option<i64>> {
+        let watermark =
+            sqlx::query("SELECT last_update_seq FROM aggregate_watermarks WHERE name = $1")
+                .bind(WATERMARK_NAME)
+                .try_map(|row: PgRow| row.try_get::<i64, _>("last_update_seq"))
+                .fetch_optional(self)
+                .await?;
+        Ok(watermark)
+    }
+}
+
+/// Inserts the AC submissions which match `filter`, where `$1` is the excluded users.
+fn insert_accepted_problems_query(filter: &str) -> String {
+    format!(
+        r"
+        INSERT INTO accepted_problems
+            (user_id, problem_id, simplified_language, submission_id, epoch_second, point)
+        SELECT DISTINCT ON (user_id, problem_id, simplified_language)
+            user_id, problem_id, simplified_language, id, epoch_second, point
+        FROM (
+            SELECT id, epoch_second, user_id, problem_id, point, {} AS simplified_language
+            FROM submissions
+            WHERE result = 'AC' AND user_id != ALL($1) AND {}
+        ) AS s
+        ORDER BY user_id, problem_id, simplified_language, id
+        ON CONFLICT (user_id, problem_id, simplified_language) DO UPDATE SET
+            submission_id = EXCLUDED.submission_id,
+            epoch_second = EXCLUDED.epoch_second,
+            point = EXCLUDED.point
+        WHERE EXCLUDED.submission_id < accepted_problems.submission_id
+        ",
+        SIMPLIFIED_LANGUAGE, filter
+    )
+}
+
+async fn refill_accepted_problems(
+    tx: &mut Transaction<'_, Postgres>,
+    user_ids: &[String],
+) -> Result<()> {
+    sqlx::query("DELETE FROM accepted_problems WHERE user_id = ANY($1)")
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&insert_accepted_problems_query("user_id = ANY($2)"))
+        .bind(Vec::<String>::new())
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+/// Replaces the aggregates of the users, or of all the users if `user_ids` is `None`.
+async fn recompute_aggregates(
+    tx: &mut Transaction<'_, Postgres>,
+    user_ids: Option<&[String]>,
+) -> Result<()> {
+    let filter = match user_ids {
+        Some(_) => "user_id = ANY($1)",
+        None => "TRUE",
+    };
+    let rated_problem_ids = format!(
+        r"
+        SELECT contest_problem.problem_id FROM contest_problem
+        JOIN contests ON contests.id = contest_problem.contest_id
+        WHERE contests.start_epoch_second >= {} AND contests.rate_change != '{}'
+        ",
+        FIRST_AGC_EPOCH_SECOND, UNRATED_STATE
+    );
+
+    // Rejected in the same way as `update_rated_point_sum`, since the sum is an integer.
+    let non_integer_point = format!(
+        r"
+        SELECT submission_id, problem_id FROM accepted_problems
+        WHERE {} AND point != TRUNC(point) AND problem_id IN ({})
+        LIMIT 1
+        ",
+        filter, rated_problem_ids
+    );
+    let query = sqlx::query(&non_integer_point);
+    let query = match user_ids {
+        Some(user_ids) => query.bind(user_ids),
+        None => query,
+    };
+    let non_integer_point = query
+        .try_map(|row: PgRow| {
+            let submission_id: i64 = row.try_get("submission_id")?;
+            let problem_id: String = row.try_get("problem_id")?;
+            Ok((submission_id, problem_id))
+        })
+        .fetch_optional(&mut *tx)
+        .await?;
+    if let Some((submission_id, problem_id)) = non_integer_point {
+        bail!(
+            "Problem of {} is {}, which is a rated problem, but has non-integer point",
+            submission_id,
+            problem_id
+        );
+    }
+
+    let queries = [
+        format!("DELETE FROM accepted_count WHERE {}", filter),
+        format!(
+            r"
+            INSERT INTO accepted_count (user_id, problem_count)
+            SELECT user_id, COUNT(DISTINCT problem_id) FROM accepted_problems
+            WHERE {}
+            GROUP BY user_id
+            ",
+            filter
+        ),
+        format!("DELETE FROM language_count WHERE {}", filter),
+        format!(
+            r"
+            INSERT INTO language_count (user_id, simplified_language, problem_count)
+            SELECT user_id, simplified_language, COUNT(*) FROM accepted_problems
+            WHERE {}
+            GROUP BY user_id, simplified_language
+            ",
+            filter
+        ),
+        format!("DELETE FROM rated_point_sum WHERE {}", filter),
+        format!(
+            r"
+            INSERT INTO rated_point_sum (user_id, point_sum)
+            SELECT user_id, SUM(point)::BIGINT FROM (
+                SELECT DISTINCT ON (user_id, problem_id) user_id, point FROM accepted_problems
+                WHERE {} AND problem_id IN ({})
+                ORDER BY user_id, problem_id, submission_id
+            ) AS rated_problems
+            GROUP BY user_id
+            ",
+            filter, rated_problem_ids
+        ),
+        format!("DELETE FROM max_streaks WHERE {}", filter),
+        // The days in JST are numbered, and the consecutive days share `day - row_number`.
+        format!(
+            r"
+            INSERT INTO max_streaks (user_id, streak)
+            SELECT user_id, MAX(streak) FROM (
+                SELECT user_id, COUNT(*) AS streak FROM (
+                    SELECT user_id, day - ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY day) AS run
+                    FROM (
+                        SELECT DISTINCT user_id, (MIN(epoch_second) + 9 * 3600) / 86400 AS day
+                        FROM accepted_problems
+                        WHERE {}
+                        GROUP BY user_id, problem_id
+                    ) AS days
+                ) AS runs
+                GROUP BY user_id, run
+            ) AS streaks
+            GROUP BY user_id
+            ",
+            filter
+        ),
+        format!("DELETE FROM latest_streaks WHERE {}", filter),
+        format!(
+            r"
+            INSERT INTO latest_streaks (user_id, streak, start_day, last_day)
+            SELECT DISTINCT ON (user_id) user_id, COUNT(*), MIN(day), MAX(day) FROM (
+                SELECT user_id, day, day - ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY day) AS run
+                FROM (
+                    SELECT DISTINCT user_id, (MIN(epoch_second) + 9 * 3600) / 86400 AS day
+                    FROM accepted_problems
+                    WHERE {}
+                    GROUP BY user_id, problem_id
+                ) AS days
+            ) AS runs
+            GROUP BY user_id, run
+            ORDER BY user_id, MAX(day) DESC
+            ",
+            filter
+        ),
+    ];
+    for query in queries.iter() {
+        let query = sqlx::query(query);
+        let query = match user_ids {
+            Some(user_ids) => query.bind(user_ids),
+            None => query,
+        };
+        query.execute(&mut *tx).await?;
+    }
+    Ok(())
+}
+
+/// Returns the latest stamp after which no transaction can commit a stamp any more.
+///
+/// The writers hold `UPDATE_SEQ_LOCK` in the shared mode from before they take stamps until they
+/// commit, so the stamps committed after the lock is taken exclusively are larger than this.
+async fn load_committed_update_seq(pool: &PgPool) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(UPDATE_SEQ_LOCK)
+        .execute(&mut tx)
+        .await?;
+    let latest = sqlx::query("SELECT COALESCE(MAX(update_seq), 0) AS latest FROM submissions")
+        .try_map(|row: PgRow| row.try_get::<i64, _>("latest"))
+        .fetch_one(&mut tx)
+        .await?;
+    tx.commit().await?;
+    Ok(latest)
+}
+
+async fn save_watermark(tx: &mut Transaction<'_, Postgres>, update_seq: i64) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO aggregate_watermarks (name, last_update_seq) VALUES ($1, $2)
+        ON CONFLICT (name) DO UPDATE SET last_update_seq = EXCLUDED.last_update_seq
+        ",
+    )
+    .bind(WATERMARK_NAME)
+    .bind(update_seq)
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use crate::models::{ContestStanding, RatingHistoryEntry};
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+#[async_trait]
+pub trait ContestResultClient {
+    /// Replaces the standings of the contest.
+    async fn update_contest_standings(
+        &self,
+        contest_id: &str,
+        standings: &[ContestStanding],
+    ) -> Result<()>;
+    async fn load_contest_standings(&self, contest_id: &str) -> Result<Vec<ContestStanding>>;
+    async fn load_user_standings(&self, user_id: &str) -> Result<Vec<ContestStanding>>;
+    async fn load_standings_contest_ids(&self) -> Result<Vec<String>>;
+    /// Returns the rated participants of the contest whose stored history does not include it.
+    async fn load_rated_users_without_history(&self, contest_id: &str) -> Result<Vec<String>>;
+
+    /// Replaces the rating history of the user.
+    async fn update_rating_history(
+        &self,
+        user_id: &str,
+        history: &[RatingHistoryEntry],
+    ) -> Result<()>;
+    async fn load_rating_history(&self, user_id: &str) -> Result<Vec<RatingHistoryEntry>>;
+}
+
+#[async_trait]
+impl ContestResultClient for PgPool {
+    async fn update_contest_standings(
+        &self,
+        contest_id: &str,
+        standings: &[ContestStanding],
+    ) -> Result<()> {
+        let user_ids = standings
+            .iter()
+            .map(|s| s.user_id.as_str())
+            .collect::<Vec<_>>();
+        let ranks = standings.iter().map(|s| s.rank).collect::<Vec<_>>();
+        let scores = standings.iter().map(|s| s.score).collect::<Vec<_>>();
+        let penalties = standings.iter().map(|s| s.penalty).collect::<Vec<_>>();
+        let elapsed_seconds = standings
+            .iter()
+            .map(|s| s.elapsed_second)
+            .collect::<Vec<_>>();
+        let is_rated = standings.iter().map(|s| s.is_rated).collect::<Vec<_>>();
+        let old_ratings = standings.iter().map(|s| s.old_rating).collect::<Vec<_>>();
+
+        let mut tx = self.begin().await?;
+        sqlx::query("DELETE FROM contest_standings WHERE contest_id = $1")
+            .bind(contest_id)
+            .execute(&mut tx)
+            .await?;
+        sqlx::query(
+            r"
+            INSERT INTO contest_standings
+                (contest_id, user_id, rank, score, penalty, elapsed_second, is_rated, old_rating)
+            SELECT $1, u.*
+            FROM UNNEST(
+                $2::VARCHAR(255)[],
+                $3::INTEGER[],
+                $4::DOUBLE PRECISION[],
+                $5::INTEGER[],
+                $6::BIGINT[],
+                $7::BOOLEAN[],
+                $8::INTEGER[]
+            ) AS u
+            ",
+        )
+        .bind(contest_id)
+        .bind(user_ids)
+        .bind(ranks)
+        .bind(scores)
+        .bind(penalties)
+        .bind(elapsed_seconds)
+        .bind(is_rated)
+        .bind(old_ratings)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_contest_standings(&self, contest_id: &str) -> Result<Vec<ContestStanding>> {
+        let standings = sqlx::query(
+            r"
+            SELECT contest_id, user_id, rank, score, penalty, elapsed_second, is_rated, old_rating
+            FROM contest_standings
+            WHERE contest_id = $1
+            ORDER BY rank, user_id
+            ",
+        )
+        .bind(contest_id)
+        .try_map(map_standing)
+        .fetch_all(self)
+        .await?;
+        Ok(standings)
+    }
+
+    async fn load_user_standings(&self, user_id: &str) -> Result<Vec<ContestStanding>> {
+        let standings = sqlx::query(
+            r"
+            SELECT contest_id, user_id, rank, score, penalty, elapsed_second, is_rated, old_rating
+            FROM contest_standings
+            WHERE user_id = $1
+            ORDER BY contest_id
+            ",
+        )
+        .bind(user_id)
+        .try_map(map_standing)
+        .fetch_all(self)
+        .await?;
+        Ok(standings)
+    }
+
+    async fn load_standings_contest_ids(&self) -> Result<Vec<String>> {
+        let contest_ids = sqlx::query(
+            r"
+            SELECT DISTINCT contest_id
+            FROM contest_standings
+            ORDER BY contest_id
+            ",
+        )
+        .try_map(|row: PgRow| row.try_get::<String, _>("contest_id"))
+        .fetch_all(self)
+        .await?;
+        Ok(contest_ids)
+    }
+
+    async fn load_rated_users_without_history(&self, contest_id: &str) -> Result<Vec<String>> {
+        let user_ids = sqlx::query(
+            r"
+            SELECT s.user_id
+            FROM contest_standings s
+            WHERE s.contest_id = $1
+            AND s.is_rated
+            AND NOT EXISTS (
+                SELECT 1 FROM rating_history h
+                WHERE h.user_id = s.user_id
+                AND h.contest_id = s.contest_id
+            )
+            ORDER BY s.user_id
+            ",
+        )
+        .bind(contest_id)
+        .try_map(|row: PgRow| row.try_get::<String, _>("user_id"))
+        .fetch_all(self)
+        .await?;
+        Ok(user_ids)
+    }
+
+    async fn update_rating_history(
+        &self,
+        user_id: &str,
+        history: &[RatingHistoryEntry],
+    ) -> Result<()> {
+        let contest_ids = history
+            .iter()
+            .map(|h| h.contest_id.as_str())
+            .collect::<Vec<_>>();
+        let is_rated = history.iter().map(|h| h.is_rated).collect::<Vec<_>>();
+        let places = history.iter().map(|h| h.place).collect::<Vec<_>>();
+        let old_ratings = history.iter().map(|h| h.old_rating).collect::<Vec<_>>();
+        let new_ratings = history.iter().map(|h| h.new_rating).collect::<Vec<_>>();
+        let performances = history.iter().map(|h| h.performance).collect::<Vec<_>>();
+        let inner_performances = history
+            .iter()
+            .map(|h| h.inner_performance)
+            .collect::<Vec<_>>();
+        let end_epoch_seconds = history
+            .iter()
+            .map(|h| h.end_epoch_second)
+            .collect::<Vec<_>>();
+
+        let mut tx = self.begin().await?;
+        sqlx::query("DELETE FROM rating_history WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut tx)
+            .await?;
+        sqlx::query(
+            r"
+            INSERT INTO rating_history
+                (user_id, contest_id, is_rated, place, old_rating, new_rating,
+                 performance, inner_performance, end_epoch_second)
+            SELECT $1, u.*
+            FROM UNNEST(
+                $2::VARCHAR(255)[],
+                $3::BOOLEAN[],
+                $4::INTEGER[],
+                $5::INTEGER[],
+                $6::INTEGER[],
+                $7::INTEGER[],
+                $8::INTEGER[],
+                $9::BIGINT[]
+            ) AS u
+            ON CONFLICT DO NOTHING
+            ",
+        )
+        .bind(user_id)
+        .bind(contest_ids)
+        .bind(is_rated)
+        .bind(places)
+        .bind(old_ratings)
+        .bind(new_ratings)
+        .bind(performances)
+        .bind(inner_performances)
+        .bind(end_epoch_seconds)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_rating_history(&self, user_id: &str) -> Result<Vec<RatingHistoryEntry>> {
+        let history = sqlx::query(
+            r"
+            SELECT
+                user_id, contest_id, is_rated, place, old_rating, new_rating,
+                performance, inner_performance, end_epoch_second
+            FROM rating_history
+            WHERE user_id = $1
+            ORDER BY end_epoch_second, contest_id
+            ",
+        )
+        .bind(user_id)
+        .try_map(|row: PgRow| {
+            Ok(RatingHistoryEntry {
+                user_id: row.try_get("user_id")?,
+                contest_id: row.try_get("contest_id")?,
+                is_rated: row.try_get("is_rated")?,
+                place: row.try_get("place")?,
+                old_rating: row.try_get("old_rating")?,
+                new_rating: row.try_get("new_rating")?,
+                performance: row.try_get("performance")?,
+                inner_performance: row.try_get("inner_performance")?,
+                end_epoch_second: row.try_get("end_epoch_second")?,
+            })
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(history)
+    }
+}
+
+fn map_standing(row: PgRow) -> sqlx::Result<ContestStanding> {
+    Ok(ContestStanding {
+        contest_id: row.try_get("contest_id")?,
+        user_id: row.try_get("user_id")?,
+        rank: row.try_get("rank")?,
+        score: row.try_get("score")?,
+        penalty: row.try_get("penalty")?,
+        elapsed_second: row.try_get("elapsed_second")?,
+        is_rated: row.try_get("is_rated")?,
+        old_rating: row.try_get("old_rating")?,
+    })
+}
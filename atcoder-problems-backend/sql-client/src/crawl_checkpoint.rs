@@ -0,0 +1,132 @@
+use crate::models::Contest;
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+/// The progress of crawling all the submissions of a contest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CrawlCheckpoint {
+    pub contest_id: String,
+    /// The last page whose submissions have been stored.
+    pub last_page: i32,
+    /// Set when the crawler reached the last page, and cleared when a new crawl starts.
+    pub finished_epoch_second: Option<i64>,
+}
+
+impl CrawlCheckpoint {
+    /// Returns true if the crawl finished after the contest had ended, so that there is nothing to
+    /// crawl again unless the submissions are rejudged.
+    pub fn is_complete(&self, contest: &Contest) -> bool {
+        match self.finished_epoch_second {
+            Some(finished) => finished >= contest.start_epoch_second + contest.duration_second,
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+pub trait CrawlCheckpointClient {
+    async fn load_crawl_checkpoints(&self) -> Result<Vec<CrawlCheckpoint>>;
+    async fn load_crawl_checkpoint(&self, contest_id: &str) -> Result<Option<CrawlCheckpoint>>;
+    async fn save_crawl_progress(&self, contest_id: &str, page: i32) -> Result<()>;
+    async fn finish_crawl(&self, contest_id: &str, finished_epoch_second: i64) -> Result<()>;
+
+    /// Deletes the checkpoints so that the contests are crawled from the first page again.
+    async fn reset_crawl_checkpoints(&self, contest_ids: &[String]) -> Result<()>;
+}
+
+#[async_trait]
+impl CrawlCheckpointClient for PgPool {
+    async fn load_crawl_checkpoints(&self) -> Result<Vec<CrawlCheckpoint>> {
+        let checkpoints = sqlx::query(
+            r"
+            SELECT contest_id, last_page, finished_epoch_second
+            FROM contest_crawl_checkpoints
+            ORDER BY contest_id
+            ",
+        )
+        .try_map(map_checkpoint)
+        .fetch_all(self)
+        .await?;
+        Ok(checkpoints)
+    }
+
+    async fn load_crawl_checkpoint(&self, contest_id: &str) -> Result<Option<CrawlCheckpoint>> {
+        let checkpoint = sqlx::query(
+            r"
+            SELECT contest_id, last_page, finished_epoch_second
+            FROM contest_crawl_checkpoints
+            WHERE contest_id = $1
+            ",
+        )
+        .bind(contest_id)
+        .try_map(map_checkpoint)
+        .fetch_optional(self)
+        .await?;
+        Ok(checkpoint)
+    }
+
+    async fn save_crawl_progress(&self, contest_id: &str, page: i32) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO contest_crawl_checkpoints
+                (contest_id, last_page, finished_epoch_second)
+            VALUES ($1, $2, NULL)
+            ON CONFLICT (contest_id)
+            DO UPDATE SET
+                last_page = EXCLUDED.last_page,
+                finished_epoch_second = NULL
+            ",
+        )
+        .bind(contest_id)
+        .bind(page)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn finish_crawl(&self, contest_id: &str, finished_epoch_second: i64) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO contest_crawl_checkpoints
+                (contest_id, last_page, finished_epoch_second)
+            VALUES ($1, 0, $2)
+            ON CONFLICT (contest_id)
+            DO UPDATE SET
+                last_page = 0,
+                finished_epoch_second = EXCLUDED.finished_epoch_second
+            ",
+        )
+        .bind(contest_id)
+        .bind(finished_epoch_second)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn reset_crawl_checkpoints(&self, contest_ids: &[String]) -> Result<()> {
+        sqlx::query(
+            r"
+            DELETE FROM contest_crawl_checkpoints
+            WHERE contest_id = ANY($1)
+            ",
+        )
+        .bind(contest_ids)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
+
+fn map_checkpoint(row: PgRow) -> sqlx::Result<CrawlCheckpoint> {
+    let contest_id: String = row.try_get("contest_id")?;
+    let last_page: i32 = row.try_get("last_page")?;
+    let finished_epoch_second: Option<i64> = row.try_get("finished_epoch_second")?;
+    Ok(CrawlCheckpoint {
+        contest_id,
+        last_page,
+        finished_epoch_second,
+    })
+}
@@ -2,3 +2,4 @@ pub mod problem_list_manager;
 pub mod progress_reset_manager;
 pub mod user_manager;
 pub mod virtual_contest_manager;
+pub mod virtual_contest_team_manager;
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use serde::Serialize;
 use sqlx::postgres::PgRow;
 use sqlx::Row;
+use uuid::Uuid;
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
 pub struct InternalUserInfo {
@@ -20,6 +21,13 @@ pub trait UserManager {
         atcoder_user_id: &str,
     ) -> Result<()>;
     async fn get_internal_user_info(&self, internal_user_id: &str) -> Result<InternalUserInfo>;
+
+    /// Issues a new calendar token of the user, which invalidates the previous one.
+    async fn create_calendar_token(&self, internal_user_id: &str) -> Result<String>;
+    async fn get_calendar_token(&self, internal_user_id: &str) -> Result<Option<String>>;
+    async fn delete_calendar_token(&self, internal_user_id: &str) -> Result<()>;
+    /// Returns the internal user id of the user who owns `calendar_token`.
+    async fn get_user_by_calendar_token(&self, calendar_token: &str) -> Result<Option<String>>;
 }
 
 #[async_trait]
@@ -78,4 +86,65 @@ impl UserManager for PgPool {
         .await?;
         Ok(res)
     }
+
+    async fn create_calendar_token(&self, internal_user_id: &str) -> Result<String> {
+        let calendar_token = Uuid::new_v4().to_string();
+        sqlx::query(
+            r"
+            UPDATE internal_users
+            SET calendar_token = $1
+            WHERE internal_user_id = $2
+            ",
+        )
+        .bind(&calendar_token)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(calendar_token)
+    }
+
+    async fn get_calendar_token(&self, internal_user_id: &str) -> Result<Option<String>> {
+        let calendar_token = sqlx::query(
+            r"
+            SELECT calendar_token
+            FROM internal_users
+            WHERE internal_user_id = $1
+            ",
+        )
+        .bind(internal_user_id)
+        .try_map(|row: PgRow| row.try_get::<Option<String>, _>("calendar_token"))
+        .fetch_optional(self)
+        .await?
+        .flatten();
+        Ok(calendar_token)
+    }
+
+    async fn delete_calendar_token(&self, internal_user_id: &str) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE internal_users
+            SET calendar_token = NULL
+            WHERE internal_user_id = $1
+            ",
+        )
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_user_by_calendar_token(&self, calendar_token: &str) -> Result<Option<String>> {
+        let internal_user_id = sqlx::query(
+            r"
+            SELECT internal_user_id
+            FROM internal_users
+            WHERE calendar_token = $1
+            ",
+        )
+        .bind(calendar_token)
+        .try_map(|row: PgRow| row.try_get::<String, _>("internal_user_id"))
+        .fetch_optional(self)
+        .await?;
+        Ok(internal_user_id)
+    }
 }
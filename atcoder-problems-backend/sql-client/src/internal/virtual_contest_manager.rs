@@ -1,15 +1,104 @@
+use crate::models::Submission;
 use crate::PgPool;
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, Result};
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::postgres::PgRow;
 use sqlx::Row;
+use std::fmt;
 use std::result::Result as StdResult;
+use std::str::FromStr;
 use uuid::Uuid;
 
 pub const MAX_PROBLEM_NUM_PER_CONTEST: usize = 300;
 pub const RECENT_CONTEST_NUM: i64 = 1000;
 
+/// Errors which callers may want to distinguish from database failures.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VirtualContestError {
+    ContestNotFound,
+    NotOwner,
+    InvalidMode,
+    RegistrationClosed,
+    InvalidInviteToken,
+    TeamNotFound,
+    TooManyTeamMembers,
+    Kicked,
+}
+
+impl fmt::Display for VirtualContestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VirtualContestError::ContestNotFound => write!(f, "The target contest does not exist."),
+            VirtualContestError::NotOwner => write!(f, "The user does not own the target contest."),
+            VirtualContestError::InvalidMode => write!(f, "The contest mode is invalid."),
+            VirtualContestError::RegistrationClosed => {
+                write!(f, "The registration of the target contest is closed.")
+            }
+            VirtualContestError::InvalidInviteToken => write!(f, "The invite token is invalid."),
+            VirtualContestError::TeamNotFound => write!(f, "The target team is not found."),
+            VirtualContestError::TooManyTeamMembers => {
+                write!(f, "The number of team members exceeds the limit.")
+            }
+            VirtualContestError::Kicked => {
+                write!(f, "The user has been kicked from the target contest.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VirtualContestError {}
+
+/// How the results of a virtual contest are scored.
+///
+/// `Normal` is stored as `NULL` in the `mode` column and serialized as `null`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VirtualContestMode {
+    Normal,
+    /// Only the first participant who solves a problem gets its point.
+    Lockout,
+    /// Every problem is worth 1 point and solving a problem unlocks the next one.
+    Training,
+}
+
+impl VirtualContestMode {
+    fn as_db_value(self) -> Option<&'static str> {
+        match self {
+            VirtualContestMode::Normal => None,
+            VirtualContestMode::Lockout => Some("lockout"),
+            VirtualContestMode::Training => Some("training"),
+        }
+    }
+}
+
+impl FromStr for VirtualContestMode {
+    type Err = VirtualContestError;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s {
+            "normal" => Ok(VirtualContestMode::Normal),
+            "lockout" => Ok(VirtualContestMode::Lockout),
+            "training" => Ok(VirtualContestMode::Training),
+            _ => Err(VirtualContestError::InvalidMode),
+        }
+    }
+}
+
+impl Serialize for VirtualContestMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        self.as_db_value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VirtualContestMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(mode) => mode.parse().map_err(de::Error::custom),
+            None => Ok(VirtualContestMode::Normal),
+        }
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct VirtualContestInfo {
     pub id: String,
@@ -18,12 +107,17 @@ pub struct VirtualContestInfo {
     pub owner_user_id: String, // column name is `internal_user_id`
     pub start_epoch_second: i64,
     pub duration_second: i64,
-    pub mode: Option<String>,
+    pub mode: VirtualContestMode,
     pub is_public: bool,
     pub penalty_second: i64,
+    pub is_archived: bool,
+    pub is_invitation_required: bool,
+    pub is_registration_closed: bool,
 }
 
-fn virtual_contest_info_mapper(row: PgRow) -> StdResult<VirtualContestInfo, sqlx::Error> {
+pub(crate) fn virtual_contest_info_mapper(
+    row: PgRow,
+) -> StdResult<VirtualContestInfo, sqlx::Error> {
     let id: String = row.try_get("id")?;
     let title: String = row.try_get("title")?;
     let memo: String = row.try_get("memo")?;
@@ -31,8 +125,15 @@ fn virtual_contest_info_mapper(row: PgRow) -> StdResult<VirtualContestInfo, sqlx
     let start_epoch_second: i64 = row.try_get("start_epoch_second")?;
     let duration_second: i64 = row.try_get("duration_second")?;
     let mode: Option<String> = row.try_get("mode")?;
+    let mode = match mode {
+        Some(mode) => mode.parse().map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        None => VirtualContestMode::Normal,
+    };
     let is_public: bool = row.try_get("is_public")?;
     let penalty_second: i64 = row.try_get("penalty_second")?;
+    let is_archived: bool = row.try_get("is_archived")?;
+    let is_invitation_required: bool = row.try_get("is_invitation_required")?;
+    let is_registration_closed: bool = row.try_get("is_registration_closed")?;
     Ok(VirtualContestInfo {
         id,
         title,
@@ -43,6 +144,9 @@ fn virtual_contest_info_mapper(row: PgRow) -> StdResult<VirtualContestInfo, sqlx
         mode,
         is_public,
         penalty_second,
+        is_archived,
+        is_invitation_required,
+        is_registration_closed,
     })
 }
 
@@ -55,6 +159,7 @@ pub struct VirtualContestItem {
 
 #[async_trait]
 pub trait VirtualContestManager {
+    #[allow(clippy::too_many_arguments)]
     async fn create_contest(
         &self,
         title: &str,
@@ -62,22 +167,38 @@ pub trait VirtualContestManager {
         internal_user_id: &str,
         start_epoch_second: i64,
         duration_second: i64,
-        mode: Option<&str>,
+        mode: VirtualContestMode,
         is_public: bool,
         penalty_second: i64,
     ) -> Result<String>;
+    async fn clone_contest(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        start_epoch_second: i64,
+    ) -> Result<String>;
+    #[allow(clippy::too_many_arguments)]
     async fn update_contest(
         &self,
         id: &str,
+        internal_user_id: &str,
         title: &str,
         memo: &str,
         start_epoch_second: i64,
         duration_second: i64,
-        mode: Option<&str>,
+        mode: VirtualContestMode,
         is_public: bool,
         penalty_second: i64,
     ) -> Result<()>;
 
+    async fn delete_contest(&self, id: &str, internal_user_id: &str) -> Result<()>;
+    async fn set_contest_archived(
+        &self,
+        id: &str,
+        internal_user_id: &str,
+        is_archived: bool,
+    ) -> Result<()>;
+
     async fn get_own_contests(&self, internal_user_id: &str) -> Result<Vec<VirtualContestInfo>>;
     async fn get_participated_contests(
         &self,
@@ -89,6 +210,7 @@ pub trait VirtualContestManager {
         &self,
         contest_id: &str,
     ) -> Result<Vec<VirtualContestItem>>;
+    async fn get_single_contest_submissions(&self, contest_id: &str) -> Result<Vec<Submission>>;
     async fn get_recent_contest_info(&self) -> Result<Vec<VirtualContestInfo>>;
     async fn get_running_contest_problems(&self, time: i64) -> Result<Vec<(String, i64)>>;
 
@@ -99,8 +221,34 @@ pub trait VirtualContestManager {
         user_id: &str,
     ) -> Result<()>;
 
-    async fn join_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<()>;
+    async fn join_contest(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()>;
     async fn leave_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<()>;
+
+    async fn create_invite_token(&self, contest_id: &str, internal_user_id: &str)
+        -> Result<String>;
+    async fn get_invite_token(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+    ) -> Result<Option<String>>;
+    async fn delete_invite_token(&self, contest_id: &str, internal_user_id: &str) -> Result<()>;
+    async fn set_registration_closed(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        is_registration_closed: bool,
+    ) -> Result<()>;
+    async fn kick_participant(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        atcoder_user_id: &str,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -112,7 +260,7 @@ impl VirtualContestManager for PgPool {
         internal_user_id: &str,
         start_epoch_second: i64,
         duration_second: i64,
-        mode: Option<&str>,
+        mode: VirtualContestMode,
         is_public: bool,
         penalty_second: i64,
     ) -> Result<String> {
@@ -130,24 +278,92 @@ impl VirtualContestManager for PgPool {
         .bind(internal_user_id)
         .bind(start_epoch_second)
         .bind(duration_second)
-        .bind(mode)
+        .bind(mode.as_db_value())
         .bind(is_public)
         .bind(penalty_second)
         .execute(self).await?;
         Ok(uuid)
     }
 
+    async fn clone_contest(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        start_epoch_second: i64,
+    ) -> Result<String> {
+        let uuid = Uuid::new_v4().to_string();
+        let mut tx = self.begin().await?;
+
+        let (owner, invite_token) = sqlx::query(
+            r"
+            SELECT internal_user_id, invite_token
+            FROM internal_virtual_contests
+            WHERE id = $1
+            FOR SHARE
+            ",
+        )
+        .bind(contest_id)
+        .try_map(|row: PgRow| {
+            let owner: Option<String> = row.try_get("internal_user_id")?;
+            let invite_token: Option<String> = row.try_get("invite_token")?;
+            Ok((owner, invite_token))
+        })
+        .fetch_optional(&mut tx)
+        .await?
+        .ok_or(VirtualContestError::ContestNotFound)?;
+
+        // A contest requiring an invitation is private to its owner and the invitees.
+        if invite_token.is_some() && owner.as_deref() != Some(internal_user_id) {
+            bail!(VirtualContestError::NotOwner);
+        }
+
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contests
+            (id, title, memo, internal_user_id, start_epoch_second, duration_second, mode, is_public, penalty_second)
+            SELECT $1, title, memo, $2, $3, duration_second, mode, is_public, penalty_second
+            FROM internal_virtual_contests
+            WHERE id = $4
+            ",
+        )
+        .bind(&uuid)
+        .bind(internal_user_id)
+        .bind(start_epoch_second)
+        .bind(contest_id)
+        .execute(&mut tx)
+        .await?;
+
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_items
+            (internal_virtual_contest_id, problem_id, user_defined_point, user_defined_order)
+            SELECT $1, problem_id, user_defined_point, user_defined_order
+            FROM internal_virtual_contest_items
+            WHERE internal_virtual_contest_id = $2
+            ",
+        )
+        .bind(&uuid)
+        .bind(contest_id)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(uuid)
+    }
+
     async fn update_contest(
         &self,
         id: &str,
+        internal_user_id: &str,
         title: &str,
         memo: &str,
         start_epoch_second: i64,
         duration_second: i64,
-        mode: Option<&str>,
+        mode: VirtualContestMode,
         is_public: bool,
         penalty_second: i64,
     ) -> Result<()> {
+        check_owner(self, id, internal_user_id).await?;
         sqlx::query(
             r"
             UPDATE internal_virtual_contests
@@ -160,16 +376,57 @@ impl VirtualContestManager for PgPool {
                 is_public = $6,
                 penalty_second = $7
             WHERE id = $8
+            AND internal_user_id = $9
             ",
         )
         .bind(title)
         .bind(memo)
         .bind(start_epoch_second)
         .bind(duration_second)
-        .bind(mode)
+        .bind(mode.as_db_value())
         .bind(is_public)
         .bind(penalty_second)
         .bind(id)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_contest(&self, id: &str, internal_user_id: &str) -> Result<()> {
+        check_owner(self, id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contests
+            WHERE id = $1
+            AND internal_user_id = $2
+            ",
+        )
+        .bind(id)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_contest_archived(
+        &self,
+        id: &str,
+        internal_user_id: &str,
+        is_archived: bool,
+    ) -> Result<()> {
+        check_owner(self, id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            UPDATE internal_virtual_contests
+            SET is_archived = $1
+            WHERE id = $2
+            AND internal_user_id = $3
+            ",
+        )
+        .bind(is_archived)
+        .bind(id)
+        .bind(internal_user_id)
         .execute(self)
         .await?;
         Ok(())
@@ -187,7 +444,10 @@ impl VirtualContestManager for PgPool {
                 duration_second,
                 mode,
                 is_public,
-                penalty_second
+                penalty_second,
+                is_archived,
+                invite_token IS NOT NULL AS is_invitation_required,
+                is_registration_closed
             FROM internal_virtual_contests
             WHERE internal_user_id = $1
             ",
@@ -215,7 +475,10 @@ impl VirtualContestManager for PgPool {
                 a.duration_second,
                 a.mode,
                 a.is_public,
-                a.penalty_second
+                a.penalty_second,
+                a.is_archived,
+                a.invite_token IS NOT NULL AS is_invitation_required,
+                a.is_registration_closed
             FROM internal_virtual_contests AS a
             LEFT JOIN internal_virtual_contest_participants AS b
             ON a.id = b.internal_virtual_contest_id
@@ -242,15 +505,19 @@ impl VirtualContestManager for PgPool {
                 duration_second,
                 mode,
                 is_public,
-                penalty_second
+                penalty_second,
+                is_archived,
+                invite_token IS NOT NULL AS is_invitation_required,
+                is_registration_closed
             FROM internal_virtual_contests
             WHERE id = $1
             ",
         )
         .bind(contest_id)
         .try_map(virtual_contest_info_mapper)
-        .fetch_one(self)
-        .await?;
+        .fetch_optional(self)
+        .await?
+        .ok_or(VirtualContestError::ContestNotFound)?;
 
         Ok(info)
     }
@@ -307,6 +574,43 @@ impl VirtualContestManager for PgPool {
         Ok(problems)
     }
 
+    async fn get_single_contest_submissions(&self, contest_id: &str) -> Result<Vec<Submission>> {
+        let submissions = sqlx::query_as(
+            r"
+            SELECT s.*
+            FROM submissions AS s
+            JOIN internal_virtual_contests AS c
+            ON c.id = $1
+            WHERE s.user_id IN (
+                SELECT b.atcoder_user_id
+                FROM internal_virtual_contest_participants AS a
+                JOIN internal_users AS b
+                ON a.internal_user_id = b.internal_user_id
+                WHERE a.internal_virtual_contest_id = $1
+                UNION
+                SELECT m.atcoder_user_id
+                FROM internal_virtual_contest_team_participants AS t
+                JOIN internal_virtual_contest_team_members AS m
+                ON t.team_id = m.team_id
+                WHERE t.internal_virtual_contest_id = $1
+            )
+            AND s.problem_id IN (
+                SELECT problem_id
+                FROM internal_virtual_contest_items
+                WHERE internal_virtual_contest_id = $1
+            )
+            AND s.epoch_second >= c.start_epoch_second
+            AND s.epoch_second <= c.start_epoch_second + c.duration_second
+            ORDER BY s.id ASC
+            ",
+        )
+        .bind(contest_id)
+        .fetch_all(self)
+        .await?;
+
+        Ok(submissions)
+    }
+
     async fn get_recent_contest_info(&self) -> Result<Vec<VirtualContestInfo>> {
         let contests = sqlx::query(
             r"
@@ -319,9 +623,14 @@ impl VirtualContestManager for PgPool {
                 duration_second,
                 mode,
                 is_public,
-                penalty_second
+                penalty_second,
+                is_archived,
+                invite_token IS NOT NULL AS is_invitation_required,
+                is_registration_closed
             FROM internal_virtual_contests
             WHERE is_public IS TRUE
+            AND is_archived IS FALSE
+            AND invite_token IS NULL
             ORDER BY start_epoch_second + duration_second DESC
             LIMIT $1
             ",
@@ -367,21 +676,7 @@ impl VirtualContestManager for PgPool {
             bail!("The number of problems exceeded.");
         }
 
-        // Checks if the target contest exists
-        sqlx::query(
-            r"
-            SELECT id
-            FROM internal_virtual_contests
-            WHERE internal_user_id = $1
-            AND id = $2
-            ",
-        )
-        .bind(user_id)
-        .bind(contest_id)
-        .try_map(|row: PgRow| row.try_get::<String, _>("id"))
-        .fetch_one(self)
-        .await
-        .context("The target contest does not exist.")?;
+        check_owner(self, contest_id, user_id).await?;
 
         let (contest_ids, problem_ids, points, orders) = problems.iter().fold(
             (vec![], vec![], vec![], vec![]),
@@ -432,7 +727,13 @@ impl VirtualContestManager for PgPool {
         Ok(())
     }
 
-    async fn join_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<()> {
+    async fn join_contest(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()> {
+        check_joinable(self, contest_id, internal_user_id, invite_token).await?;
         sqlx::query(
             r"
             INSERT INTO internal_virtual_contest_participants
@@ -461,4 +762,209 @@ impl VirtualContestManager for PgPool {
         .await?;
         Ok(())
     }
+
+    async fn create_invite_token(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+    ) -> Result<String> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        let invite_token = Uuid::new_v4().to_string();
+        sqlx::query(
+            r"
+            UPDATE internal_virtual_contests
+            SET invite_token = $1
+            WHERE id = $2
+            AND internal_user_id = $3
+            ",
+        )
+        .bind(&invite_token)
+        .bind(contest_id)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(invite_token)
+    }
+
+    async fn get_invite_token(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+    ) -> Result<Option<String>> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        let invite_token = sqlx::query(
+            r"
+            SELECT invite_token
+            FROM internal_virtual_contests
+            WHERE id = $1
+            ",
+        )
+        .bind(contest_id)
+        .try_map(|row: PgRow| row.try_get::<Option<String>, _>("invite_token"))
+        .fetch_one(self)
+        .await?;
+        Ok(invite_token)
+    }
+
+    async fn delete_invite_token(&self, contest_id: &str, internal_user_id: &str) -> Result<()> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            UPDATE internal_virtual_contests
+            SET invite_token = NULL
+            WHERE id = $1
+            AND internal_user_id = $2
+            ",
+        )
+        .bind(contest_id)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_registration_closed(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        is_registration_closed: bool,
+    ) -> Result<()> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            UPDATE internal_virtual_contests
+            SET is_registration_closed = $1
+            WHERE id = $2
+            AND internal_user_id = $3
+            ",
+        )
+        .bind(is_registration_closed)
+        .bind(contest_id)
+        .bind(internal_user_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn kick_participant(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        atcoder_user_id: &str,
+    ) -> Result<()> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        let mut tx = self.begin().await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contest_participants
+            WHERE internal_virtual_contest_id = $1
+            AND internal_user_id IN (
+                SELECT internal_user_id
+                FROM internal_users
+                WHERE atcoder_user_id = $2
+            )
+            ",
+        )
+        .bind(contest_id)
+        .bind(atcoder_user_id)
+        .execute(&mut tx)
+        .await?;
+
+        // Remember the kicked users so that they cannot join the contest again.
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_kicked_users
+            (internal_virtual_contest_id, internal_user_id)
+            SELECT $1, internal_user_id
+            FROM internal_users
+            WHERE atcoder_user_id = $2
+            ON CONFLICT DO NOTHING
+            ",
+        )
+        .bind(contest_id)
+        .bind(atcoder_user_id)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+pub(crate) async fn check_owner(
+    pool: &PgPool,
+    contest_id: &str,
+    internal_user_id: &str,
+) -> Result<()> {
+    let owner = sqlx::query(
+        r"
+        SELECT internal_user_id
+        FROM internal_virtual_contests
+        WHERE id = $1
+        ",
+    )
+    .bind(contest_id)
+    .try_map(|row: PgRow| row.try_get::<Option<String>, _>("internal_user_id"))
+    .fetch_optional(pool)
+    .await?
+    .ok_or(VirtualContestError::ContestNotFound)?;
+
+    if owner.as_deref() != Some(internal_user_id) {
+        bail!(VirtualContestError::NotOwner);
+    }
+    Ok(())
+}
+
+/// Checks that `internal_user_id` may register for the contest, either by themselves or as a team.
+pub(crate) async fn check_joinable(
+    pool: &PgPool,
+    contest_id: &str,
+    internal_user_id: &str,
+    invite_token: Option<&str>,
+) -> Result<()> {
+    let (owner, expected_token, is_registration_closed) = sqlx::query(
+        r"
+        SELECT internal_user_id, invite_token, is_registration_closed
+        FROM internal_virtual_contests
+        WHERE id = $1
+        ",
+    )
+    .bind(contest_id)
+    .try_map(|row: PgRow| {
+        let owner: Option<String> = row.try_get("internal_user_id")?;
+        let invite_token: Option<String> = row.try_get("invite_token")?;
+        let is_registration_closed: bool = row.try_get("is_registration_closed")?;
+        Ok((owner, invite_token, is_registration_closed))
+    })
+    .fetch_optional(pool)
+    .await?
+    .ok_or(VirtualContestError::ContestNotFound)?;
+
+    // The owner can always join their own contest.
+    if owner.as_deref() != Some(internal_user_id) {
+        if is_registration_closed {
+            bail!(VirtualContestError::RegistrationClosed);
+        }
+        if expected_token.is_some() && expected_token.as_deref() != invite_token {
+            bail!(VirtualContestError::InvalidInviteToken);
+        }
+
+        let kicked = sqlx::query(
+            r"
+            SELECT 1
+            FROM internal_virtual_contest_kicked_users
+            WHERE internal_virtual_contest_id = $1
+            AND internal_user_id = $2
+            ",
+        )
+        .bind(contest_id)
+        .bind(internal_user_id)
+        .fetch_optional(pool)
+        .await?;
+        if kicked.is_some() {
+            bail!(VirtualContestError::Kicked);
+        }
+    }
+
+    Ok(())
 }
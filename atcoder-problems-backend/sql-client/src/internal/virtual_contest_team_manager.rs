@@ -0,0 +1,360 @@
+use crate::internal::virtual_contest_manager::{
+    check_joinable, check_owner, virtual_contest_info_mapper, VirtualContestError,
+    VirtualContestInfo,
+};
+use crate::PgPool;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use uuid::Uuid;
+
+pub const MAX_MEMBER_NUM_PER_TEAM: usize = 10;
+
+/// A group of AtCoder accounts which joins virtual contests as a single entry.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct VirtualContestTeam {
+    pub id: String,
+    pub name: String,
+    pub owner_user_id: String,
+    pub members: Vec<String>,
+}
+
+#[async_trait]
+pub trait VirtualContestTeamManager {
+    async fn create_team(
+        &self,
+        name: &str,
+        internal_user_id: &str,
+        members: &[String],
+    ) -> Result<String>;
+    async fn update_team(
+        &self,
+        team_id: &str,
+        internal_user_id: &str,
+        name: &str,
+        members: &[String],
+    ) -> Result<()>;
+    async fn delete_team(&self, team_id: &str, internal_user_id: &str) -> Result<()>;
+    async fn get_own_teams(&self, internal_user_id: &str) -> Result<Vec<VirtualContestTeam>>;
+    async fn get_single_contest_teams(&self, contest_id: &str) -> Result<Vec<VirtualContestTeam>>;
+    /// Returns the contests joined by the teams which the user owns or belongs to.
+    async fn get_team_participated_contests(
+        &self,
+        internal_user_id: &str,
+    ) -> Result<Vec<VirtualContestInfo>>;
+
+    async fn join_contest_as_team(
+        &self,
+        contest_id: &str,
+        team_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()>;
+    async fn leave_contest_as_team(
+        &self,
+        contest_id: &str,
+        team_id: &str,
+        internal_user_id: &str,
+    ) -> Result<()>;
+    /// Removes a team from a contest on behalf of the contest owner.
+    async fn kick_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_id: &str,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl VirtualContestTeamManager for PgPool {
+    async fn create_team(
+        &self,
+        name: &str,
+        internal_user_id: &str,
+        members: &[String],
+    ) -> Result<String> {
+        if members.len() > MAX_MEMBER_NUM_PER_TEAM {
+            bail!(VirtualContestError::TooManyTeamMembers);
+        }
+
+        let team_id = Uuid::new_v4().to_string();
+        let mut tx = self.begin().await?;
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_teams (id, name, internal_user_id)
+            VALUES ($1, $2, $3)
+            ",
+        )
+        .bind(&team_id)
+        .bind(name)
+        .bind(internal_user_id)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_team_members (team_id, atcoder_user_id)
+            SELECT $1, UNNEST($2::VARCHAR(255)[])
+            ON CONFLICT DO NOTHING
+            ",
+        )
+        .bind(&team_id)
+        .bind(members)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(team_id)
+    }
+
+    async fn update_team(
+        &self,
+        team_id: &str,
+        internal_user_id: &str,
+        name: &str,
+        members: &[String],
+    ) -> Result<()> {
+        if members.len() > MAX_MEMBER_NUM_PER_TEAM {
+            bail!(VirtualContestError::TooManyTeamMembers);
+        }
+
+        check_team_owner(self, team_id, internal_user_id).await?;
+        let mut tx = self.begin().await?;
+        sqlx::query(
+            r"
+            UPDATE internal_virtual_contest_teams
+            SET name = $1
+            WHERE id = $2
+            ",
+        )
+        .bind(name)
+        .bind(team_id)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contest_team_members
+            WHERE team_id = $1
+            ",
+        )
+        .bind(team_id)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_team_members (team_id, atcoder_user_id)
+            SELECT $1, UNNEST($2::VARCHAR(255)[])
+            ON CONFLICT DO NOTHING
+            ",
+        )
+        .bind(team_id)
+        .bind(members)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete_team(&self, team_id: &str, internal_user_id: &str) -> Result<()> {
+        check_team_owner(self, team_id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contest_teams
+            WHERE id = $1
+            ",
+        )
+        .bind(team_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_own_teams(&self, internal_user_id: &str) -> Result<Vec<VirtualContestTeam>> {
+        let teams = sqlx::query(
+            r"
+            SELECT
+                t.id,
+                t.name,
+                t.internal_user_id,
+                ARRAY_REMOVE(ARRAY_AGG(m.atcoder_user_id ORDER BY m.atcoder_user_id), NULL)::TEXT[] AS members
+            FROM internal_virtual_contest_teams AS t
+            LEFT JOIN internal_virtual_contest_team_members AS m
+            ON t.id = m.team_id
+            WHERE t.internal_user_id = $1
+            GROUP BY t.id
+            ORDER BY t.name, t.id
+            ",
+        )
+        .bind(internal_user_id)
+        .try_map(map_team)
+        .fetch_all(self)
+        .await?;
+        Ok(teams)
+    }
+
+    async fn get_single_contest_teams(&self, contest_id: &str) -> Result<Vec<VirtualContestTeam>> {
+        let teams = sqlx::query(
+            r"
+            SELECT
+                t.id,
+                t.name,
+                t.internal_user_id,
+                ARRAY_REMOVE(ARRAY_AGG(m.atcoder_user_id ORDER BY m.atcoder_user_id), NULL)::TEXT[] AS members
+            FROM internal_virtual_contest_team_participants AS p
+            JOIN internal_virtual_contest_teams AS t
+            ON p.team_id = t.id
+            LEFT JOIN internal_virtual_contest_team_members AS m
+            ON t.id = m.team_id
+            WHERE p.internal_virtual_contest_id = $1
+            GROUP BY t.id
+            ORDER BY t.name, t.id
+            ",
+        )
+        .bind(contest_id)
+        .try_map(map_team)
+        .fetch_all(self)
+        .await?;
+        Ok(teams)
+    }
+
+    async fn get_team_participated_contests(
+        &self,
+        internal_user_id: &str,
+    ) -> Result<Vec<VirtualContestInfo>> {
+        let contests = sqlx::query(
+            r"
+            SELECT
+                c.id,
+                c.title,
+                c.memo,
+                c.internal_user_id,
+                c.start_epoch_second,
+                c.duration_second,
+                c.mode,
+                c.is_public,
+                c.penalty_second,
+                c.is_archived,
+                c.invite_token IS NOT NULL AS is_invitation_required,
+                c.is_registration_closed
+            FROM internal_virtual_contests AS c
+            WHERE c.id IN (
+                SELECT p.internal_virtual_contest_id
+                FROM internal_virtual_contest_team_participants AS p
+                JOIN internal_virtual_contest_teams AS t
+                ON p.team_id = t.id
+                WHERE t.internal_user_id = $1
+                OR t.id IN (
+                    SELECT m.team_id
+                    FROM internal_virtual_contest_team_members AS m
+                    JOIN internal_users AS u
+                    ON m.atcoder_user_id = u.atcoder_user_id
+                    WHERE u.internal_user_id = $1
+                )
+            )
+            ",
+        )
+        .bind(internal_user_id)
+        .try_map(virtual_contest_info_mapper)
+        .fetch_all(self)
+        .await?;
+        Ok(contests)
+    }
+
+    async fn join_contest_as_team(
+        &self,
+        contest_id: &str,
+        team_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()> {
+        check_team_owner(self, team_id, internal_user_id).await?;
+        check_joinable(self, contest_id, internal_user_id, invite_token).await?;
+        sqlx::query(
+            r"
+            INSERT INTO internal_virtual_contest_team_participants
+            (internal_virtual_contest_id, team_id)
+            VALUES ($1, $2)
+            ",
+        )
+        .bind(contest_id)
+        .bind(team_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn leave_contest_as_team(
+        &self,
+        contest_id: &str,
+        team_id: &str,
+        internal_user_id: &str,
+    ) -> Result<()> {
+        check_team_owner(self, team_id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contest_team_participants
+            WHERE internal_virtual_contest_id = $1
+            AND team_id = $2
+            ",
+        )
+        .bind(contest_id)
+        .bind(team_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn kick_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_id: &str,
+    ) -> Result<()> {
+        check_owner(self, contest_id, internal_user_id).await?;
+        sqlx::query(
+            r"
+            DELETE FROM internal_virtual_contest_team_participants
+            WHERE internal_virtual_contest_id = $1
+            AND team_id = $2
+            ",
+        )
+        .bind(contest_id)
+        .bind(team_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
+
+fn map_team(row: PgRow) -> sqlx::Result<VirtualContestTeam> {
+    let id: String = row.try_get("id")?;
+    let name: String = row.try_get("name")?;
+    let owner_user_id: String = row.try_get("internal_user_id")?;
+    let members: Vec<String> = row.try_get("members")?;
+    Ok(VirtualContestTeam {
+        id,
+        name,
+        owner_user_id,
+        members,
+    })
+}
+
+async fn check_team_owner(pool: &PgPool, team_id: &str, internal_user_id: &str) -> Result<()> {
+    let owner = sqlx::query(
+        r"
+        SELECT internal_user_id
+        FROM internal_virtual_contest_teams
+        WHERE id = $1
+        ",
+    )
+    .bind(team_id)
+    .try_map(|row: PgRow| row.try_get::<Option<String>, _>("internal_user_id"))
+    .fetch_optional(pool)
+    .await?
+    .ok_or(VirtualContestError::TeamNotFound)?;
+
+    if owner.as_deref() != Some(internal_user_id) {
+        bail!(VirtualContestError::NotOwner);
+    }
+    Ok(())
+}
@@ -2,20 +2,32 @@ use anyhow::Result;
 use std::time::Duration;
 
 pub mod accepted_count;
+pub mod activity;
+pub mod aggregate;
 pub mod contest_problem;
+pub mod contest_result;
+pub mod crawl_checkpoint;
 pub mod internal;
 pub mod language_count;
 pub mod models;
+pub mod problem_detail;
 pub mod problem_info;
 pub mod problems_submissions;
+pub mod rate_limit;
 pub mod rated_point_sum;
+pub mod rejudge;
+pub mod scheduler_job;
 pub mod simple_client;
 pub mod streak;
 pub mod submission_client;
+pub mod submission_listener;
 
 pub use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
 pub use sqlx::{query, Row};
 
+/// The versioned schema migrations, whose applied versions are recorded in `_sqlx_migrations`.
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
 const FIRST_AGC_EPOCH_SECOND: i64 = 1_468_670_400;
 const UNRATED_STATE: &str = "-";
 const MAX_INSERT_ROWS: usize = 10_000;
@@ -28,3 +40,70 @@ pub async fn initialize_pool<S: AsRef<str>>(database_url: S) -> Result<PgPool> {
         .await?;
     Ok(pool)
 }
+
+/// Applies the migrations which have not been applied to the database yet.
+pub async fn run_migrations(pool: &PgPool) -> Result<()> {
+    MIGRATOR.run(pool).await?;
+    Ok(())
+}
+
+/// The names and the definitions of the indexes on the large tables.
+///
+/// A plain `CREATE INDEX` blocks the writes to the table until the index is built, so these are
+/// built with `CONCURRENTLY` instead, which cannot run in the transaction of a migration.
+const CONCURRENT_INDEXES: &[(&str, &str)] = &[
+    ("submissions_update_seq_idx", "submissions (update_seq)"),
+    // The paging APIs order the submissions by (epoch_second, id), since many submissions share
+    // a second.
+    (
+        "submissions_epoch_second_id_idx",
+        "submissions (epoch_second, id)",
+    ),
+    (
+        "submissions_lower_epoch_second_id_idx",
+        "submissions (LOWER(user_id), epoch_second, id)",
+    ),
+];
+
+/// The indexes which are prefixes of the ones in `CONCURRENT_INDEXES` and dropped after them.
+const SUPERSEDED_INDEXES: &[&str] = &[
+    "submissions_lower_idx",
+    "submissions_lower_epoch_second_idx",
+];
+
+/// Builds the indexes in `CONCURRENT_INDEXES` which do not exist yet and drops the superseded ones.
+/// It has to run after `run_migrations`, since the indexes depend on the migrated schema.
+pub async fn create_indexes_concurrently(pool: &PgPool) -> Result<()> {
+    for (name, definition) in CONCURRENT_INDEXES {
+        // An interrupted build leaves an invalid index, which `IF NOT EXISTS` would skip.
+        let is_valid: Option<bool> = sqlx::query_scalar(
+            r"
+            SELECT indisvalid
+            FROM pg_index
+            WHERE indexrelid = to_regclass($1)
+            ",
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+        if is_valid == Some(false) {
+            sqlx::query(&format!("DROP INDEX CONCURRENTLY {}", name))
+                .execute(pool)
+                .await?;
+        }
+
+        sqlx::query(&format!(
+            "CREATE INDEX CONCURRENTLY IF NOT EXISTS {} ON {}",
+            name, definition
+        ))
+        .execute(pool)
+        .await?;
+    }
+
+    for name in SUPERSEDED_INDEXES {
+        sqlx::query(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", name))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
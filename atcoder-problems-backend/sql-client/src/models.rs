@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgRow;
 use sqlx::FromRow;
 use sqlx::Row;
+use std::str::FromStr;
 
 #[derive(Default, Debug, Eq, PartialEq, Serialize)]
 pub struct Contest {
@@ -17,13 +18,55 @@ impl Contest {
     pub fn is_rated(&self) -> bool {
         self.start_epoch_second >= FIRST_AGC_EPOCH_SECOND && self.rate_change != UNRATED_STATE
     }
+
+    pub fn series(&self) -> ContestSeries {
+        if self.id.starts_with("abc") {
+            ContestSeries::Abc
+        } else if self.id.starts_with("arc") {
+            ContestSeries::Arc
+        } else if self.id.starts_with("agc") {
+            ContestSeries::Agc
+        } else {
+            ContestSeries::Other
+        }
+    }
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize)]
+/// The series of an official contest, which is determined by the prefix of its id.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ContestSeries {
+    Abc,
+    Arc,
+    Agc,
+    Other,
+}
+
+impl FromStr for ContestSeries {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "abc" => Ok(ContestSeries::Abc),
+            "arc" => Ok(ContestSeries::Arc),
+            "agc" => Ok(ContestSeries::Agc),
+            "other" => Ok(ContestSeries::Other),
+            _ => Err(anyhow::anyhow!("Unknown contest series: {}", s)),
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Serialize)]
 pub struct Problem {
     pub id: String,
     pub contest_id: String,
+    /// The title prefixed with the index, e.g. `A. Title`.
     pub title: String,
+    /// The index in the contest, e.g. `A`.
+    pub problem_index: String,
+    /// The score stated in the problem statement.
+    pub score: Option<f64>,
+    pub time_limit_ms: Option<i32>,
+    pub memory_limit_kb: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize)]
@@ -67,6 +110,34 @@ impl FromRow<'_, PgRow> for Submission {
     }
 }
 
+/// A row of the final standings of a contest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContestStanding {
+    pub contest_id: String,
+    pub user_id: String,
+    pub rank: i32,
+    pub score: f64,
+    pub penalty: i32,
+    pub elapsed_second: i64,
+    pub is_rated: bool,
+    /// The rating before the contest.
+    pub old_rating: i32,
+}
+
+/// The result of a user in a rated contest.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct RatingHistoryEntry {
+    pub user_id: String,
+    pub contest_id: String,
+    pub is_rated: bool,
+    pub place: i32,
+    pub old_rating: i32,
+    pub new_rating: i32,
+    pub performance: i32,
+    pub inner_performance: i32,
+    pub end_epoch_second: i64,
+}
+
 #[derive(Debug, Eq, PartialEq, Serialize)]
 pub struct UserLanguageCount {
     pub user_id: String,
@@ -111,3 +182,30 @@ pub struct UserStreak {
     pub user_id: String,
     pub streak: i64,
 }
+
+/// A run of consecutive days in JST with a new AC, where the days are counted from the epoch.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct StreakInterval {
+    pub start_day: i64,
+    pub last_day: i64,
+}
+
+impl StreakInterval {
+    pub fn streak(&self) -> i64 {
+        self.last_day - self.start_day + 1
+    }
+
+    /// Whether the streak can still be continued on the day.
+    pub fn is_current(&self, today: i64) -> bool {
+        self.last_day + 1 >= today
+    }
+}
+
+/// The numbers of the submissions of a user on a day in JST, counted from the epoch.
+#[derive(PartialEq, Eq, Debug)]
+pub struct DailyActivity {
+    pub day: i64,
+    pub submission_count: i64,
+    pub accepted_count: i64,
+    pub new_accepted_count: i64,
+}
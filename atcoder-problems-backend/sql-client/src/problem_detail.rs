@@ -0,0 +1,86 @@
+use crate::models::Problem;
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+#[async_trait]
+pub trait ProblemDetailClient {
+    /// Returns the problems whose score or limits are missing and whose pages have not been
+    /// scraped, the ones which have not failed recently first.
+    async fn load_problems_without_detail(&self, limit: i64) -> Result<Vec<Problem>>;
+    /// Stores the score and the limits scraped from the page of the problem.
+    async fn update_problem_detail(&self, problem: &Problem, epoch_second: i64) -> Result<()>;
+    async fn record_problem_detail_failure(
+        &self,
+        problem_id: &str,
+        epoch_second: i64,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl ProblemDetailClient for PgPool {
+    async fn load_problems_without_detail(&self, limit: i64) -> Result<Vec<Problem>> {
+        let problems = sqlx::query(
+            r"
+            SELECT id, contest_id, title, problem_index, score, time_limit_ms, memory_limit_kb
+            FROM problems
+            WHERE detail_fetched_epoch_second IS NULL
+            AND (score IS NULL OR time_limit_ms IS NULL OR memory_limit_kb IS NULL)
+            ORDER BY detail_failed_epoch_second NULLS FIRST, id
+            LIMIT $1
+            ",
+        )
+        .bind(limit)
+        .try_map(|row: PgRow| {
+            Ok(Problem {
+                id: row.try_get("id")?,
+                contest_id: row.try_get("contest_id")?,
+                title: row.try_get("title")?,
+                problem_index: row.try_get("problem_index")?,
+                score: row.try_get("score")?,
+                time_limit_ms: row.try_get("time_limit_ms")?,
+                memory_limit_kb: row.try_get("memory_limit_kb")?,
+            })
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(problems)
+    }
+
+    async fn update_problem_detail(&self, problem: &Problem, epoch_second: i64) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE problems
+            SET
+                score = $2,
+                time_limit_ms = $3,
+                memory_limit_kb = $4,
+                detail_fetched_epoch_second = $5
+            WHERE id = $1
+            ",
+        )
+        .bind(&problem.id)
+        .bind(problem.score)
+        .bind(problem.time_limit_ms)
+        .bind(problem.memory_limit_kb)
+        .bind(epoch_second)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_problem_detail_failure(
+        &self,
+        problem_id: &str,
+        epoch_second: i64,
+    ) -> Result<()> {
+        sqlx::query("UPDATE problems SET detail_failed_epoch_second = $2 WHERE id = $1")
+            .bind(problem_id)
+            .bind(epoch_second)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+}
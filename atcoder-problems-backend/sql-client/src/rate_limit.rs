@@ -0,0 +1,94 @@
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use std::time::Duration;
+
+#[async_trait]
+pub trait RateLimitClient {
+    /// Takes a token from the bucket `name`, which refills `requests_per_second` tokens per second
+    /// up to `burst`, and returns how long to wait before using it.
+    ///
+    /// A token is reserved even if the bucket is empty, so the caller must not retry after waiting.
+    async fn reserve_rate_limit_token(
+        &self,
+        name: &str,
+        requests_per_second: f64,
+        burst: f64,
+    ) -> Result<Duration>;
+
+    /// Empties the bucket `name` so that the next token is reserved after `duration`, e.g. as told
+    /// by `Retry-After`. A longer pause which is already in effect is kept.
+    async fn pause_rate_limit_bucket(
+        &self,
+        name: &str,
+        requests_per_second: f64,
+        burst: f64,
+        duration: Duration,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl RateLimitClient for PgPool {
+    async fn reserve_rate_limit_token(
+        &self,
+        name: &str,
+        requests_per_second: f64,
+        burst: f64,
+    ) -> Result<Duration> {
+        // The clock of the database is used so that the clocks of the processes may differ.
+        let tokens = sqlx::query(
+            r"
+            INSERT INTO rate_limit_buckets AS b (name, tokens, updated_at)
+            VALUES ($1, $3::FLOAT8 - 1, clock_timestamp())
+            ON CONFLICT (name) DO UPDATE SET
+                tokens = LEAST(
+                    $3::FLOAT8,
+                    b.tokens + EXTRACT(EPOCH FROM clock_timestamp() - b.updated_at)::FLOAT8 * $2::FLOAT8
+                ) - 1,
+                updated_at = clock_timestamp()
+            RETURNING tokens
+            ",
+        )
+        .bind(name)
+        .bind(requests_per_second)
+        .bind(burst)
+        .try_map(|row: PgRow| row.try_get::<f64, _>("tokens"))
+        .fetch_one(self)
+        .await?;
+
+        let wait = (-tokens).max(0.0) / requests_per_second;
+        Ok(Duration::from_secs_f64(wait))
+    }
+
+    async fn pause_rate_limit_bucket(
+        &self,
+        name: &str,
+        requests_per_second: f64,
+        burst: f64,
+        duration: Duration,
+    ) -> Result<()> {
+        // The next reservation takes the last token, which becomes available after `duration`.
+        sqlx::query(
+            r"
+            INSERT INTO rate_limit_buckets AS b (name, tokens, updated_at)
+            VALUES ($1, 1 - $4::FLOAT8 * $2::FLOAT8, clock_timestamp())
+            ON CONFLICT (name) DO UPDATE SET
+                tokens = LEAST(
+                    $3::FLOAT8,
+                    b.tokens + EXTRACT(EPOCH FROM clock_timestamp() - b.updated_at)::FLOAT8 * $2::FLOAT8,
+                    1 - $4::FLOAT8 * $2::FLOAT8
+                ),
+                updated_at = clock_timestamp()
+            ",
+        )
+        .bind(name)
+        .bind(requests_per_second)
+        .bind(burst)
+        .bind(duration.as_secs_f64())
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
@@ -76,7 +76,7 @@ impl RatedPointSumClient for PgPool {
             })
             .into_iter()
             .map(|(user_id, set)| {
-                let sum = set.into_iter().map(|(_, point)| point).sum::<i64>();
+                let sum = set.into_values().sum::<i64>();
                 (user_id, sum)
             })
             .collect::<Vec<_>>();
@@ -0,0 +1,186 @@
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+/// A verdict changed by a rejudge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejudge {
+    pub submission_id: i64,
+    pub detected_epoch_second: i64,
+    pub contest_id: String,
+    pub problem_id: String,
+    pub user_id: String,
+    pub old_result: String,
+    pub new_result: String,
+    pub old_point: f64,
+    pub new_point: f64,
+}
+
+#[async_trait]
+pub trait RejudgeClient {
+    /// Records the rejudges, and marks their users for recomputation.
+    async fn record_rejudges(&self, rejudges: &[Rejudge]) -> Result<()>;
+    async fn load_rejudges(&self, from_epoch_second: i64) -> Result<Vec<Rejudge>>;
+
+    /// Returns the users marked for recomputation with the time they were marked.
+    async fn load_users_to_recompute(&self) -> Result<Vec<(String, i64)>>;
+
+    /// Unmarks the users, unless they have been marked again after `marked_epoch_second`.
+    async fn unmark_users_to_recompute(
+        &self,
+        user_ids: &[String],
+        marked_epoch_second: i64,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl RejudgeClient for PgPool {
+    async fn record_rejudges(&self, rejudges: &[Rejudge]) -> Result<()> {
+        let submission_ids = rejudges.iter().map(|r| r.submission_id).collect::<Vec<_>>();
+        let detected_epoch_seconds = rejudges
+            .iter()
+            .map(|r| r.detected_epoch_second)
+            .collect::<Vec<_>>();
+        let contest_ids = rejudges
+            .iter()
+            .map(|r| r.contest_id.as_str())
+            .collect::<Vec<_>>();
+        let problem_ids = rejudges
+            .iter()
+            .map(|r| r.problem_id.as_str())
+            .collect::<Vec<_>>();
+        let user_ids = rejudges
+            .iter()
+            .map(|r| r.user_id.as_str())
+            .collect::<Vec<_>>();
+        let old_results = rejudges
+            .iter()
+            .map(|r| r.old_result.as_str())
+            .collect::<Vec<_>>();
+        let new_results = rejudges
+            .iter()
+            .map(|r| r.new_result.as_str())
+            .collect::<Vec<_>>();
+        let old_points = rejudges.iter().map(|r| r.old_point).collect::<Vec<_>>();
+        let new_points = rejudges.iter().map(|r| r.new_point).collect::<Vec<_>>();
+
+        let mut tx = self.begin().await?;
+        sqlx::query(
+            r"
+            INSERT INTO submission_rejudges (
+                submission_id, detected_epoch_second, contest_id, problem_id, user_id,
+                old_result, new_result, old_point, new_point
+            )
+            VALUES (
+                UNNEST($1::BIGINT[]),
+                UNNEST($2::BIGINT[]),
+                UNNEST($3::VARCHAR(255)[]),
+                UNNEST($4::VARCHAR(255)[]),
+                UNNEST($5::VARCHAR(255)[]),
+                UNNEST($6::VARCHAR(255)[]),
+                UNNEST($7::VARCHAR(255)[]),
+                UNNEST($8::DOUBLE PRECISION[]),
+                UNNEST($9::DOUBLE PRECISION[])
+            )
+            ON CONFLICT DO NOTHING
+            ",
+        )
+        .bind(submission_ids)
+        .bind(&detected_epoch_seconds)
+        .bind(contest_ids)
+        .bind(problem_ids)
+        .bind(&user_ids)
+        .bind(old_results)
+        .bind(new_results)
+        .bind(old_points)
+        .bind(new_points)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query(
+            r"
+            INSERT INTO users_to_recompute (user_id, marked_epoch_second)
+            SELECT user_id, MAX(marked_epoch_second)
+            FROM UNNEST($1::VARCHAR(255)[], $2::BIGINT[]) AS u (user_id, marked_epoch_second)
+            GROUP BY user_id
+            ON CONFLICT (user_id) DO UPDATE
+            SET marked_epoch_second = GREATEST(
+                users_to_recompute.marked_epoch_second,
+                EXCLUDED.marked_epoch_second
+            )
+            ",
+        )
+        .bind(&user_ids)
+        .bind(&detected_epoch_seconds)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_rejudges(&self, from_epoch_second: i64) -> Result<Vec<Rejudge>> {
+        let rejudges = sqlx::query(
+            r"
+            SELECT
+                submission_id, detected_epoch_second, contest_id, problem_id, user_id,
+                old_result, new_result, old_point, new_point
+            FROM submission_rejudges
+            WHERE detected_epoch_second >= $1
+            ORDER BY detected_epoch_second, submission_id
+            ",
+        )
+        .bind(from_epoch_second)
+        .try_map(|row: PgRow| {
+            Ok(Rejudge {
+                submission_id: row.try_get("submission_id")?,
+                detected_epoch_second: row.try_get("detected_epoch_second")?,
+                contest_id: row.try_get("contest_id")?,
+                problem_id: row.try_get("problem_id")?,
+                user_id: row.try_get("user_id")?,
+                old_result: row.try_get("old_result")?,
+                new_result: row.try_get("new_result")?,
+                old_point: row.try_get("old_point")?,
+                new_point: row.try_get("new_point")?,
+            })
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(rejudges)
+    }
+
+    async fn load_users_to_recompute(&self) -> Result<Vec<(String, i64)>> {
+        let users = sqlx::query(
+            r"
+            SELECT user_id, marked_epoch_second FROM users_to_recompute
+            ORDER BY user_id
+            ",
+        )
+        .try_map(|row: PgRow| {
+            let user_id: String = row.try_get("user_id")?;
+            let marked_epoch_second: i64 = row.try_get("marked_epoch_second")?;
+            Ok((user_id, marked_epoch_second))
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(users)
+    }
+
+    async fn unmark_users_to_recompute(
+        &self,
+        user_ids: &[String],
+        marked_epoch_second: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            DELETE FROM users_to_recompute
+            WHERE user_id = ANY($1) AND marked_epoch_second <= $2
+            ",
+        )
+        .bind(user_ids)
+        .bind(marked_epoch_second)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::PgPool;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, Row};
+
+/// The first key of the advisory locks, which separates them from other advisory locks.
+const JOB_LOCK_NAMESPACE: &str = "scheduler_jobs";
+
+/// The last results of a scheduled job.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct JobStatus {
+    pub job_name: String,
+    pub last_started_epoch_second: Option<i64>,
+    pub last_success_epoch_second: Option<i64>,
+    pub last_error_epoch_second: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// A session-level advisory lock held on a connection taken from the pool.
+///
+/// Pooled connections are reused instead of being closed, so a lock dropped without `release`
+/// closes its connection, which ends the session and the lock with it.
+pub struct JobLock {
+    conn: Option<PoolConnection<Postgres>>,
+    job_name: String,
+}
+
+impl JobLock {
+    /// Takes the lock of `job_name`, or returns `None` if another session holds it.
+    pub async fn try_acquire(pool: &PgPool, job_name: &str) -> Result<Option<Self>> {
+        let mut conn = pool.acquire().await?;
+        let locked: bool =
+            sqlx::query("SELECT pg_try_advisory_lock(hashtext($1), hashtext($2)) AS locked")
+                .bind(JOB_LOCK_NAMESPACE)
+                .bind(job_name)
+                .try_map(|row: PgRow| row.try_get("locked"))
+                .fetch_one(&mut conn)
+                .await?;
+        if locked {
+            Ok(Some(Self {
+                conn: Some(conn),
+                job_name: job_name.to_string(),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn release(mut self) -> Result<()> {
+        if let Some(conn) = self.conn.as_mut() {
+            sqlx::query("SELECT pg_advisory_unlock(hashtext($1), hashtext($2))")
+                .bind(JOB_LOCK_NAMESPACE)
+                .bind(&self.job_name)
+                .execute(conn)
+                .await?;
+        }
+        // The connection goes back to the pool only after the lock is released.
+        self.conn.take();
+        Ok(())
+    }
+}
+
+impl Drop for JobLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+#[async_trait]
+pub trait JobStatusClient {
+    async fn record_job_started(&self, job_name: &str, epoch_second: i64) -> Result<()>;
+    async fn record_job_success(&self, job_name: &str, epoch_second: i64) -> Result<()>;
+    async fn record_job_error(&self, job_name: &str, epoch_second: i64, error: &str) -> Result<()>;
+    async fn load_job_statuses(&self) -> Result<Vec<JobStatus>>;
+}
+
+#[async_trait]
+impl JobStatusClient for PgPool {
+    async fn record_job_started(&self, job_name: &str, epoch_second: i64) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO scheduler_jobs (job_name, last_started_epoch_second)
+            VALUES ($1, $2)
+            ON CONFLICT (job_name)
+            DO UPDATE SET last_started_epoch_second = EXCLUDED.last_started_epoch_second
+            ",
+        )
+        .bind(job_name)
+        .bind(epoch_second)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_job_success(&self, job_name: &str, epoch_second: i64) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO scheduler_jobs (job_name, last_success_epoch_second)
+            VALUES ($1, $2)
+            ON CONFLICT (job_name)
+            DO UPDATE SET last_success_epoch_second = EXCLUDED.last_success_epoch_second
+            ",
+        )
+        .bind(job_name)
+        .bind(epoch_second)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_job_error(&self, job_name: &str, epoch_second: i64, error: &str) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO scheduler_jobs (job_name, last_error_epoch_second, last_error)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (job_name)
+            DO UPDATE SET
+                last_error_epoch_second = EXCLUDED.last_error_epoch_second,
+                last_error = EXCLUDED.last_error
+            ",
+        )
+        .bind(job_name)
+        .bind(epoch_second)
+        .bind(error)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn load_job_statuses(&self) -> Result<Vec<JobStatus>> {
+        let statuses = sqlx::query(
+            r"
+            SELECT
+                job_name,
+                last_started_epoch_second,
+                last_success_epoch_second,
+                last_error_epoch_second,
+                last_error
+            FROM scheduler_jobs
+            ORDER BY job_name
+            ",
+        )
+        .try_map(|row: PgRow| {
+            Ok(JobStatus {
+                job_name: row.try_get("job_name")?,
+                last_started_epoch_second: row.try_get("last_started_epoch_second")?,
+                last_success_epoch_second: row.try_get("last_success_epoch_second")?,
+                last_error_epoch_second: row.try_get("last_error_epoch_second")?,
+                last_error: row.try_get("last_error")?,
+            })
+        })
+        .fetch_all(self)
+        .await?;
+        Ok(statuses)
+    }
+}
@@ -11,6 +11,7 @@ pub trait SimpleClient {
     async fn insert_problems(&self, values: &[Problem]) -> Result<usize>;
     async fn load_problems(&self) -> Result<Vec<Problem>>;
     async fn load_contests(&self) -> Result<Vec<Contest>>;
+    async fn load_contests_ending_after(&self, epoch_second: i64) -> Result<Vec<Contest>>;
 }
 
 #[async_trait]
@@ -68,31 +69,48 @@ impl SimpleClient for PgPool {
     }
 
     async fn insert_problems(&self, values: &[Problem]) -> Result<usize> {
-        let (ids, contest_ids, titles) = values.iter().fold(
-            (vec![], vec![], vec![]),
-            |(mut ids, mut contest_ids, mut titles), cur| {
-                ids.push(cur.id.as_str());
-                contest_ids.push(cur.contest_id.as_str());
-                titles.push(cur.title.as_str());
-                (ids, contest_ids, titles)
-            },
-        );
+        let ids = values.iter().map(|p| p.id.as_str()).collect::<Vec<_>>();
+        let contest_ids = values
+            .iter()
+            .map(|p| p.contest_id.as_str())
+            .collect::<Vec<_>>();
+        let titles = values.iter().map(|p| p.title.as_str()).collect::<Vec<_>>();
+        let problem_indexes = values
+            .iter()
+            .map(|p| p.problem_index.as_str())
+            .collect::<Vec<_>>();
+        let scores = values.iter().map(|p| p.score).collect::<Vec<_>>();
+        let time_limits = values.iter().map(|p| p.time_limit_ms).collect::<Vec<_>>();
+        let memory_limits = values.iter().map(|p| p.memory_limit_kb).collect::<Vec<_>>();
 
+        // The metadata which failed to be scraped does not overwrite the stored one.
         let result = sqlx::query(
             r"
             INSERT INTO problems
-            (id, contest_id, title)
+            (id, contest_id, title, problem_index, score, time_limit_ms, memory_limit_kb)
             VALUES (
                 UNNEST($1::VARCHAR(255)[]),
                 UNNEST($2::VARCHAR(255)[]),
-                UNNEST($3::VARCHAR(255)[])
+                UNNEST($3::VARCHAR(255)[]),
+                UNNEST($4::VARCHAR(255)[]),
+                UNNEST($5::DOUBLE PRECISION[]),
+                UNNEST($6::INTEGER[]),
+                UNNEST($7::INTEGER[])
             )
-            ON CONFLICT DO NOTHING
+            ON CONFLICT (id) DO UPDATE SET
+                problem_index = EXCLUDED.problem_index,
+                score = COALESCE(EXCLUDED.score, problems.score),
+                time_limit_ms = COALESCE(EXCLUDED.time_limit_ms, problems.time_limit_ms),
+                memory_limit_kb = COALESCE(EXCLUDED.memory_limit_kb, problems.memory_limit_kb)
             ",
         )
         .bind(ids)
         .bind(contest_ids)
         .bind(titles)
+        .bind(problem_indexes)
+        .bind(scores)
+        .bind(time_limits)
+        .bind(memory_limits)
         .execute(self)
         .await?;
 
@@ -100,19 +118,25 @@ impl SimpleClient for PgPool {
     }
 
     async fn load_problems(&self) -> Result<Vec<Problem>> {
-        let problems = sqlx::query("SELECT id, contest_id, title FROM problems")
-            .try_map(|row: PgRow| {
-                let id: String = row.try_get("id")?;
-                let contest_id: String = row.try_get("contest_id")?;
-                let title: String = row.try_get("title")?;
-                Ok(Problem {
-                    id,
-                    contest_id,
-                    title,
-                })
+        let problems = sqlx::query(
+            r"
+            SELECT id, contest_id, title, problem_index, score, time_limit_ms, memory_limit_kb
+            FROM problems
+            ",
+        )
+        .try_map(|row: PgRow| {
+            Ok(Problem {
+                id: row.try_get("id")?,
+                contest_id: row.try_get("contest_id")?,
+                title: row.try_get("title")?,
+                problem_index: row.try_get("problem_index")?,
+                score: row.try_get("score")?,
+                time_limit_ms: row.try_get("time_limit_ms")?,
+                memory_limit_kb: row.try_get("memory_limit_kb")?,
             })
-            .fetch_all(self)
-            .await?;
+        })
+        .fetch_all(self)
+        .await?;
         Ok(problems)
     }
 
@@ -128,22 +152,45 @@ impl SimpleClient for PgPool {
                  FROM contests
                  ",
         )
-        .try_map(|row: PgRow| {
-            let id: String = row.try_get("id")?;
-            let start_epoch_second: i64 = row.try_get("start_epoch_second")?;
-            let duration_second: i64 = row.try_get("duration_second")?;
-            let title: String = row.try_get("title")?;
-            let rate_change: String = row.try_get("rate_change")?;
-            Ok(Contest {
+        .try_map(map_contest)
+        .fetch_all(self)
+        .await?;
+        Ok(contests)
+    }
+
+    async fn load_contests_ending_after(&self, epoch_second: i64) -> Result<Vec<Contest>> {
+        let contests = sqlx::query(
+            r"
+            SELECT
                 id,
                 start_epoch_second,
                 duration_second,
                 title,
-                rate_change,
-            })
-        })
+                rate_change
+            FROM contests
+            WHERE start_epoch_second + duration_second >= $1
+            ORDER BY start_epoch_second, id
+            ",
+        )
+        .bind(epoch_second)
+        .try_map(map_contest)
         .fetch_all(self)
         .await?;
         Ok(contests)
     }
 }
+
+fn map_contest(row: PgRow) -> sqlx::Result<Contest> {
+    let id: String = row.try_get("id")?;
+    let start_epoch_second: i64 = row.try_get("start_epoch_second")?;
+    let duration_second: i64 = row.try_get("duration_second")?;
+    let title: String = row.try_get("title")?;
+    let rate_change: String = row.try_get("rate_change")?;
+    Ok(Contest {
+        id,
+        start_epoch_second,
+        duration_second,
+        title,
+        rate_change,
+    })
+}
@@ -1,4 +1,4 @@
-use crate::models::{Submission, UserStreak};
+use crate::models::{StreakInterval, Submission, UserStreak};
 use crate::{PgPool, MAX_INSERT_ROWS};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -19,6 +19,18 @@ pub trait StreakClient {
     async fn get_users_streak_count(&self, user_id: &str) -> Option<i64>;
     async fn get_streak_count_rank(&self, streak_count: i64) -> Result<i64>;
     async fn update_streak_count(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Loads the users in the range of the ranking of the current streaks on the day in JST.
+    async fn load_current_streak_count_in_range(
+        &self,
+        rank_range: Range<usize>,
+        today: i64,
+    ) -> Result<Vec<UserStreak>>;
+    async fn get_users_latest_streak(&self, user_id: &str) -> Result<Option<StreakInterval>>;
+    async fn get_current_streak_count_rank(&self, streak_count: i64, today: i64) -> Result<i64>;
+
+    /// Computes all the streaks of the user from the first AC submission of each problem.
+    async fn load_streak_intervals(&self, user_id: &str) -> Result<Vec<StreakInterval>>;
 }
 
 #[async_trait]
@@ -102,16 +114,21 @@ impl StreakClient for PgPool {
             },
         );
 
-        let user_max_streak = first_ac_map
+        let user_streaks = first_ac_map
             .into_iter()
             .map(|(user_id, m)| {
-                let max_streak = get_max_streak(m.into_iter().map(|(_, utc)| utc).collect());
-                (user_id, max_streak)
+                let first_acs = m.into_values().collect::<Vec<_>>();
+                let latest_streak = get_streak_intervals(first_acs.clone())
+                    .pop()
+                    .expect("a user has at least one AC");
+                let max_streak = get_max_streak(first_acs);
+                (user_id, max_streak, latest_streak)
             })
             .collect::<Vec<_>>();
 
-        for chunk in user_max_streak.chunks(MAX_INSERT_ROWS) {
-            let (user_ids, max_streaks): (Vec<&str>, Vec<i64>) = chunk.iter().copied().unzip();
+        for chunk in user_streaks.chunks(MAX_INSERT_ROWS) {
+            let user_ids = chunk.iter().map(|s| s.0).collect::<Vec<_>>();
+            let max_streaks = chunk.iter().map(|s| s.1).collect::<Vec<_>>();
             sqlx::query(
                 r"
                 INSERT INTO max_streaks (user_id, streak)
@@ -123,14 +140,131 @@ impl StreakClient for PgPool {
                 DO UPDATE SET streak = EXCLUDED.streak
                 ",
             )
-            .bind(user_ids)
+            .bind(&user_ids)
             .bind(max_streaks)
             .execute(self)
             .await?;
+
+            let latest_streaks = chunk.iter().map(|s| s.2.streak()).collect::<Vec<_>>();
+            let start_days = chunk.iter().map(|s| s.2.start_day).collect::<Vec<_>>();
+            let last_days = chunk.iter().map(|s| s.2.last_day).collect::<Vec<_>>();
+            sqlx::query(
+                r"
+                INSERT INTO latest_streaks (user_id, streak, start_day, last_day)
+                VALUES (
+                    UNNEST($1::VARCHAR(255)[]),
+                    UNNEST($2::BIGINT[]),
+                    UNNEST($3::BIGINT[]),
+                    UNNEST($4::BIGINT[])
+                )
+                ON CONFLICT (user_id)
+                DO UPDATE SET
+                    streak = EXCLUDED.streak,
+                    start_day = EXCLUDED.start_day,
+                    last_day = EXCLUDED.last_day
+                ",
+            )
+            .bind(&user_ids)
+            .bind(latest_streaks)
+            .bind(start_days)
+            .bind(last_days)
+            .execute(self)
+            .await?;
         }
 
         Ok(())
     }
+
+    async fn load_current_streak_count_in_range(
+        &self,
+        rank_range: Range<usize>,
+        today: i64,
+    ) -> Result<Vec<UserStreak>> {
+        let users_streaks = sqlx::query(
+            r"
+            SELECT user_id, streak FROM latest_streaks
+            WHERE last_day >= $1
+            ORDER BY streak DESC, user_id ASC
+            OFFSET $2 LIMIT $3;
+            ",
+        )
+        .bind(today - 1)
+        .bind(rank_range.start as i32)
+        .bind(rank_range.len() as i32)
+        .try_map(|row: PgRow| {
+            let user_id: String = row.try_get("user_id")?;
+            let streak: i64 = row.try_get("streak")?;
+            Ok(UserStreak { user_id, streak })
+        })
+        .fetch_all(self)
+        .await?;
+
+        Ok(users_streaks)
+    }
+
+    async fn get_users_latest_streak(&self, user_id: &str) -> Result<Option<StreakInterval>> {
+        let streak = sqlx::query(
+            r"
+            SELECT start_day, last_day FROM latest_streaks
+            WHERE LOWER(user_id) = LOWER($1)
+            ",
+        )
+        .bind(user_id)
+        .try_map(|row: PgRow| {
+            let start_day: i64 = row.try_get("start_day")?;
+            let last_day: i64 = row.try_get("last_day")?;
+            Ok(StreakInterval {
+                start_day,
+                last_day,
+            })
+        })
+        .fetch_optional(self)
+        .await?;
+
+        Ok(streak)
+    }
+
+    async fn get_current_streak_count_rank(&self, streak_count: i64, today: i64) -> Result<i64> {
+        let rank = sqlx::query(
+            r"
+            SELECT COUNT(*) AS rank
+            FROM latest_streaks
+            WHERE last_day >= $1 AND streak > $2
+            ",
+        )
+        .bind(today - 1)
+        .bind(streak_count)
+        .try_map(|row: PgRow| row.try_get::<i64, _>("rank"))
+        .fetch_one(self)
+        .await?;
+
+        Ok(rank)
+    }
+
+    async fn load_streak_intervals(&self, user_id: &str) -> Result<Vec<StreakInterval>> {
+        let first_acs = sqlx::query(
+            r"
+            SELECT MIN(epoch_second) AS epoch_second FROM submissions
+            WHERE LOWER(user_id) = LOWER($1) AND result = 'AC'
+            GROUP BY problem_id
+            ",
+        )
+        .bind(user_id)
+        .try_map(|row: PgRow| row.try_get::<i64, _>("epoch_second"))
+        .fetch_all(self)
+        .await?;
+
+        let first_acs = first_acs
+            .into_iter()
+            .map(|epoch_second| Utc.timestamp(epoch_second, 0))
+            .collect();
+        Ok(get_streak_intervals(first_acs))
+    }
+}
+
+/// Returns the number of the days in JST since the epoch.
+pub fn jst_day(epoch_second: i64) -> i64 {
+    Utc.timestamp(epoch_second, 0).day_in_jst()
 }
 
 fn get_max_streak<Tz: TimeZone>(mut v: Vec<DateTime<Tz>>) -> i64 {
@@ -147,8 +281,30 @@ fn get_max_streak<Tz: TimeZone>(mut v: Vec<DateTime<Tz>>) -> i64 {
     max_streak
 }
 
+/// Splits the days in JST of the timestamps into the runs of consecutive days, in order.
+fn get_streak_intervals<Tz: TimeZone>(mut v: Vec<DateTime<Tz>>) -> Vec<StreakInterval> {
+    v.sort();
+    let mut intervals: Vec<StreakInterval> = vec![];
+    for day in v.into_iter().map(|t| t.day_in_jst()) {
+        match intervals.last_mut() {
+            Some(interval) if day <= interval.last_day + 1 => interval.last_day = day,
+            _ => intervals.push(StreakInterval {
+                start_day: day,
+                last_day: day,
+            }),
+        }
+    }
+    intervals
+}
+
 trait AsJst {
     fn as_jst(&self) -> DateTime<FixedOffset>;
+    fn day_in_jst(&self) -> i64 {
+        self.as_jst()
+            .naive_local()
+            .timestamp()
+            .div_euclid(24 * 3600)
+    }
     fn is_same_day_in_jst<T: TimeZone>(&self, rhs: &DateTime<T>) -> bool {
         let d1 = self.as_jst();
         let d2 = rhs.as_jst();
@@ -173,10 +329,10 @@ mod tests {
     #[test]
     fn test_to_jst() {
         let dt_10_04 = Utc.timestamp(1570114800, 0); //2019-10-04T00:00:00+09:00
-        assert_eq!(dt_10_04.as_jst().day(), 04);
+        assert_eq!(dt_10_04.as_jst().day(), 4);
 
         let dt_10_03 = Utc.timestamp(1570114799, 0); //2019-10-03T23:59:59+09:00
-        assert_eq!(dt_10_03.as_jst().day(), 03);
+        assert_eq!(dt_10_03.as_jst().day(), 3);
 
         let tomorrow = dt_10_03 + Duration::days(1);
         assert!((tomorrow).is_same_day_in_jst(&dt_10_04));
@@ -204,4 +360,34 @@ mod tests {
         let streak = get_max_streak(v);
         assert_eq!(streak, 4);
     }
+
+    #[test]
+    fn test_get_streak_intervals() {
+        let v = vec![
+            "2019-10-03T23:59:59+09:00",
+            "2019-10-04T00:00:00+09:00",
+            "2019-10-04T23:00:00+09:00",
+            "2019-10-07T10:00:00+09:00",
+            "2019-10-05T10:00:00+09:00",
+        ]
+        .into_iter()
+        .map(|s| s.parse::<DateTime<Utc>>().unwrap())
+        .collect::<Vec<_>>();
+        let oct_3 = jst_day(1570114799);
+        assert_eq!(
+            get_streak_intervals(v),
+            vec![
+                StreakInterval {
+                    start_day: oct_3,
+                    last_day: oct_3 + 2,
+                },
+                StreakInterval {
+                    start_day: oct_3 + 4,
+                    last_day: oct_3 + 4,
+                },
+            ]
+        );
+        assert_eq!(jst_day(1570114800), oct_3 + 1);
+        assert!(get_streak_intervals::<Utc>(vec![]).is_empty());
+    }
 }
@@ -1,12 +1,64 @@
 use crate::models::Submission;
+use crate::submission_listener::notify_submissions;
 use crate::{PgPool, PgRow};
 use anyhow::Result;
 use async_trait::async_trait;
-use sqlx::Row;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use sqlx::postgres::PgArguments;
+use sqlx::query::QueryAs;
+use sqlx::{Postgres, Row};
+use std::fmt;
 use std::ops::Range;
+use std::str::FromStr;
 
 const SUBMISSION_LIMIT: i64 = 10000;
 
+/// The advisory lock which the writers share while they stamp the submissions with `update_seq`.
+pub(crate) const UPDATE_SEQ_LOCK: &str = "submission_update_seq";
+
+/// The position right after a submission in the order of `(epoch_second, id)`, which the paging
+/// APIs hand out as an opaque string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionCursor {
+    pub epoch_second: i64,
+    pub id: i64,
+}
+
+impl SubmissionCursor {
+    /// The position before all the submissions.
+    const START: SubmissionCursor = SubmissionCursor {
+        epoch_second: i64::MIN,
+        id: i64::MIN,
+    };
+
+    pub fn after(submission: &Submission) -> Self {
+        Self {
+            epoch_second: submission.epoch_second,
+            id: submission.id,
+        }
+    }
+}
+
+impl fmt::Display for SubmissionCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.epoch_second, self.id)
+    }
+}
+
+impl FromStr for SubmissionCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (epoch_second, id) = s
+            .split_once('_')
+            .ok_or_else(|| anyhow::anyhow!("Invalid cursor: {}", s))?;
+        Ok(Self {
+            epoch_second: epoch_second.parse()?,
+            id: id.parse()?,
+        })
+    }
+}
+
 pub enum SubmissionRequest<'a> {
     UserAll {
         user_id: &'a str,
@@ -16,11 +68,13 @@ pub enum SubmissionRequest<'a> {
     },
     FromTime {
         from_second: i64,
+        after: Option<SubmissionCursor>,
         count: i64,
     },
     FromUserAndTime {
         user_id: &'a str,
         from_second: i64,
+        after: Option<SubmissionCursor>,
         count: usize,
     },
     RecentAccepted {
@@ -48,6 +102,22 @@ pub enum SubmissionRequest<'a> {
 pub trait SubmissionClient {
     async fn get_submissions<'a>(&self, request: SubmissionRequest<'a>) -> Result<Vec<Submission>>;
     async fn update_submissions(&self, values: &[Submission]) -> Result<usize>;
+
+    /// Streams the submissions without holding all of them in memory.
+    ///
+    /// The default implementation collects `get_submissions`.
+    fn stream_submissions<'a>(
+        &'a self,
+        request: SubmissionRequest<'a>,
+    ) -> BoxStream<'a, Result<Submission>>
+    where
+        Self: Sync,
+    {
+        stream::once(self.get_submissions(request))
+            .map_ok(|submissions| stream::iter(submissions.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
     async fn count_stored_submissions(&self, ids: &[i64]) -> Result<usize> {
         let submissions = self
             .get_submissions(SubmissionRequest::ByIds { ids })
@@ -61,126 +131,20 @@ pub trait SubmissionClient {
 #[async_trait]
 impl SubmissionClient for PgPool {
     async fn get_submissions<'a>(&self, request: SubmissionRequest<'a>) -> Result<Vec<Submission>> {
-        let submissions = match request {
-            SubmissionRequest::UserAll { user_id } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE LOWER(user_id) = LOWER($1)
-                    ",
-            )
-            .bind(user_id)
-            .fetch_all(self),
-            SubmissionRequest::FromTime { from_second, count } => sqlx::query_as(
-                r"
-                         SELECT * FROM submissions
-                         WHERE epoch_second >= $1
-                         ORDER BY epoch_second ASC
-                         LIMIT $2
-                         ",
-            )
-            .bind(from_second)
-            .bind(count)
-            .fetch_all(self),
-            SubmissionRequest::FromUserAndTime {
-                user_id,
-                from_second,
-                count,
-            } => sqlx::query_as(
-                r"
-                         SELECT * FROM submissions
-                         WHERE LOWER(user_id) = LOWER($1)
-                         AND epoch_second >= $2
-                         ORDER BY epoch_second ASC
-                         LIMIT $3
-                         ",
-            )
-            .bind(user_id)
-            .bind(from_second)
-            .bind(count as i64)
-            .fetch_all(self),
-            SubmissionRequest::RecentAccepted { count } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE result = 'AC'
-                    ORDER BY id DESC
-                    LIMIT $1
-                    ",
-            )
-            .bind(count)
-            .fetch_all(self),
-            SubmissionRequest::RecentAll { count } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    ORDER BY id DESC
-                    LIMIT $1
-                    ",
-            )
-            .bind(count)
-            .fetch_all(self),
-            SubmissionRequest::UsersAccepted { user_ids } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE result = 'AC'
-                    AND user_id = ANY($1)
-                    ",
-            )
-            .bind(user_ids)
-            .fetch_all(self),
-            SubmissionRequest::AllAccepted => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE result = 'AC'
-                    ",
-            )
-            .fetch_all(self),
-            SubmissionRequest::InvalidResult { from_second } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE 
-                        result != ALL(
-                            ARRAY['AC', 'WA', 'TLE', 'CE', 'RE', 'MLE', 'OLE', 'QLE', 'IE', 'NG']
-                        )
-                    AND 
-                        epoch_second >= $1
-                    ORDER BY id DESC
-                    ",
-            )
-            .bind(from_second)
-            .fetch_all(self),
-            SubmissionRequest::ByIds { ids } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE id = ANY($1)
-                    ",
-            )
-            .bind(ids)
-            .fetch_all(self),
-            SubmissionRequest::UsersProblemsTime {
-                user_ids,
-                problem_ids,
-                from_second,
-                to_second,
-            } => sqlx::query_as(
-                r"
-                    SELECT * FROM submissions
-                    WHERE user_id = ANY($1)
-                    AND problem_id = ANY($2)
-                    AND epoch_second >= $3
-                    AND epoch_second <= $4
-                    LIMIT $5
-                    ",
-            )
-            .bind(user_ids)
-            .bind(problem_ids)
-            .bind(from_second)
-            .bind(to_second)
-            .bind(SUBMISSION_LIMIT)
-            .fetch_all(self),
-        }
-        .await?;
+        let submissions = build_query(request).fetch_all(self).await?;
         Ok(submissions)
     }
 
+    fn stream_submissions<'a>(
+        &'a self,
+        request: SubmissionRequest<'a>,
+    ) -> BoxStream<'a, Result<Submission>> {
+        build_query(request)
+            .fetch(self)
+            .map_err(anyhow::Error::from)
+            .boxed()
+    }
+
     async fn update_submissions(&self, values: &[Submission]) -> Result<usize> {
         let (
             ids,
@@ -244,6 +208,11 @@ impl SubmissionClient for PgPool {
                 )
             },
         );
+        let mut tx = self.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock_shared(hashtext($1))")
+            .bind(UPDATE_SEQ_LOCK)
+            .execute(&mut tx)
+            .await?;
         let count = sqlx::query(
             r"
             INSERT INTO submissions
@@ -276,7 +245,14 @@ impl SubmissionClient for PgPool {
                 user_id = EXCLUDED.user_id,
                 result = EXCLUDED.result,
                 point = EXCLUDED.point,
-                execution_time = EXCLUDED.execution_time
+                execution_time = EXCLUDED.execution_time,
+                update_seq = CASE
+                    WHEN submissions.user_id IS DISTINCT FROM EXCLUDED.user_id
+                        OR submissions.result IS DISTINCT FROM EXCLUDED.result
+                        OR submissions.point IS DISTINCT FROM EXCLUDED.point
+                    THEN EXCLUDED.update_seq
+                    ELSE submissions.update_seq
+                END
             ",
         )
         .bind(ids)
@@ -289,8 +265,11 @@ impl SubmissionClient for PgPool {
         .bind(lengths)
         .bind(results)
         .bind(execution_times)
-        .execute(self)
+        .execute(&mut tx)
         .await?;
+        // Notifications are delivered on commit, so the listeners can read the stored rows.
+        notify_submissions(&mut tx, values).await?;
+        tx.commit().await?;
         Ok(count.rows_affected() as usize)
     }
 
@@ -312,3 +291,129 @@ impl SubmissionClient for PgPool {
         Ok(count as usize)
     }
 }
+
+fn build_query(request: SubmissionRequest<'_>) -> QueryAs<'_, Postgres, Submission, PgArguments> {
+    match request {
+        SubmissionRequest::UserAll { user_id } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE LOWER(user_id) = LOWER($1)
+                ",
+        )
+        .bind(user_id),
+        SubmissionRequest::FromTime {
+            from_second,
+            after,
+            count,
+        } => {
+            let after = after.unwrap_or(SubmissionCursor::START);
+            sqlx::query_as(
+                r"
+                SELECT * FROM submissions
+                WHERE epoch_second >= $1
+                AND (epoch_second, id) > ($2, $3)
+                ORDER BY epoch_second ASC, id ASC
+                LIMIT $4
+                ",
+            )
+            .bind(from_second)
+            .bind(after.epoch_second)
+            .bind(after.id)
+            .bind(count)
+        }
+        SubmissionRequest::FromUserAndTime {
+            user_id,
+            from_second,
+            after,
+            count,
+        } => {
+            let after = after.unwrap_or(SubmissionCursor::START);
+            sqlx::query_as(
+                r"
+                SELECT * FROM submissions
+                WHERE LOWER(user_id) = LOWER($1)
+                AND epoch_second >= $2
+                AND (epoch_second, id) > ($3, $4)
+                ORDER BY epoch_second ASC, id ASC
+                LIMIT $5
+                ",
+            )
+            .bind(user_id)
+            .bind(from_second)
+            .bind(after.epoch_second)
+            .bind(after.id)
+            .bind(count as i64)
+        }
+        SubmissionRequest::RecentAccepted { count } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE result = 'AC'
+                ORDER BY id DESC
+                LIMIT $1
+                ",
+        )
+        .bind(count),
+        SubmissionRequest::RecentAll { count } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                ORDER BY id DESC
+                LIMIT $1
+                ",
+        )
+        .bind(count),
+        SubmissionRequest::UsersAccepted { user_ids } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE result = 'AC'
+                AND user_id = ANY($1)
+                ",
+        )
+        .bind(user_ids),
+        SubmissionRequest::AllAccepted => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE result = 'AC'
+                ",
+        ),
+        SubmissionRequest::InvalidResult { from_second } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE 
+                    result != ALL(
+                        ARRAY['AC', 'WA', 'TLE', 'CE', 'RE', 'MLE', 'OLE', 'QLE', 'IE', 'NG']
+                    )
+                AND 
+                    epoch_second >= $1
+                ORDER BY id DESC
+                ",
+        )
+        .bind(from_second),
+        SubmissionRequest::ByIds { ids } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE id = ANY($1)
+                ",
+        )
+        .bind(ids),
+        SubmissionRequest::UsersProblemsTime {
+            user_ids,
+            problem_ids,
+            from_second,
+            to_second,
+        } => sqlx::query_as(
+            r"
+                SELECT * FROM submissions
+                WHERE user_id = ANY($1)
+                AND problem_id = ANY($2)
+                AND epoch_second >= $3
+                AND epoch_second <= $4
+                LIMIT $5
+                ",
+        )
+        .bind(user_ids)
+        .bind(problem_ids)
+        .bind(from_second)
+        .bind(to_second)
+        .bind(SUBMISSION_LIMIT),
+    }
+}
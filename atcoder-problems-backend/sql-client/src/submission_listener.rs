@@ -0,0 +1,88 @@
+use crate::models::Submission;
+use crate::submission_client::{SubmissionClient, SubmissionRequest};
+use crate::PgPool;
+use anyhow::Result;
+use sqlx::postgres::PgListener;
+use sqlx::{Postgres, Transaction};
+
+/// The channel which `update_submissions` notifies of the ids of the stored submissions.
+pub const SUBMISSION_CHANNEL: &str = "submissions_updated";
+
+/// A NOTIFY payload must be shorter than 8000 bytes.
+const MAX_PAYLOAD_BYTES: usize = 7900;
+
+/// Notifies the listeners of the submission ids as JSON arrays split to fit in a payload.
+///
+/// The notifications are sent when `tx` commits.
+pub(crate) async fn notify_submissions(
+    tx: &mut Transaction<'_, Postgres>,
+    submissions: &[Submission],
+) -> Result<()> {
+    if submissions.is_empty() {
+        return Ok(());
+    }
+
+    let mut payloads = vec![];
+    let mut chunk: Vec<String> = vec![];
+    let mut chunk_bytes = 2;
+    for submission in submissions {
+        let id = submission.id.to_string();
+        if !chunk.is_empty() && chunk_bytes + id.len() + 1 > MAX_PAYLOAD_BYTES {
+            payloads.push(format!("[{}]", chunk.join(",")));
+            chunk.clear();
+            chunk_bytes = 2;
+        }
+        chunk_bytes += id.len() + 1;
+        chunk.push(id);
+    }
+    if !chunk.is_empty() {
+        payloads.push(format!("[{}]", chunk.join(",")));
+    }
+
+    sqlx::query("SELECT pg_notify($1, payload) FROM UNNEST($2::TEXT[]) AS payload")
+        .bind(SUBMISSION_CHANNEL)
+        .bind(payloads)
+        .execute(tx)
+        .await?;
+    Ok(())
+}
+
+pub struct SubmissionListener {
+    pool: PgPool,
+    listener: PgListener,
+}
+
+impl SubmissionListener {
+    pub async fn connect(pool: &PgPool) -> Result<Self> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(SUBMISSION_CHANNEL).await?;
+        Ok(Self {
+            pool: pool.clone(),
+            listener,
+        })
+    }
+
+    /// Waits for the next batch of stored submissions, ordered by id.
+    /// Notifications raised while the connection is lost are dropped.
+    pub async fn recv(&mut self) -> Result<Vec<Submission>> {
+        let ids = self.recv_ids().await?;
+        self.load(&ids).await
+    }
+
+    /// Waits for the ids of the next batch of stored submissions without loading them.
+    pub async fn recv_ids(&mut self) -> Result<Vec<i64>> {
+        let notification = self.listener.recv().await?;
+        let ids = serde_json::from_str(notification.payload())?;
+        Ok(ids)
+    }
+
+    /// Loads the submissions of the ids received by `recv_ids`, ordered by id.
+    pub async fn load(&self, ids: &[i64]) -> Result<Vec<Submission>> {
+        let mut submissions = self
+            .pool
+            .get_submissions(SubmissionRequest::ByIds { ids })
+            .await?;
+        submissions.sort_by_key(|s| s.id);
+        Ok(submissions)
+    }
+}
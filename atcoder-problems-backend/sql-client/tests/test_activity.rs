@@ -0,0 +1,76 @@
+use sql_client::activity::ActivityClient;
+use sql_client::models::DailyActivity;
+use sql_client::streak::jst_day;
+
+mod utils;
+
+#[tokio::test]
+async fn test_load_daily_activities() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    sqlx::query(
+        r"
+        INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+        (1, 1570114799, 'problem_a', '', 'user1', '', 0, 0, 'WA'), -- 2019-10-03T23:59:59+09:00
+        (2, 1570114800, 'problem_a', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-04T00:00:00+09:00
+        (3, 1570150800, 'problem_a', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-04T10:00:00+09:00
+        (4, 1570150800, 'problem_b', '', 'user1', '', 0, 0, 'TLE'), -- 2019-10-04T10:00:00+09:00
+        (5, 1570201199, 'problem_b', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-04T23:59:59+09:00
+        (6, 1570287600, 'problem_b', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-06T00:00:00+09:00
+        (7, 1570287600, 'problem_c', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-06T00:00:00+09:00
+        (8, 1570150800, 'problem_c', '', 'user2', '', 0, 0, 'AC'); -- 2019-10-04T10:00:00+09:00
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let oct_3 = jst_day(1570114799);
+    let activities = pool
+        .load_daily_activities("USER1", oct_3..(oct_3 + 10))
+        .await
+        .unwrap();
+    assert_eq!(
+        activities,
+        vec![
+            DailyActivity {
+                day: oct_3,
+                submission_count: 1,
+                accepted_count: 0,
+                new_accepted_count: 0,
+            },
+            DailyActivity {
+                day: oct_3 + 1,
+                submission_count: 4,
+                accepted_count: 3,
+                new_accepted_count: 2,
+            },
+            DailyActivity {
+                day: oct_3 + 3,
+                submission_count: 2,
+                accepted_count: 2,
+                new_accepted_count: 1,
+            },
+        ]
+    );
+
+    // The first AC of problem_b before the range is not a new AC in the range.
+    let activities = pool
+        .load_daily_activities("user1", (oct_3 + 2)..(oct_3 + 4))
+        .await
+        .unwrap();
+    assert_eq!(
+        activities,
+        vec![DailyActivity {
+            day: oct_3 + 3,
+            submission_count: 2,
+            accepted_count: 2,
+            new_accepted_count: 1,
+        }]
+    );
+
+    assert!(pool
+        .load_daily_activities("user3", oct_3..(oct_3 + 10))
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,382 @@
+use sql_client::accepted_count::AcceptedCountClient;
+use sql_client::aggregate::AggregateClient;
+use sql_client::contest_problem::ContestProblemClient;
+use sql_client::language_count::LanguageCountClient;
+use sql_client::models::{Contest, ContestProblem, Submission};
+use sql_client::rated_point_sum::RatedPointSumClient;
+use sql_client::simple_client::SimpleClient;
+use sql_client::streak::StreakClient;
+use sql_client::submission_client::SubmissionClient;
+use sql_client::{PgPool, PgRow, Row};
+use std::time::Duration;
+
+mod utils;
+
+const DAY: i64 = 24 * 60 * 60;
+const RATED_START: i64 = 1_500_000_000;
+
+fn submission(
+    id: i64,
+    user_id: &str,
+    problem_id: &str,
+    language: &str,
+    result: &str,
+    day: i64,
+) -> Submission {
+    Submission {
+        id,
+        epoch_second: RATED_START + day * DAY,
+        problem_id: problem_id.to_string(),
+        contest_id: "contest".to_string(),
+        user_id: user_id.to_string(),
+        language: language.to_string(),
+        point: 100.0,
+        result: result.to_string(),
+        ..Default::default()
+    }
+}
+
+async fn insert_rated_problems(pool: &PgPool) {
+    pool.insert_contests(&[
+        Contest {
+            id: "rated".to_string(),
+            start_epoch_second: RATED_START,
+            rate_change: "All".to_string(),
+            ..Default::default()
+        },
+        Contest {
+            id: "unrated".to_string(),
+            start_epoch_second: RATED_START,
+            rate_change: "-".to_string(),
+            ..Default::default()
+        },
+    ])
+    .await
+    .unwrap();
+    pool.insert_contest_problem(&[
+        ContestProblem {
+            contest_id: "rated".to_string(),
+            problem_id: "problem1".to_string(),
+        },
+        ContestProblem {
+            contest_id: "rated".to_string(),
+            problem_id: "problem2".to_string(),
+        },
+        ContestProblem {
+            contest_id: "unrated".to_string(),
+            problem_id: "problem3".to_string(),
+        },
+    ])
+    .await
+    .unwrap();
+}
+
+type Aggregates = (
+    Vec<(String, i32)>,
+    Vec<(String, String, i32)>,
+    Vec<(String, i64)>,
+    Vec<(String, i64)>,
+    Vec<(String, i64, i64, i64)>,
+);
+
+async fn load_aggregates(pool: &PgPool) -> Aggregates {
+    let accepted_count = sql_client::query("SELECT * FROM accepted_count ORDER BY user_id")
+        .map(|row: PgRow| (row.get("user_id"), row.get("problem_count")))
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    let language_count =
+        sql_client::query("SELECT * FROM language_count ORDER BY user_id, simplified_language")
+            .map(|row: PgRow| {
+                (
+                    row.get("user_id"),
+                    row.get("simplified_language"),
+                    row.get("problem_count"),
+                )
+            })
+            .fetch_all(pool)
+            .await
+            .unwrap();
+    let rated_point_sum = sql_client::query("SELECT * FROM rated_point_sum ORDER BY user_id")
+        .map(|row: PgRow| (row.get("user_id"), row.get("point_sum")))
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    let max_streaks = sql_client::query("SELECT * FROM max_streaks ORDER BY user_id")
+        .map(|row: PgRow| (row.get("user_id"), row.get("streak")))
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    let latest_streaks = sql_client::query("SELECT * FROM latest_streaks ORDER BY user_id")
+        .map(|row: PgRow| {
+            (
+                row.get("user_id"),
+                row.get("streak"),
+                row.get("start_day"),
+                row.get("last_day"),
+            )
+        })
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    (
+        accepted_count,
+        language_count,
+        rated_point_sum,
+        max_streaks,
+        latest_streaks,
+    )
+}
+
+async fn clear_aggregates(pool: &PgPool) {
+    for table in [
+        "accepted_count",
+        "language_count",
+        "rated_point_sum",
+        "max_streaks",
+        "latest_streaks",
+    ] {
+        sql_client::query(&format!("DELETE FROM {}", table))
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_same_as_full_recomputation() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    insert_rated_problems(&pool).await;
+    let submissions = vec![
+        submission(1, "user1", "problem1", "C++ (GCC 9.2.1)", "AC", 0),
+        submission(2, "user1", "problem1", "Rust (1.42.0)", "AC", 1),
+        submission(3, "user1", "problem2", "Rust (1.42.0)", "AC", 2),
+        submission(4, "user1", "problem3", "Perl6 (rakudo)", "AC", 4),
+        submission(5, "user1", "problem1", "C++ (GCC 9.2.1)", "AC", 5),
+        submission(6, "user2", "problem1", "PyPy3 (7.3.0)", "WA", 0),
+        submission(7, "user2", "problem2", "PyPy3 (7.3.0)", "AC", 0),
+        submission(8, "user2", "problem3", "Python3 (3.8.2)", "AC", 1),
+        submission(9, "user3", "problem1", "Haxe (4.0.3); js", "WA", 0),
+    ];
+    pool.update_submissions(&submissions).await.unwrap();
+
+    let accepted = submissions
+        .iter()
+        .filter(|s| s.result == "AC")
+        .cloned()
+        .collect::<Vec<_>>();
+    pool.update_accepted_count(&accepted).await.unwrap();
+    pool.update_language_count(&accepted, &[]).await.unwrap();
+    pool.update_rated_point_sum(&accepted).await.unwrap();
+    pool.update_streak_count(&accepted).await.unwrap();
+    let expected = load_aggregates(&pool).await;
+    assert_eq!(
+        expected.0,
+        vec![("user1".to_string(), 3), ("user2".to_string(), 2)]
+    );
+    let first_day = (RATED_START + 9 * 3600) / DAY;
+    assert_eq!(
+        expected.4,
+        vec![
+            ("user1".to_string(), 1, first_day + 4, first_day + 4),
+            ("user2".to_string(), 2, first_day, first_day + 1),
+        ]
+    );
+
+    clear_aggregates(&pool).await;
+    pool.update_aggregates(&[], 2).await.unwrap();
+    assert_eq!(load_aggregates(&pool).await, expected);
+
+    clear_aggregates(&pool).await;
+    pool.rebuild_all_aggregates(&[]).await.unwrap();
+    assert_eq!(load_aggregates(&pool).await, expected);
+}
+
+#[tokio::test]
+async fn test_non_integer_rated_point() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    insert_rated_problems(&pool).await;
+    let submissions = vec![
+        Submission {
+            point: 100.5,
+            ..submission(1, "user1", "problem1", "Rust (1.42.0)", "AC", 0)
+        },
+        Submission {
+            point: 200.0,
+            ..submission(2, "user1", "problem1", "C++ (GCC 9.2.1)", "AC", 1)
+        },
+    ];
+    pool.update_submissions(&submissions).await.unwrap();
+
+    assert!(pool.update_rated_point_sum(&submissions).await.is_err());
+    assert!(
+        pool.rebuild_all_aggregates(&[]).await.is_err(),
+        "A non-integer rated point should be rejected as update_rated_point_sum does."
+    );
+    assert!(pool.update_aggregates(&[], 2).await.is_err());
+
+    let submissions = vec![Submission {
+        point: 100.0,
+        ..submissions[0].clone()
+    }];
+    pool.update_submissions(&submissions).await.unwrap();
+    pool.update_aggregates(&[], 2).await.unwrap();
+    let (_, _, rated_point_sum, _, _) = load_aggregates(&pool).await;
+    assert_eq!(
+        rated_point_sum,
+        vec![("user1".to_string(), 100)],
+        "The point of the first AC should count, not the largest one."
+    );
+}
+
+#[tokio::test]
+async fn test_update_aggregates_incrementally() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    insert_rated_problems(&pool).await;
+    assert_eq!(pool.load_aggregate_watermark().await.unwrap(), None);
+
+    pool.update_submissions(&[submission(10, "user1", "problem1", "Rust", "AC", 0)])
+        .await
+        .unwrap();
+    pool.update_aggregates(&["excluded"], 100).await.unwrap();
+    let watermark = pool.load_aggregate_watermark().await.unwrap().unwrap();
+    assert_eq!(pool.get_users_accepted_count("user1").await, Some(1));
+
+    // Submissions with smaller ids are stored later by the crawlers.
+    pool.update_submissions(&[
+        submission(5, "user1", "problem2", "Rust", "AC", 1),
+        submission(6, "user2", "problem1", "Rust", "AC", 0),
+        submission(7, "excluded", "problem1", "Rust", "AC", 0),
+    ])
+    .await
+    .unwrap();
+    assert_eq!(pool.update_aggregates(&["excluded"], 100).await.unwrap(), 3);
+    assert!(pool.load_aggregate_watermark().await.unwrap().unwrap() > watermark);
+    assert_eq!(pool.get_users_accepted_count("user1").await, Some(2));
+    assert_eq!(pool.get_users_accepted_count("user2").await, Some(1));
+    assert_eq!(pool.get_users_accepted_count("excluded").await, None);
+    assert_eq!(pool.get_users_rated_point_sum("user1").await, Some(200));
+    assert_eq!(pool.get_users_streak_count("user1").await, Some(2));
+
+    // Storing the same submissions again does not stamp them.
+    pool.update_submissions(&[submission(6, "user2", "problem1", "Rust", "AC", 0)])
+        .await
+        .unwrap();
+    assert_eq!(pool.update_aggregates(&["excluded"], 100).await.unwrap(), 0);
+
+    // The first AC of user1 for problem2 has been rejudged.
+    pool.update_submissions(&[submission(5, "user1", "problem2", "Rust", "WA", 1)])
+        .await
+        .unwrap();
+    pool.update_aggregates(&["excluded"], 100).await.unwrap();
+    assert_eq!(pool.get_users_accepted_count("user1").await, Some(1));
+    assert_eq!(pool.get_users_streak_count("user1").await, Some(1));
+
+    // The only AC of user2 has been rejudged.
+    pool.update_submissions(&[submission(6, "user2", "problem1", "Rust", "WA", 0)])
+        .await
+        .unwrap();
+    pool.update_aggregates(&["excluded"], 100).await.unwrap();
+    assert_eq!(pool.get_users_accepted_count("user2").await, None);
+    assert_eq!(pool.get_users_rated_point_sum("user2").await, None);
+}
+
+#[tokio::test]
+async fn test_update_aggregates_with_concurrent_writers() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.update_aggregates(&[], 100).await.unwrap();
+
+    // Holds the id of the slow writer, which stamps its submission and waits for this.
+    let mut blocker = pool.begin().await.unwrap();
+    sql_client::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES (1, 0, 'problem1', 'contest', 'blocker', 'Rust', 0, 0, 'WA')
+        ",
+    )
+    .execute(&mut blocker)
+    .await
+    .unwrap();
+    let slow_pool = pool.clone();
+    let slow_writer = tokio::spawn(async move {
+        slow_pool
+            .update_submissions(&[submission(1, "slow", "problem1", "Rust", "AC", 0)])
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The fast writer commits a larger stamp before the slow one.
+    pool.update_submissions(&[submission(2, "fast", "problem1", "Rust", "AC", 0)])
+        .await
+        .unwrap();
+    let aggregate_pool = pool.clone();
+    let aggregator = tokio::spawn(async move { aggregate_pool.update_aggregates(&[], 100).await });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    blocker.rollback().await.unwrap();
+    slow_writer.await.unwrap().unwrap();
+    aggregator.await.unwrap().unwrap();
+    pool.update_aggregates(&[], 100).await.unwrap();
+    assert_eq!(pool.get_users_accepted_count("fast").await, Some(1));
+    assert_eq!(
+        pool.get_users_accepted_count("slow").await,
+        Some(1),
+        "The submission committed late with a smaller stamp should be processed."
+    );
+}
+
+#[tokio::test]
+async fn test_rebuild_user_aggregates() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.update_submissions(&[
+        submission(1, "user1", "problem1", "Rust", "AC", 0),
+        submission(2, "user2", "problem1", "Rust", "AC", 0),
+    ])
+    .await
+    .unwrap();
+    pool.update_aggregates(&[], 100).await.unwrap();
+    clear_aggregates(&pool).await;
+
+    pool.rebuild_user_aggregates(&["user1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(pool.get_users_accepted_count("user1").await, Some(1));
+    assert_eq!(pool.get_users_accepted_count("user2").await, None);
+}
+
+#[tokio::test]
+async fn test_simplified_language() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let languages = [
+        ("language1", "language1"),
+        ("Perl (5)", "Perl"),
+        ("Perl6", "Raku"),
+        ("Fortran(GNU Fortran 9.2.1)", "Fortran"),
+        ("Ada2012 (GNAT 9.2.1)", "Ada"),
+        ("PyPy2 (7.3.0)", "PyPy"),
+        ("Haxe (4.0.3); js", "Haxe; js"),
+    ];
+    let submissions = languages
+        .iter()
+        .enumerate()
+        .map(|(i, (language, _))| submission(i as i64, "user", "problem", language, "AC", 0))
+        .collect::<Vec<_>>();
+    pool.update_submissions(&submissions).await.unwrap();
+    pool.update_aggregates(&[], 100).await.unwrap();
+
+    let mut expected = languages
+        .iter()
+        .map(|(_, simplified)| simplified.to_string())
+        .collect::<Vec<_>>();
+    expected.sort();
+    let mut actual = pool
+        .load_users_language_count("user")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|count| count.simplified_language)
+        .collect::<Vec<_>>();
+    actual.sort();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,149 @@
+use sql_client::contest_result::ContestResultClient;
+use sql_client::models::{ContestStanding, RatingHistoryEntry};
+
+mod utils;
+
+fn standing(contest_id: &str, user_id: &str, rank: i32) -> ContestStanding {
+    ContestStanding {
+        contest_id: contest_id.to_string(),
+        user_id: user_id.to_string(),
+        rank,
+        score: 100.0,
+        penalty: 0,
+        elapsed_second: 60,
+        is_rated: true,
+        old_rating: 1200,
+    }
+}
+
+fn history(user_id: &str, contest_id: &str, end_epoch_second: i64) -> RatingHistoryEntry {
+    RatingHistoryEntry {
+        user_id: user_id.to_string(),
+        contest_id: contest_id.to_string(),
+        is_rated: true,
+        place: 10,
+        old_rating: 1200,
+        new_rating: 1250,
+        performance: 1500,
+        inner_performance: 1500,
+        end_epoch_second,
+    }
+}
+
+#[tokio::test]
+async fn test_contest_standings() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    assert!(pool.load_standings_contest_ids().await.unwrap().is_empty());
+
+    pool.update_contest_standings(
+        "contest1",
+        &[
+            standing("contest1", "user2", 2),
+            standing("contest1", "user1", 1),
+        ],
+    )
+    .await
+    .unwrap();
+    pool.update_contest_standings("contest2", &[standing("contest2", "user1", 5)])
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_contest_standings("contest1").await.unwrap(),
+        vec![
+            standing("contest1", "user1", 1),
+            standing("contest1", "user2", 2)
+        ]
+    );
+    assert_eq!(
+        pool.load_user_standings("user1").await.unwrap(),
+        vec![
+            standing("contest1", "user1", 1),
+            standing("contest2", "user1", 5)
+        ]
+    );
+    assert_eq!(
+        pool.load_standings_contest_ids().await.unwrap(),
+        vec!["contest1", "contest2"]
+    );
+
+    pool.update_contest_standings("contest1", &[standing("contest1", "user3", 1)])
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_contest_standings("contest1").await.unwrap(),
+        vec![standing("contest1", "user3", 1)],
+        "The standings should be replaced."
+    );
+}
+
+#[tokio::test]
+async fn test_rating_history() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    assert!(pool.load_rating_history("user1").await.unwrap().is_empty());
+
+    pool.update_rating_history(
+        "user1",
+        &[
+            history("user1", "contest2", 200),
+            history("user1", "contest1", 100),
+        ],
+    )
+    .await
+    .unwrap();
+    pool.update_rating_history("user2", &[history("user2", "contest1", 100)])
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_rating_history("user1").await.unwrap(),
+        vec![
+            history("user1", "contest1", 100),
+            history("user1", "contest2", 200)
+        ]
+    );
+
+    pool.update_rating_history("user1", &[history("user1", "contest3", 300)])
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_rating_history("user1").await.unwrap(),
+        vec![history("user1", "contest3", 300)]
+    );
+    assert_eq!(pool.load_rating_history("user2").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_rated_users_without_history() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let mut unrated = standing("contest1", "user3", 3);
+    unrated.is_rated = false;
+    pool.update_contest_standings(
+        "contest1",
+        &[
+            standing("contest1", "user1", 1),
+            standing("contest1", "user2", 2),
+            unrated,
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        pool.load_rated_users_without_history("contest1")
+            .await
+            .unwrap(),
+        vec!["user1", "user2"]
+    );
+
+    pool.update_rating_history("user1", &[history("user1", "contest1", 100)])
+        .await
+        .unwrap();
+    pool.update_rating_history("user2", &[history("user2", "contest0", 50)])
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_rated_users_without_history("contest1")
+            .await
+            .unwrap(),
+        vec!["user2"],
+        "The users whose history includes the contest should be skipped."
+    );
+}
@@ -0,0 +1,61 @@
+use sql_client::crawl_checkpoint::{CrawlCheckpoint, CrawlCheckpointClient};
+use sql_client::models::Contest;
+
+mod utils;
+
+#[tokio::test]
+async fn test_crawl_checkpoint() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    assert_eq!(pool.load_crawl_checkpoint("contest").await.unwrap(), None);
+
+    pool.save_crawl_progress("contest", 1).await.unwrap();
+    pool.save_crawl_progress("contest", 2).await.unwrap();
+    assert_eq!(
+        pool.load_crawl_checkpoint("contest").await.unwrap(),
+        Some(CrawlCheckpoint {
+            contest_id: "contest".to_string(),
+            last_page: 2,
+            finished_epoch_second: None,
+        })
+    );
+
+    pool.finish_crawl("contest", 1000).await.unwrap();
+    let checkpoint = pool
+        .load_crawl_checkpoint("contest")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(checkpoint.last_page, 0);
+    assert_eq!(checkpoint.finished_epoch_second, Some(1000));
+
+    let contest = Contest {
+        id: "contest".to_string(),
+        start_epoch_second: 0,
+        duration_second: 1000,
+        title: "".to_string(),
+        rate_change: "".to_string(),
+    };
+    assert!(checkpoint.is_complete(&contest));
+    let running_contest = Contest {
+        duration_second: 2000,
+        ..contest
+    };
+    assert!(!checkpoint.is_complete(&running_contest));
+
+    pool.save_crawl_progress("contest", 1).await.unwrap();
+    let checkpoint = pool
+        .load_crawl_checkpoint("contest")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(checkpoint.finished_epoch_second, None);
+
+    pool.save_crawl_progress("other", 1).await.unwrap();
+    assert_eq!(pool.load_crawl_checkpoints().await.unwrap().len(), 2);
+    pool.reset_crawl_checkpoints(&["contest".to_string()])
+        .await
+        .unwrap();
+    let checkpoints = pool.load_crawl_checkpoints().await.unwrap();
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].contest_id, "other");
+}
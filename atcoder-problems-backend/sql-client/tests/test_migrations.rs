@@ -0,0 +1,54 @@
+use sql_client::{create_indexes_concurrently, run_migrations, MIGRATOR};
+
+mod utils;
+
+#[tokio::test]
+async fn test_run_migrations() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    // Applying the migrations again should be a no-op.
+    run_migrations(&pool).await.unwrap();
+
+    let versions: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    let expected = MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .collect::<Vec<_>>();
+    assert_eq!(versions, expected);
+}
+
+#[tokio::test]
+async fn test_create_indexes_concurrently() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    create_indexes_concurrently(&pool).await.unwrap();
+    // Creating the indexes again should be a no-op.
+    create_indexes_concurrently(&pool).await.unwrap();
+
+    let indexes: Vec<String> = sqlx::query_scalar(
+        r"
+        SELECT c.relname::TEXT
+        FROM pg_index AS i
+        JOIN pg_class AS c ON c.oid = i.indexrelid
+        WHERE i.indrelid = 'submissions'::regclass AND i.indisvalid
+        ",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    for name in [
+        "submissions_update_seq_idx",
+        "submissions_epoch_second_id_idx",
+        "submissions_lower_epoch_second_id_idx",
+    ] {
+        assert!(indexes.contains(&name.to_owned()), "{} is missing", name);
+    }
+    assert!(
+        !indexes.contains(&"submissions_lower_idx".to_owned()),
+        "The superseded index should be dropped."
+    );
+}
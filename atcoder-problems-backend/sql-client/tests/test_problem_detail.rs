@@ -0,0 +1,63 @@
+use sql_client::models::Problem;
+use sql_client::problem_detail::ProblemDetailClient;
+use sql_client::simple_client::SimpleClient;
+
+mod utils;
+
+fn problem(id: &str, score: Option<f64>, time_limit_ms: Option<i32>) -> Problem {
+    Problem {
+        id: id.to_string(),
+        contest_id: "contest".to_string(),
+        title: format!("A. {}", id),
+        problem_index: "A".to_string(),
+        score,
+        time_limit_ms,
+        memory_limit_kb: Some(1024),
+    }
+}
+
+fn ids(problems: &[Problem]) -> Vec<&str> {
+    problems.iter().map(|p| p.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_problem_detail() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.insert_problems(&[
+        problem("complete", Some(100.0), Some(2000)),
+        problem("no_score", None, Some(2000)),
+        problem("no_limit", Some(100.0), None),
+        problem("no_detail", None, None),
+    ])
+    .await
+    .unwrap();
+
+    let problems = pool.load_problems_without_detail(10).await.unwrap();
+    assert_eq!(ids(&problems), vec!["no_detail", "no_limit", "no_score"]);
+    let problems = pool.load_problems_without_detail(2).await.unwrap();
+    assert_eq!(ids(&problems), vec!["no_detail", "no_limit"]);
+
+    pool.record_problem_detail_failure("no_detail", 100)
+        .await
+        .unwrap();
+    let problems = pool.load_problems_without_detail(2).await.unwrap();
+    assert_eq!(
+        ids(&problems),
+        vec!["no_limit", "no_score"],
+        "The failed problem should be retried after the others."
+    );
+
+    pool.update_problem_detail(&problem("no_limit", Some(100.0), Some(3000)), 200)
+        .await
+        .unwrap();
+    // The old problems do not state their scores.
+    pool.update_problem_detail(&problem("no_score", None, Some(2000)), 200)
+        .await
+        .unwrap();
+    let problems = pool.load_problems_without_detail(10).await.unwrap();
+    assert_eq!(ids(&problems), vec!["no_detail"]);
+
+    let stored = pool.load_problems().await.unwrap();
+    let no_limit = stored.iter().find(|p| p.id == "no_limit").unwrap();
+    assert_eq!(no_limit.time_limit_ms, Some(3000));
+}
@@ -0,0 +1,75 @@
+use sql_client::rate_limit::RateLimitClient;
+use std::time::Duration;
+
+mod utils;
+
+#[tokio::test]
+async fn test_reserve_rate_limit_token() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    let waits = [
+        pool.reserve_rate_limit_token("bucket", 1.0, 2.0)
+            .await
+            .unwrap(),
+        pool.reserve_rate_limit_token("bucket", 1.0, 2.0)
+            .await
+            .unwrap(),
+        pool.reserve_rate_limit_token("bucket", 1.0, 2.0)
+            .await
+            .unwrap(),
+        pool.reserve_rate_limit_token("bucket", 1.0, 2.0)
+            .await
+            .unwrap(),
+    ];
+    assert_eq!(waits[0], Duration::ZERO);
+    assert_eq!(waits[1], Duration::ZERO);
+    assert!(
+        Duration::from_millis(900) < waits[2] && waits[2] <= Duration::from_secs(1),
+        "The third token should be reserved about 1 second ahead, but got {:?}",
+        waits[2]
+    );
+    assert!(
+        Duration::from_millis(1900) < waits[3] && waits[3] <= Duration::from_secs(2),
+        "The fourth token should be reserved about 2 seconds ahead, but got {:?}",
+        waits[3]
+    );
+
+    assert_eq!(
+        pool.reserve_rate_limit_token("other", 1.0, 2.0)
+            .await
+            .unwrap(),
+        Duration::ZERO,
+        "Buckets should be independent of each other."
+    );
+}
+
+#[tokio::test]
+async fn test_pause_rate_limit_bucket() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    pool.pause_rate_limit_bucket("bucket", 1.0, 2.0, Duration::from_secs(30))
+        .await
+        .unwrap();
+    let wait = pool
+        .reserve_rate_limit_token("bucket", 1.0, 2.0)
+        .await
+        .unwrap();
+    assert!(
+        Duration::from_secs(29) < wait && wait <= Duration::from_secs(30),
+        "The token should be reserved after the pause, but got {:?}",
+        wait
+    );
+
+    pool.pause_rate_limit_bucket("bucket", 1.0, 2.0, Duration::from_secs(5))
+        .await
+        .unwrap();
+    let wait = pool
+        .reserve_rate_limit_token("bucket", 1.0, 2.0)
+        .await
+        .unwrap();
+    assert!(
+        wait > Duration::from_secs(29),
+        "A shorter pause should not cut the longer one, but got {:?}",
+        wait
+    );
+}
@@ -0,0 +1,78 @@
+use sql_client::rejudge::{Rejudge, RejudgeClient};
+
+mod utils;
+
+fn rejudge(submission_id: i64, user_id: &str, detected_epoch_second: i64) -> Rejudge {
+    Rejudge {
+        submission_id,
+        detected_epoch_second,
+        contest_id: "contest".to_string(),
+        problem_id: "problem".to_string(),
+        user_id: user_id.to_string(),
+        old_result: "AC".to_string(),
+        new_result: "WA".to_string(),
+        old_point: 100.0,
+        new_point: 0.0,
+    }
+}
+
+#[tokio::test]
+async fn test_record_rejudges() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    assert!(pool.load_rejudges(0).await.unwrap().is_empty());
+    assert!(pool.load_users_to_recompute().await.unwrap().is_empty());
+
+    pool.record_rejudges(&[
+        rejudge(1, "user1", 100),
+        rejudge(2, "user1", 110),
+        rejudge(3, "user2", 100),
+    ])
+    .await
+    .unwrap();
+    pool.record_rejudges(&[rejudge(1, "user1", 100)])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        pool.load_rejudges(0).await.unwrap(),
+        vec![
+            rejudge(1, "user1", 100),
+            rejudge(3, "user2", 100),
+            rejudge(2, "user1", 110)
+        ]
+    );
+    assert_eq!(
+        pool.load_rejudges(105).await.unwrap(),
+        vec![rejudge(2, "user1", 110)]
+    );
+    assert_eq!(
+        pool.load_users_to_recompute().await.unwrap(),
+        vec![("user1".to_string(), 110), ("user2".to_string(), 100)]
+    );
+}
+
+#[tokio::test]
+async fn test_unmark_users_to_recompute() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.record_rejudges(&[rejudge(1, "user1", 100), rejudge(2, "user2", 100)])
+        .await
+        .unwrap();
+    let user_ids = vec!["user1".to_string(), "user2".to_string()];
+
+    pool.record_rejudges(&[rejudge(3, "user2", 200)])
+        .await
+        .unwrap();
+    pool.unmark_users_to_recompute(&user_ids, 100)
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.load_users_to_recompute().await.unwrap(),
+        vec![("user2".to_string(), 200)],
+        "The users marked again should remain."
+    );
+
+    pool.unmark_users_to_recompute(&user_ids, 200)
+        .await
+        .unwrap();
+    assert!(pool.load_users_to_recompute().await.unwrap().is_empty());
+}
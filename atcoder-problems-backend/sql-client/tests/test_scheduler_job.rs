@@ -0,0 +1,80 @@
+use sql_client::scheduler_job::{JobLock, JobStatus, JobStatusClient};
+use std::time::Duration;
+
+mod utils;
+
+#[tokio::test]
+async fn test_job_lock() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    let lock = JobLock::try_acquire(&pool, "job").await.unwrap();
+    assert!(lock.is_some());
+    assert!(
+        JobLock::try_acquire(&pool, "job").await.unwrap().is_none(),
+        "The same job should not be locked twice."
+    );
+    let other_lock = JobLock::try_acquire(&pool, "other_job").await.unwrap();
+    assert!(other_lock.is_some());
+
+    lock.unwrap().release().await.unwrap();
+    other_lock.unwrap().release().await.unwrap();
+    let lock = JobLock::try_acquire(&pool, "job").await.unwrap();
+    assert!(lock.is_some());
+    lock.unwrap().release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_job_lock_dropped() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    let lock = JobLock::try_acquire(&pool, "job").await.unwrap();
+    drop(lock);
+
+    // Advisory locks are reentrant in a session, so the lock is checked from another pool.
+    let other_pool = sql_client::initialize_pool(std::env::var("SQL_URL").unwrap())
+        .await
+        .unwrap();
+    // Postgres releases the lock when it notices that the connection is closed.
+    for _ in 0..50 {
+        if let Some(lock) = JobLock::try_acquire(&other_pool, "job").await.unwrap() {
+            lock.release().await.unwrap();
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("The dropped lock should be released.");
+}
+
+#[tokio::test]
+async fn test_job_status() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    assert!(pool.load_job_statuses().await.unwrap().is_empty());
+
+    pool.record_job_started("job", 100).await.unwrap();
+    pool.record_job_success("job", 110).await.unwrap();
+    pool.record_job_started("job", 200).await.unwrap();
+    pool.record_job_error("job", 210, "error").await.unwrap();
+    pool.record_job_error("other_job", 300, "other error")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        pool.load_job_statuses().await.unwrap(),
+        vec![
+            JobStatus {
+                job_name: "job".to_string(),
+                last_started_epoch_second: Some(200),
+                last_success_epoch_second: Some(110),
+                last_error_epoch_second: Some(210),
+                last_error: Some("error".to_string()),
+            },
+            JobStatus {
+                job_name: "other_job".to_string(),
+                last_started_epoch_second: None,
+                last_success_epoch_second: None,
+                last_error_epoch_second: Some(300),
+                last_error: Some("other error".to_string()),
+            },
+        ]
+    );
+}
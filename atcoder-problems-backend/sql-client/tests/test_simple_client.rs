@@ -31,6 +31,30 @@ async fn test_insert_contests() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn test_load_contests_ending_after() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let contests = [
+        ("ended", 0, 100),
+        ("running", 50, 100),
+        ("upcoming", 200, 100),
+    ]
+    .iter()
+    .map(|&(id, start_epoch_second, duration_second)| Contest {
+        id: id.to_string(),
+        start_epoch_second,
+        duration_second,
+        title: "".to_string(),
+        rate_change: "".to_string(),
+    })
+    .collect::<Vec<_>>();
+    pool.insert_contests(&contests).await.unwrap();
+
+    let contests = pool.load_contests_ending_after(120).await.unwrap();
+    let ids = contests.iter().map(|c| c.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["running", "upcoming"]);
+}
+
 #[tokio::test]
 async fn test_insert_problems() {
     let pool = utils::initialize_and_connect_to_test_sql().await;
@@ -39,6 +63,7 @@ async fn test_insert_problems() {
         id: "problem1".to_string(),
         contest_id: "".to_string(),
         title: "".to_string(),
+        ..Default::default()
     }])
     .await
     .unwrap();
@@ -50,7 +75,48 @@ async fn test_insert_problems() {
         id: "problem1".to_string(),
         contest_id: "".to_string(),
         title: "".to_string(),
+        ..Default::default()
+    }])
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_insert_problem_metadata() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let problem = Problem {
+        id: "abc107_a".to_string(),
+        contest_id: "abc107".to_string(),
+        title: "A. Train".to_string(),
+        problem_index: "A".to_string(),
+        score: Some(100.0),
+        time_limit_ms: Some(2000),
+        memory_limit_kb: Some(1048576),
+    };
+    pool.insert_problems(&[problem]).await.unwrap();
+
+    pool.insert_problems(&[Problem {
+        id: "abc107_a".to_string(),
+        contest_id: "abc107".to_string(),
+        title: "A. Train".to_string(),
+        problem_index: "A".to_string(),
+        score: None,
+        time_limit_ms: Some(3000),
+        memory_limit_kb: None,
     }])
     .await
     .unwrap();
+    assert_eq!(
+        pool.load_problems().await.unwrap(),
+        vec![Problem {
+            id: "abc107_a".to_string(),
+            contest_id: "abc107".to_string(),
+            title: "A. Train".to_string(),
+            problem_index: "A".to_string(),
+            score: Some(100.0),
+            time_limit_ms: Some(3000),
+            memory_limit_kb: Some(1048576),
+        }],
+        "The missing metadata should not overwrite the stored one."
+    );
 }
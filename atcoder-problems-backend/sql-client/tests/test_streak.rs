@@ -1,5 +1,5 @@
-use sql_client::models::UserStreak;
-use sql_client::streak::StreakClient;
+use sql_client::models::{StreakInterval, UserStreak};
+use sql_client::streak::{jst_day, StreakClient};
 use sql_client::submission_client::{SubmissionClient, SubmissionRequest};
 
 mod utils;
@@ -76,3 +76,104 @@ async fn test_streak_ranking() {
         }
     );
 }
+
+#[tokio::test]
+async fn test_current_streak() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    sqlx::query(
+        r"
+        INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+        (1, 1570114800, 'problem_a', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-04T00:00:00+09:00
+        (2, 1570201200, 'problem_b', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-05T00:00:00+09:00
+        (3, 1570287600, 'problem_c', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-06T00:00:00+09:00
+        (4, 1570460400, 'problem_d', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-08T00:00:00+09:00
+        (5, 1570546800, 'problem_e', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-09T00:00:00+09:00
+        (10, 1570582800, 'problem_a', '', 'user1', '', 0, 0, 'AC'), -- 2019-10-09T10:00:00+09:00
+        (6, 1570201200, 'problem_a', '', 'user2', '', 0, 0, 'AC'), -- 2019-10-05T00:00:00+09:00
+        (7, 1570287600, 'problem_b', '', 'user2', '', 0, 0, 'AC'), -- 2019-10-06T00:00:00+09:00
+        (8, 1570373999, 'problem_c', '', 'user2', '', 0, 0, 'AC'), -- 2019-10-06T23:59:59+09:00
+        (9, 1570460400, 'problem_a', '', 'user3', '', 0, 0, 'AC'); -- 2019-10-08T00:00:00+09:00
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let submissions = pool
+        .get_submissions(SubmissionRequest::AllAccepted)
+        .await
+        .unwrap();
+    pool.update_streak_count(&submissions).await.unwrap();
+
+    let oct_4 = jst_day(1570114800);
+    assert_eq!(
+        pool.load_streak_intervals("User1").await.unwrap(),
+        vec![
+            StreakInterval {
+                start_day: oct_4,
+                last_day: oct_4 + 2,
+            },
+            StreakInterval {
+                start_day: oct_4 + 4,
+                last_day: oct_4 + 5,
+            },
+        ]
+    );
+    assert!(pool
+        .load_streak_intervals("user4")
+        .await
+        .unwrap()
+        .is_empty());
+
+    let latest = pool
+        .get_users_latest_streak("user2")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.streak(), 2);
+    assert!(latest.is_current(oct_4 + 3));
+    assert!(!latest.is_current(oct_4 + 4));
+    assert!(pool
+        .get_users_latest_streak("user4")
+        .await
+        .unwrap()
+        .is_none());
+
+    // On 2019-10-09, the streak of user2 has already been broken.
+    let today = oct_4 + 5;
+    let ranking = pool
+        .load_current_streak_count_in_range(0..10, today)
+        .await
+        .unwrap();
+    assert_eq!(
+        ranking,
+        vec![
+            UserStreak {
+                user_id: "user1".to_owned(),
+                streak: 2
+            },
+            UserStreak {
+                user_id: "user3".to_owned(),
+                streak: 1
+            },
+        ]
+    );
+    assert_eq!(
+        pool.get_current_streak_count_rank(2, today).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        pool.get_current_streak_count_rank(1, today).await.unwrap(),
+        1
+    );
+    assert_eq!(
+        pool.get_current_streak_count_rank(0, today).await.unwrap(),
+        2
+    );
+
+    let ranking = pool
+        .load_current_streak_count_in_range(0..10, today + 1)
+        .await
+        .unwrap();
+    assert_eq!(ranking.len(), 1);
+}
@@ -1,5 +1,7 @@
+use futures_util::TryStreamExt;
 use sql_client::models::Submission;
-use sql_client::submission_client::{SubmissionClient, SubmissionRequest};
+use sql_client::submission_client::{SubmissionClient, SubmissionCursor, SubmissionRequest};
+use sql_client::submission_listener::SubmissionListener;
 
 mod utils;
 
@@ -53,6 +55,7 @@ async fn test_submission_client() {
 
     let request = SubmissionRequest::FromTime {
         from_second: 100,
+        after: None,
         count: 10,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -60,6 +63,7 @@ async fn test_submission_client() {
 
     let request = SubmissionRequest::FromTime {
         from_second: 200,
+        after: None,
         count: 10,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -67,6 +71,7 @@ async fn test_submission_client() {
 
     let request = SubmissionRequest::FromTime {
         from_second: 100,
+        after: None,
         count: 1,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -75,6 +80,7 @@ async fn test_submission_client() {
     let request = SubmissionRequest::FromUserAndTime {
         user_id: "usEr1",
         from_second: 300,
+        after: None,
         count: 1000,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -85,6 +91,7 @@ async fn test_submission_client() {
     let request = SubmissionRequest::FromUserAndTime {
         user_id: "usEr1",
         from_second: 300,
+        after: None,
         count: 1,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -93,6 +100,7 @@ async fn test_submission_client() {
     let request = SubmissionRequest::FromUserAndTime {
         user_id: "user3",
         from_second: 300,
+        after: None,
         count: 1000,
     };
     let submissions = pool.get_submissions(request).await.unwrap();
@@ -128,6 +136,114 @@ async fn test_submission_client() {
     assert_eq!(submissions.len(), 1);
 }
 
+#[tokio::test]
+async fn test_submission_cursor() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    sqlx::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (5, 100, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'AC'),
+            (2, 100, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'WA'),
+            (4, 100, 'problem1', 'contest1', 'user2', 'language1', 1.0, 1, 'AC'),
+            (3, 100, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'AC'),
+            (1, 200, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'AC'),
+            (6, 300, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'AC');
+    ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut ids = vec![];
+    let mut after = None;
+    loop {
+        let request = SubmissionRequest::FromTime {
+            from_second: 100,
+            after,
+            count: 2,
+        };
+        let submissions = pool.get_submissions(request).await.unwrap();
+        ids.extend(submissions.iter().map(|s| s.id));
+        match submissions.last() {
+            Some(last) => after = Some(SubmissionCursor::after(last)),
+            None => break,
+        }
+    }
+    assert_eq!(ids, vec![2, 3, 4, 5, 1, 6]);
+
+    let mut ids = vec![];
+    let mut after = None;
+    loop {
+        let request = SubmissionRequest::FromUserAndTime {
+            user_id: "USER1",
+            from_second: 100,
+            after,
+            count: 2,
+        };
+        let submissions = pool.get_submissions(request).await.unwrap();
+        ids.extend(submissions.iter().map(|s| s.id));
+        match submissions.last() {
+            Some(last) => after = Some(SubmissionCursor::after(last)),
+            None => break,
+        }
+    }
+    assert_eq!(ids, vec![2, 3, 5, 1, 6]);
+
+    let cursor = SubmissionCursor {
+        epoch_second: 100,
+        id: 3,
+    };
+    assert_eq!(
+        cursor.to_string().parse::<SubmissionCursor>().unwrap(),
+        cursor
+    );
+    assert!("100".parse::<SubmissionCursor>().is_err());
+    assert!("a_3".parse::<SubmissionCursor>().is_err());
+}
+
+#[tokio::test]
+async fn test_stream_submissions() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    sqlx::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'AC'),
+            (2, 200, 'problem1', 'contest1', 'user2', 'language1', 1.0, 1, 'AC'),
+            (3, 300, 'problem1', 'contest1', 'user1', 'language1', 1.0, 1, 'WA'),
+            (4, 400, 'problem2', 'contest1', 'user1', 'language1', 1.0, 1, 'AC');
+    ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let requests: [fn() -> SubmissionRequest<'static>; 4] = [
+        || SubmissionRequest::UserAll { user_id: "usEr1" },
+        || SubmissionRequest::UserAll { user_id: "user3" },
+        || SubmissionRequest::FromTime {
+            from_second: 200,
+            after: None,
+            count: 10,
+        },
+        || SubmissionRequest::AllAccepted,
+    ];
+    for request in requests {
+        let expected = pool.get_submissions(request()).await.unwrap();
+        let streamed = pool
+            .stream_submissions(request())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let expected = expected.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        let streamed = streamed.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(streamed, expected);
+    }
+}
+
 #[tokio::test]
 async fn test_update_submissions() {
     let pool = utils::initialize_and_connect_to_test_sql().await;
@@ -193,3 +309,34 @@ async fn test_update_submissions() {
     assert_eq!(submissions[0].point, 100.0);
     assert_eq!(submissions[0].execution_time, Some(1));
 }
+
+#[tokio::test]
+async fn test_update_submissions_notifies_listener() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let mut listener = SubmissionListener::connect(&pool).await.unwrap();
+
+    // Large enough to be split into several notifications.
+    let submissions = (0..2000)
+        .map(|id| Submission {
+            id,
+            user_id: format!("user{}", id),
+            problem_id: "problem".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    pool.update_submissions(&submissions).await.unwrap();
+
+    let mut notified = vec![];
+    while notified.len() < submissions.len() {
+        let received = listener.recv().await.unwrap();
+        assert!(!received.is_empty());
+        notified.extend(received);
+    }
+    assert_eq!(
+        notified.iter().map(|s| s.id).collect::<Vec<_>>(),
+        (0..2000).collect::<Vec<_>>()
+    );
+    assert_eq!(notified[10].user_id, "user10");
+    assert_eq!(notified[10].result, "AC");
+}
@@ -54,3 +54,39 @@ async fn test_user_manager() {
         "`get_internal_user_info` after `atcoder_user_id` was set returned an unexpected value."
     );
 }
+
+#[tokio::test]
+async fn test_calendar_token() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.register_user("user_id").await.unwrap();
+    assert_eq!(pool.get_calendar_token("user_id").await.unwrap(), None);
+
+    let token = pool.create_calendar_token("user_id").await.unwrap();
+    assert_eq!(
+        pool.get_calendar_token("user_id").await.unwrap(),
+        Some(token.clone())
+    );
+    assert_eq!(
+        pool.get_user_by_calendar_token(&token).await.unwrap(),
+        Some("user_id".to_owned())
+    );
+
+    let rotated = pool.create_calendar_token("user_id").await.unwrap();
+    assert_ne!(token, rotated);
+    assert_eq!(
+        pool.get_user_by_calendar_token(&token).await.unwrap(),
+        None,
+        "The previous token should be revoked by rotation."
+    );
+    assert_eq!(
+        pool.get_user_by_calendar_token(&rotated).await.unwrap(),
+        Some("user_id".to_owned())
+    );
+
+    pool.delete_calendar_token("user_id").await.unwrap();
+    assert_eq!(pool.get_calendar_token("user_id").await.unwrap(), None);
+    assert_eq!(
+        pool.get_user_by_calendar_token(&rotated).await.unwrap(),
+        None
+    );
+}
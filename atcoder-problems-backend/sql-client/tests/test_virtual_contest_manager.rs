@@ -1,5 +1,6 @@
 use sql_client::internal::virtual_contest_manager::{
-    VirtualContestInfo, VirtualContestItem, VirtualContestManager, MAX_PROBLEM_NUM_PER_CONTEST,
+    VirtualContestError, VirtualContestInfo, VirtualContestItem, VirtualContestManager,
+    VirtualContestMode, MAX_PROBLEM_NUM_PER_CONTEST,
 };
 
 mod utils;
@@ -47,7 +48,7 @@ async fn test_virtual_contest_manager() {
     let memo = "memo";
     let start_epoch_second = 0;
     let duration_second = now_second.saturating_add(TIME_DELTA); // future
-    let mode = None;
+    let mode = VirtualContestMode::Normal;
     let is_public = true;
     let penalty_second = 42;
     let contest_id = pool
@@ -57,7 +58,7 @@ async fn test_virtual_contest_manager() {
             user_id,
             start_epoch_second,
             duration_second,
-            mode.as_deref(),
+            mode,
             is_public,
             penalty_second,
         )
@@ -70,9 +71,12 @@ async fn test_virtual_contest_manager() {
         owner_user_id: user_id.to_string(),
         start_epoch_second,
         duration_second,
-        mode: mode.clone(),
+        mode,
         is_public,
         penalty_second,
+        is_archived: false,
+        is_invitation_required: false,
+        is_registration_closed: false,
     };
     let created_contests = vec![created_contest.clone()];
 
@@ -159,7 +163,7 @@ async fn test_virtual_contest_manager() {
         .await;
     assert!(update_result.is_err(), "`update_items` should fail because too many problems were passed, but actually it succeeded.");
 
-    pool.join_contest(&contest_id, user_id).await.unwrap();
+    pool.join_contest(&contest_id, user_id, None).await.unwrap();
 
     let participated_contests = pool.get_participated_contests(user_id).await.unwrap();
     assert_eq!(
@@ -193,13 +197,60 @@ async fn test_virtual_contest_manager() {
     );
 
     let updated_duration_second = now_second.saturating_sub(TIME_DELTA); // past
+    let update_result = pool
+        .update_contest(
+            &contest_id,
+            "THIS_IS_ANOTHER_USER",
+            "hijacked",
+            memo,
+            start_epoch_second,
+            updated_duration_second,
+            mode,
+            is_public,
+            penalty_second,
+        )
+        .await;
+    assert_eq!(
+        update_result
+            .unwrap_err()
+            .downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "`update_contest` should fail because the wrong user id was passed, but actually it did not."
+    );
+    let update_result = pool
+        .update_contest(
+            "THIS_IS_FAKE_CONTEST_ID",
+            user_id,
+            title,
+            memo,
+            start_epoch_second,
+            updated_duration_second,
+            mode,
+            is_public,
+            penalty_second,
+        )
+        .await;
+    assert_eq!(
+        update_result
+            .unwrap_err()
+            .downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::ContestNotFound),
+        "`update_contest` should fail because the wrong contest id was passed, but actually it did not."
+    );
+    assert_eq!(
+        pool.get_single_contest_info(&contest_id).await.unwrap(),
+        created_contest,
+        "A failed update should not modify the contest."
+    );
+
     pool.update_contest(
         &contest_id,
+        user_id,
         title,
         memo,
         start_epoch_second,
         updated_duration_second,
-        mode.as_deref(),
+        mode,
         is_public,
         penalty_second,
     )
@@ -222,3 +273,413 @@ async fn test_virtual_contest_manager() {
         "`get_running_contest_problems` here should return an empty list, but got not empty."
     );
 }
+
+#[tokio::test]
+async fn test_single_contest_submissions() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    let atcoder_id = "atcoder_id";
+    utils::setup_internal_user(&pool, user_id, atcoder_id).await;
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            user_id,
+            100,
+            100,
+            VirtualContestMode::Normal,
+            true,
+            0,
+        )
+        .await
+        .unwrap();
+    let items = [VirtualContestItem {
+        id: "problem".to_string(),
+        point: None,
+        order: None,
+    }];
+    pool.update_items(&contest_id, &items, user_id)
+        .await
+        .unwrap();
+    pool.join_contest(&contest_id, user_id, None).await.unwrap();
+
+    sqlx::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 50,  'problem', 'contest', 'atcoder_id', 'Rust', 0, 0, 'WA'),
+            (2, 150, 'problem', 'contest', 'atcoder_id', 'Rust', 0, 0, 'WA'),
+            (3, 160, 'other',   'contest', 'atcoder_id', 'Rust', 0, 0, 'WA'),
+            (4, 170, 'problem', 'contest', 'other_user', 'Rust', 0, 0, 'WA'),
+            (5, 200, 'problem', 'contest', 'atcoder_id', 'Rust', 0, 0, 'AC'),
+            (6, 250, 'problem', 'contest', 'atcoder_id', 'Rust', 0, 0, 'AC')
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let submissions = pool
+        .get_single_contest_submissions(&contest_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![2, 5],
+        "Only the participants' submissions to the contest problems during the contest should be returned."
+    );
+}
+
+#[tokio::test]
+async fn test_delete_and_archive_contest() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    let other_user_id = "other_user_id";
+    utils::setup_internal_user(&pool, user_id, "atcoder_id").await;
+    utils::setup_internal_user(&pool, other_user_id, "other_atcoder_id").await;
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            user_id,
+            0,
+            100,
+            VirtualContestMode::Normal,
+            true,
+            0,
+        )
+        .await
+        .unwrap();
+    pool.join_contest(&contest_id, other_user_id, None)
+        .await
+        .unwrap();
+
+    let result = pool
+        .set_contest_archived(&contest_id, other_user_id, true)
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to archive the contest."
+    );
+
+    pool.set_contest_archived(&contest_id, user_id, true)
+        .await
+        .unwrap();
+    assert!(
+        pool.get_recent_contest_info().await.unwrap().is_empty(),
+        "An archived contest should not be listed as a recent contest."
+    );
+    let info = pool.get_single_contest_info(&contest_id).await.unwrap();
+    assert!(
+        info.is_archived,
+        "The archived contest should still be available."
+    );
+    assert_eq!(pool.get_own_contests(user_id).await.unwrap(), vec![info]);
+
+    pool.set_contest_archived(&contest_id, user_id, false)
+        .await
+        .unwrap();
+    assert_eq!(pool.get_recent_contest_info().await.unwrap().len(), 1);
+
+    let result = pool.delete_contest(&contest_id, other_user_id).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to delete the contest."
+    );
+
+    pool.delete_contest(&contest_id, user_id).await.unwrap();
+    assert!(pool.get_single_contest_info(&contest_id).await.is_err());
+    assert!(pool.get_own_contests(user_id).await.unwrap().is_empty());
+    assert!(pool
+        .get_participated_contests(other_user_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let result = pool.delete_contest(&contest_id, user_id).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::ContestNotFound),
+        "Deleting a deleted contest should fail."
+    );
+}
+
+#[tokio::test]
+async fn test_contest_mode() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    utils::setup_internal_user(&pool, user_id, "atcoder_id").await;
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            user_id,
+            0,
+            100,
+            VirtualContestMode::Lockout,
+            true,
+            0,
+        )
+        .await
+        .unwrap();
+    let info = pool.get_single_contest_info(&contest_id).await.unwrap();
+    assert_eq!(info.mode, VirtualContestMode::Lockout);
+
+    pool.update_contest(
+        &contest_id,
+        user_id,
+        "title",
+        "memo",
+        0,
+        100,
+        VirtualContestMode::Training,
+        true,
+        0,
+    )
+    .await
+    .unwrap();
+    let info = pool.get_single_contest_info(&contest_id).await.unwrap();
+    assert_eq!(info.mode, VirtualContestMode::Training);
+
+    sqlx::query("UPDATE internal_virtual_contests SET mode = 'unknown' WHERE id = $1")
+        .bind(&contest_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(
+        pool.get_single_contest_info(&contest_id).await.is_err(),
+        "An unknown mode stored in the database should not be read as a valid contest."
+    );
+
+    assert_eq!(
+        "lockout".parse::<VirtualContestMode>(),
+        Ok(VirtualContestMode::Lockout)
+    );
+    assert_eq!(
+        "normal".parse::<VirtualContestMode>(),
+        Ok(VirtualContestMode::Normal)
+    );
+    assert_eq!(
+        "unknown".parse::<VirtualContestMode>(),
+        Err(VirtualContestError::InvalidMode)
+    );
+}
+
+#[tokio::test]
+async fn test_private_contest() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    let other_user_id = "other_user_id";
+    let third_user_id = "third_user_id";
+    utils::setup_internal_user(&pool, user_id, "atcoder_id").await;
+    utils::setup_internal_user(&pool, other_user_id, "other_atcoder_id").await;
+    utils::setup_internal_user(&pool, third_user_id, "third_atcoder_id").await;
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            user_id,
+            0,
+            100,
+            VirtualContestMode::Normal,
+            true,
+            0,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.get_invite_token(&contest_id, user_id).await.unwrap(),
+        None
+    );
+
+    let result = pool.create_invite_token(&contest_id, other_user_id).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to create an invite token."
+    );
+    let invite_token = pool
+        .create_invite_token(&contest_id, user_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.get_invite_token(&contest_id, user_id).await.unwrap(),
+        Some(invite_token.clone())
+    );
+    assert!(
+        pool.get_single_contest_info(&contest_id)
+            .await
+            .unwrap()
+            .is_invitation_required
+    );
+    assert!(
+        pool.get_recent_contest_info().await.unwrap().is_empty(),
+        "A contest requiring an invitation should not be listed as a recent contest."
+    );
+
+    let result = pool.join_contest(&contest_id, other_user_id, None).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::InvalidInviteToken)
+    );
+    let result = pool
+        .join_contest(&contest_id, other_user_id, Some("wrong-token"))
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::InvalidInviteToken)
+    );
+    pool.join_contest(&contest_id, other_user_id, Some(&invite_token))
+        .await
+        .unwrap();
+    pool.join_contest(&contest_id, user_id, None).await.unwrap();
+
+    pool.set_registration_closed(&contest_id, user_id, true)
+        .await
+        .unwrap();
+    let result = pool
+        .join_contest(&contest_id, third_user_id, Some(&invite_token))
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::RegistrationClosed)
+    );
+
+    let result = pool
+        .kick_participant(&contest_id, other_user_id, "atcoder_id")
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to kick a participant."
+    );
+    pool.kick_participant(&contest_id, user_id, "other_atcoder_id")
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.get_single_contest_participants(&contest_id)
+            .await
+            .unwrap(),
+        vec!["atcoder_id"]
+    );
+
+    pool.set_registration_closed(&contest_id, user_id, false)
+        .await
+        .unwrap();
+    let result = pool
+        .join_contest(&contest_id, other_user_id, Some(&invite_token))
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::Kicked),
+        "A kicked user should not be able to join the contest again."
+    );
+    pool.delete_invite_token(&contest_id, user_id)
+        .await
+        .unwrap();
+    pool.join_contest(&contest_id, third_user_id, None)
+        .await
+        .unwrap();
+    assert_eq!(pool.get_recent_contest_info().await.unwrap().len(), 1);
+
+    let result = pool
+        .join_contest("unknown-contest", third_user_id, None)
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::ContestNotFound)
+    );
+}
+
+#[tokio::test]
+async fn test_clone_contest() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    let other_user_id = "other_user_id";
+    utils::setup_internal_user(&pool, user_id, "atcoder_id").await;
+    utils::setup_internal_user(&pool, other_user_id, "other_atcoder_id").await;
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            user_id,
+            0,
+            100,
+            VirtualContestMode::Lockout,
+            false,
+            300,
+        )
+        .await
+        .unwrap();
+    let problems = vec![
+        VirtualContestItem {
+            id: "problem_1".to_owned(),
+            point: Some(100),
+            order: Some(2),
+        },
+        VirtualContestItem {
+            id: "problem_2".to_owned(),
+            point: None,
+            order: Some(1),
+        },
+    ];
+    pool.update_items(&contest_id, &problems, user_id)
+        .await
+        .unwrap();
+    pool.join_contest(&contest_id, user_id, None).await.unwrap();
+
+    let cloned_id = pool
+        .clone_contest(&contest_id, other_user_id, 1000)
+        .await
+        .unwrap();
+    assert_ne!(cloned_id, contest_id);
+
+    let info = pool.get_single_contest_info(&cloned_id).await.unwrap();
+    assert_eq!(info.title, "title");
+    assert_eq!(info.memo, "memo");
+    assert_eq!(info.owner_user_id, other_user_id);
+    assert_eq!(info.start_epoch_second, 1000);
+    assert_eq!(info.duration_second, 100);
+    assert_eq!(info.mode, VirtualContestMode::Lockout);
+    assert!(!info.is_public);
+    assert_eq!(info.penalty_second, 300);
+
+    assert_eq!(
+        pool.get_single_contest_problems(&cloned_id).await.unwrap(),
+        pool.get_single_contest_problems(&contest_id).await.unwrap()
+    );
+    assert!(
+        pool.get_single_contest_participants(&cloned_id)
+            .await
+            .unwrap()
+            .is_empty(),
+        "Participants should not be copied."
+    );
+
+    pool.create_invite_token(&contest_id, user_id)
+        .await
+        .unwrap();
+    let result = pool.clone_contest(&contest_id, other_user_id, 1000).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to clone a contest requiring an invitation."
+    );
+    pool.clone_contest(&contest_id, user_id, 1000)
+        .await
+        .unwrap();
+
+    let result = pool.clone_contest("unknown-contest", user_id, 0).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::ContestNotFound)
+    );
+}
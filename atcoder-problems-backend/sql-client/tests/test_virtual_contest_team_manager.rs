@@ -0,0 +1,196 @@
+use sql_client::internal::virtual_contest_manager::{
+    VirtualContestError, VirtualContestItem, VirtualContestManager, VirtualContestMode,
+};
+use sql_client::internal::virtual_contest_team_manager::{
+    VirtualContestTeam, VirtualContestTeamManager, MAX_MEMBER_NUM_PER_TEAM,
+};
+
+mod utils;
+
+#[tokio::test]
+async fn test_virtual_contest_team_manager() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    let user_id = "user_id";
+    let other_user_id = "other_user_id";
+    utils::setup_internal_user(&pool, user_id, "atcoder_id").await;
+    utils::setup_internal_user(&pool, other_user_id, "other_atcoder_id").await;
+
+    let team_id = pool
+        .create_team(
+            "team",
+            user_id,
+            &["member_2".to_owned(), "member_1".to_owned()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        pool.get_own_teams(user_id).await.unwrap(),
+        vec![VirtualContestTeam {
+            id: team_id.clone(),
+            name: "team".to_owned(),
+            owner_user_id: user_id.to_owned(),
+            members: vec!["member_1".to_owned(), "member_2".to_owned()],
+        }]
+    );
+    assert!(pool.get_own_teams(other_user_id).await.unwrap().is_empty());
+
+    let result = pool
+        .update_team(&team_id, other_user_id, "hijacked", &[])
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the owner should be able to update the team."
+    );
+    pool.update_team(&team_id, user_id, "renamed", &["member_3".to_owned()])
+        .await
+        .unwrap();
+    let teams = pool.get_own_teams(user_id).await.unwrap();
+    assert_eq!(teams[0].name, "renamed");
+    assert_eq!(teams[0].members, vec!["member_3"]);
+
+    let members = (0..=MAX_MEMBER_NUM_PER_TEAM)
+        .map(|i| format!("member_{}", i))
+        .collect::<Vec<_>>();
+    let result = pool.create_team("too large", user_id, &members).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::TooManyTeamMembers)
+    );
+
+    let contest_id = pool
+        .create_contest(
+            "title",
+            "memo",
+            other_user_id,
+            100,
+            100,
+            VirtualContestMode::Normal,
+            true,
+            0,
+        )
+        .await
+        .unwrap();
+    let result = pool
+        .join_contest_as_team(&contest_id, &team_id, other_user_id, None)
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the team owner should be able to register the team."
+    );
+    let result = pool
+        .join_contest_as_team(&contest_id, "unknown-team", user_id, None)
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::TeamNotFound)
+    );
+
+    pool.set_registration_closed(&contest_id, other_user_id, true)
+        .await
+        .unwrap();
+    let result = pool
+        .join_contest_as_team(&contest_id, &team_id, user_id, None)
+        .await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::RegistrationClosed)
+    );
+    pool.set_registration_closed(&contest_id, other_user_id, false)
+        .await
+        .unwrap();
+
+    pool.join_contest_as_team(&contest_id, &team_id, user_id, None)
+        .await
+        .unwrap();
+    let teams = pool.get_single_contest_teams(&contest_id).await.unwrap();
+    assert_eq!(teams.len(), 1);
+    assert_eq!(teams[0].id, team_id);
+
+    utils::setup_internal_user(&pool, "member_user_id", "member_3").await;
+    for internal_user_id in [user_id, "member_user_id"] {
+        let contests = pool
+            .get_team_participated_contests(internal_user_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            contests.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+            vec![contest_id.as_str()],
+            "The team owner and the members should see the contest joined by the team."
+        );
+    }
+    assert!(pool
+        .get_team_participated_contests(other_user_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let items = [VirtualContestItem {
+        id: "problem".to_string(),
+        point: None,
+        order: None,
+    }];
+    pool.update_items(&contest_id, &items, other_user_id)
+        .await
+        .unwrap();
+    sqlx::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 150, 'problem', 'contest', 'member_1', 'Rust', 0, 0, 'WA'),
+            (2, 160, 'problem', 'contest', 'member_3', 'Rust', 0, 0, 'AC')
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let submissions = pool
+        .get_single_contest_submissions(&contest_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![2],
+        "The submissions of the current team members should be returned."
+    );
+
+    pool.leave_contest_as_team(&contest_id, &team_id, user_id)
+        .await
+        .unwrap();
+    assert!(pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    pool.join_contest_as_team(&contest_id, &team_id, user_id, None)
+        .await
+        .unwrap();
+    let result = pool.kick_team(&contest_id, user_id, &team_id).await;
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<VirtualContestError>(),
+        Some(&VirtualContestError::NotOwner),
+        "Only the contest owner should be able to kick a team."
+    );
+    pool.kick_team(&contest_id, other_user_id, &team_id)
+        .await
+        .unwrap();
+    assert!(pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    pool.join_contest_as_team(&contest_id, &team_id, user_id, None)
+        .await
+        .unwrap();
+    pool.delete_team(&team_id, user_id).await.unwrap();
+    assert!(pool.get_own_teams(user_id).await.unwrap().is_empty());
+    assert!(pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
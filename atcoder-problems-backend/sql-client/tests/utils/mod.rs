@@ -1,9 +1,6 @@
 use sql_client::PgPool;
 use sqlx::Executor;
-use std::fs::File;
-use std::io::prelude::*;
 
-const SQL_FILE: &str = "../../config/database-definition.sql";
 const SQL_URL_ENV_KEY: &str = "SQL_URL";
 
 #[cfg(test)]
@@ -30,9 +27,9 @@ pub async fn initialize_and_connect_to_test_sql() -> PgPool {
 }
 
 async fn initialize(pool: &PgPool) {
-    let mut file = File::open(SQL_FILE).unwrap();
-    let mut sql = String::new();
-    file.read_to_string(&mut sql).unwrap();
     let mut conn = pool.acquire().await.unwrap();
-    conn.execute(sql.as_str()).await.unwrap();
+    conn.execute("DROP SCHEMA public CASCADE; CREATE SCHEMA public;")
+        .await
+        .unwrap();
+    sql_client::run_migrations(pool).await.unwrap();
 }
@@ -1,65 +1,86 @@
 use anyhow::Result;
 use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::WholeContestCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use log::{error, info};
-use sql_client::initialize_pool;
+use sql_client::crawl_checkpoint::{CrawlCheckpoint, CrawlCheckpointClient};
 use sql_client::models::Contest;
 use sql_client::simple_client::SimpleClient;
-use std::{env, thread, time};
+use sql_client::{initialize_pool, PgPool};
+use std::collections::BTreeMap;
+use std::{env, time};
 
 #[actix_web::main]
 async fn main() {
     init_log_config().unwrap();
     info!("Started");
     let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let db = loop {
+        match initialize_pool(&url).await {
+            Ok(db) => break db,
+            Err(e) => {
+                error!("Failed to connect to the database: {:?}", e);
+                sleep_1sec().await;
+            }
+        }
+    };
+    let client = init_atcoder_client(&db).unwrap();
 
     loop {
         info!("Start new loop");
 
-        match load_contest(&url).await {
+        match load_contests(&db).await {
             Ok(contests) => {
-                for contest in contests.into_iter() {
-                    finish_one_contest(&url, &contest.id).await;
+                for (contest, checkpoint) in contests.into_iter() {
+                    if let Some(checkpoint) = checkpoint {
+                        if checkpoint.is_complete(&contest) {
+                            continue;
+                        }
+                    }
+                    finish_one_contest(&db, &client, &contest.id).await;
                 }
             }
             Err(e) => {
                 error!("Failed to load the contests: {:?}", e);
-                sleep_1sec();
             }
         }
+        sleep_1sec().await;
     }
 }
 
-async fn finish_one_contest(url: &str, contest_id: &str) {
-    loop {
-        info!("Starting {}", contest_id);
-        match crawl_one_contest(url, contest_id).await {
-            Ok(_) => {
-                info!("Finished {}", contest_id);
-                return;
-            }
-            Err(e) => {
-                error!("Error while crawling {}: {:?}", contest_id, e);
-                sleep_1sec();
-            }
+/// A contest which fails is resumed from its checkpoint in the next loop.
+async fn finish_one_contest(db: &PgPool, client: &AtCoderClient, contest_id: &str) {
+    info!("Starting {}", contest_id);
+    let crawler = WholeContestCrawler::new(db.clone(), client.clone(), contest_id);
+    match crawler.crawl_from_checkpoint().await {
+        Ok(_) => {
+            info!("Finished {}", contest_id);
+        }
+        Err(e) => {
+            error!("Error while crawling {}: {:?}", contest_id, e);
+            sleep_1sec().await;
         }
     }
 }
 
-async fn crawl_one_contest(url: &str, contest_id: &str) -> Result<()> {
-    let db = initialize_pool(url).await?;
-    let crawler = WholeContestCrawler::new(db, AtCoderClient::default(), contest_id);
-    crawler.crawl().await?;
-    Ok(())
-}
-
-async fn load_contest(url: &str) -> Result<Vec<Contest>> {
-    let db = initialize_pool(url).await?;
+async fn load_contests(db: &PgPool) -> Result<Vec<(Contest, Option<CrawlCheckpoint>)>> {
     let contests = db.load_contests().await?;
+    let mut checkpoints = db
+        .load_crawl_checkpoints()
+        .await?
+        .into_iter()
+        .map(|checkpoint| (checkpoint.contest_id.clone(), checkpoint))
+        .collect::<BTreeMap<_, _>>();
+    let contests = contests
+        .into_iter()
+        .map(|contest| {
+            let checkpoint = checkpoints.remove(&contest.id);
+            (contest, checkpoint)
+        })
+        .collect();
     Ok(contests)
 }
 
-fn sleep_1sec() {
-    thread::sleep(time::Duration::from_millis(1000));
+async fn sleep_1sec() {
+    actix_web::rt::time::sleep(time::Duration::from_millis(1000)).await;
 }
@@ -1,26 +1,26 @@
 use anyhow::Result;
 use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::{FixCrawler, VirtualContestCrawler};
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use chrono::Utc;
 use rand::{thread_rng, Rng};
 use sql_client::initialize_pool;
+use std::env;
 use std::time::{Duration, Instant};
-use std::{env, thread};
 
 const FIX_RANGE_SECOND: i64 = 10 * 60;
 
-async fn crawl<R: Rng>(url: &str, rng: &mut R) -> Result<()> {
+async fn crawl<R: Rng>(url: &str, client: &AtCoderClient, rng: &mut R) -> Result<()> {
     log::info!("Start crawling...");
     let pg_pool = initialize_pool(&url).await?;
-    let mut crawler = VirtualContestCrawler::new(pg_pool, AtCoderClient::default(), rng);
+    let mut crawler = VirtualContestCrawler::new(pg_pool, client.clone(), rng);
     crawler.crawl().await?;
     log::info!("Finished crawling");
 
     log::info!("Starting fixing...");
     let conn = initialize_pool(&url).await?;
     let cur = Utc::now().timestamp();
-    let crawler = FixCrawler::new(conn, AtCoderClient::default(), cur - FIX_RANGE_SECOND);
+    let crawler = FixCrawler::new(conn, client.clone(), cur - FIX_RANGE_SECOND);
     crawler.crawl().await?;
     log::info!("Finished fixing");
 
@@ -31,6 +31,8 @@ async fn crawl<R: Rng>(url: &str, rng: &mut R) -> Result<()> {
 async fn main() {
     init_log_config().unwrap();
     let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let pool = initialize_pool(&url).await.unwrap();
+    let client = init_atcoder_client(&pool).unwrap();
     log::info!("Started");
 
     let mut rng = thread_rng();
@@ -39,7 +41,7 @@ async fn main() {
         log::info!("Start new loop...");
         let now = Instant::now();
 
-        if let Err(e) = crawl(&url, &mut rng).await {
+        if let Err(e) = crawl(&url, &client, &mut rng).await {
             log::error!("{:?}", e);
         }
 
@@ -48,7 +50,7 @@ async fn main() {
         if elapsed_secs < 10 {
             let sleep_seconds = 10 - elapsed_secs;
             log::info!("Sleeping {} sec.", sleep_seconds);
-            thread::sleep(Duration::from_secs(sleep_seconds));
+            actix_web::rt::time::sleep(Duration::from_secs(sleep_seconds)).await;
         }
 
         log::info!("Finished a loop");
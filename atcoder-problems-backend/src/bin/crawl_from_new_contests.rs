@@ -1,15 +1,15 @@
 use anyhow::Result;
 use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::WholeContestCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use log::info;
 use sql_client::initialize_pool;
 use sql_client::simple_client::SimpleClient;
-use std::{env, thread, time};
+use std::{env, time};
 
 const NEW_CONTEST_NUM: usize = 5;
 
-async fn iteration(url: &str) -> Result<()> {
+async fn iteration(url: &str, client: &AtCoderClient) -> Result<()> {
     let db = initialize_pool(&url).await?;
     let mut contests = db.load_contests().await?;
     contests.sort_by_key(|c| c.start_epoch_second);
@@ -17,8 +17,10 @@ async fn iteration(url: &str) -> Result<()> {
 
     for contest in &contests[0..NEW_CONTEST_NUM] {
         info!("Starting {}", contest.id);
-        let crawler = WholeContestCrawler::new(db.clone(), AtCoderClient::default(), &contest.id);
-        crawler.crawl().await?;
+        let crawler = WholeContestCrawler::new(db.clone(), client.clone(), &contest.id);
+        if let Err(e) = crawler.crawl().await {
+            log::error!("Error while crawling {}: {:?}", contest.id, e);
+        }
     }
     Ok(())
 }
@@ -28,12 +30,14 @@ async fn main() {
     init_log_config().unwrap();
     info!("Started");
     let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let pool = initialize_pool(&url).await.unwrap();
+    let client = init_atcoder_client(&pool).unwrap();
 
     loop {
         info!("Start new loop");
-        if let Err(e) = iteration(&url).await {
+        if let Err(e) = iteration(&url, &client).await {
             log::error!("{:?}", e);
-            thread::sleep(time::Duration::from_millis(1000));
+            actix_web::rt::time::sleep(time::Duration::from_millis(1000)).await;
         }
     }
 }
@@ -1,6 +1,5 @@
-use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::ProblemCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use sql_client::initialize_pool;
 use std::env;
 
@@ -11,7 +10,8 @@ async fn main() {
     let url = env::var("SQL_URL").expect("SQL_URL is not set.");
 
     let db = initialize_pool(&url).await.unwrap();
-    let crawler = ProblemCrawler::new(db, AtCoderClient::default());
+    let client = init_atcoder_client(&db).unwrap();
+    let crawler = ProblemCrawler::new(db, client);
     crawler.crawl().await.expect("Failed to crawl");
 
     log::info!("Finished");
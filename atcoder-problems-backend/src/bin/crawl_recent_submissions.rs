@@ -1,13 +1,13 @@
 use anyhow::Result;
 use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::RecentCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use sql_client::initialize_pool;
-use std::{env, thread, time};
+use std::{env, time};
 
-async fn crawl(url: &str) -> Result<()> {
+async fn crawl(url: &str, client: &AtCoderClient) -> Result<()> {
     let db = initialize_pool(url).await?;
-    let crawler = RecentCrawler::new(db, AtCoderClient::default());
+    let crawler = RecentCrawler::new(db, client.clone());
     crawler.crawl().await
 }
 
@@ -16,12 +16,14 @@ async fn main() {
     init_log_config().unwrap();
     log::info!("Started");
     let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let pool = initialize_pool(&url).await.unwrap();
+    let client = init_atcoder_client(&pool).unwrap();
 
     loop {
         log::info!("Start new loop");
-        if let Err(e) = crawl(&url).await {
+        if let Err(e) = crawl(&url, &client).await {
             log::error!("{:?}", e);
-            thread::sleep(time::Duration::from_millis(1000));
+            actix_web::rt::time::sleep(time::Duration::from_millis(1000)).await;
         }
     }
 }
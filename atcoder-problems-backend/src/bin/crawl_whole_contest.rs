@@ -1,7 +1,6 @@
 use anyhow::Result;
-use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::WholeContestCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use log::info;
 use sql_client::initialize_pool;
 use std::env;
@@ -15,7 +14,8 @@ async fn main() -> Result<()> {
         .nth(1)
         .expect("contest_id is not set.\nUsage: cargo run --bin crawl_whole_contest <contest_id>");
     let db = initialize_pool(&url).await?;
-    let crawler = WholeContestCrawler::new(db, AtCoderClient::default(), contest_id);
+    let client = init_atcoder_client(&db)?;
+    let crawler = WholeContestCrawler::new(db, client, contest_id);
     crawler.crawl().await?;
     Ok(())
 }
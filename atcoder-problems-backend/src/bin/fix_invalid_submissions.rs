@@ -1,6 +1,5 @@
-use atcoder_client::AtCoderClient;
 use atcoder_problems_backend::crawler::FixCrawler;
-use atcoder_problems_backend::utils::init_log_config;
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
 use chrono::Utc;
 use log::info;
 use sql_client::initialize_pool;
@@ -15,7 +14,8 @@ async fn main() {
     let url = env::var("SQL_URL").expect("SQL_URL must be set.");
     let db = initialize_pool(&url).await.unwrap();
     let now = Utc::now().timestamp();
-    let crawler = FixCrawler::new(db, AtCoderClient::default(), now - ONE_DAY);
+    let client = init_atcoder_client(&db).unwrap();
+    let crawler = FixCrawler::new(db, client, now - ONE_DAY);
     crawler.crawl().await.expect("Failed to crawl");
     info!("Finished fixing.");
 }
@@ -0,0 +1,18 @@
+use atcoder_problems_backend::utils::init_log_config;
+use sql_client::{create_indexes_concurrently, initialize_pool, run_migrations};
+use std::env;
+
+#[actix_web::main]
+async fn main() {
+    init_log_config().unwrap();
+    log::info!("Started");
+    let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+
+    let db = initialize_pool(&url).await.unwrap();
+    run_migrations(&db).await.expect("Failed to run migrations");
+    create_indexes_concurrently(&db)
+        .await
+        .expect("Failed to create indexes");
+
+    log::info!("Finished");
+}
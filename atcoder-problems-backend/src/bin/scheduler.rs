@@ -0,0 +1,26 @@
+use atcoder_problems_backend::scheduler::{parse_intervals, Scheduler};
+use atcoder_problems_backend::utils::{init_atcoder_client, init_log_config};
+use sql_client::{initialize_pool, PgPoolOptions};
+use std::env;
+
+#[actix_web::main]
+async fn main() {
+    init_log_config().unwrap();
+    log::info!("Started");
+    let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let intervals = env::var("SCHEDULER_INTERVALS").unwrap_or_default();
+    let intervals = parse_intervals(&intervals).expect("SCHEDULER_INTERVALS is invalid.");
+    for (job, interval) in intervals.iter() {
+        log::info!("{} runs every {} sec.", job.name(), interval.as_secs());
+    }
+
+    let pool = initialize_pool(&url).await.unwrap();
+    // Every running job holds its lock on a connection of its own.
+    let lock_pool = PgPoolOptions::new()
+        .max_connections(intervals.len().max(1) as u32)
+        .connect(&url)
+        .await
+        .unwrap();
+    let client = init_atcoder_client(&pool).unwrap();
+    Scheduler::new(pool, lock_pool, client).run(intervals).await;
+}
@@ -1,6 +1,6 @@
 use crate::crawler::AtCoderFetcher;
 use anyhow::Result;
-use log::info;
+use log::{error, info};
 use sql_client::submission_client::{SubmissionClient, SubmissionRequest};
 use std::collections::BTreeMap;
 
@@ -50,7 +50,13 @@ where
             for page in 1.. {
                 info!("Fetching from {}-{}", contest_id, page);
                 let (submissions, max_page) =
-                    self.fetcher.fetch_submissions(&contest_id, page).await;
+                    match self.fetcher.fetch_submissions(&contest_id, page).await {
+                        Ok(fetched) => fetched,
+                        Err(e) => {
+                            error!("Skipping {}: {:?}", contest_id, e);
+                            break;
+                        }
+                    };
                 self.db.update_submissions(&submissions).await?;
                 let all_old = submissions.iter().all(|s| s.id <= minimum_id);
                 if all_old || max_page == page {
@@ -117,10 +123,10 @@ mod tests {
     #[test]
     fn test_fix_crawler_found() {
         let fetcher = MockFetcher(|_, _| {
-            vec![Submission {
+            Ok(vec![Submission {
                 id: 50,
                 ..Default::default()
-            }]
+            }])
         });
         let crawler = FixCrawler::new(MockDB, fetcher, CURRENT_TIME);
         let rt = actix_web::rt::Runtime::new().unwrap();
@@ -130,10 +136,10 @@ mod tests {
     #[test]
     fn test_fix_crawler_all_old() {
         let fetcher = MockFetcher(|_, _| {
-            vec![Submission {
+            Ok(vec![Submission {
                 id: 30,
                 ..Default::default()
-            }]
+            }])
         });
 
         let crawler = FixCrawler::new(MockDB, fetcher, CURRENT_TIME);
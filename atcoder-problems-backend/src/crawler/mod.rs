@@ -1,34 +1,63 @@
 mod fix_crawler;
 mod problem_crawler;
+mod problem_detail_crawler;
 mod recent_crawler;
+mod rejudge_crawler;
+mod standings_crawler;
+#[cfg(test)]
 pub(crate) mod utils;
 mod virtual_contest_crawler;
 mod whole_contest_crawler;
 
 pub use fix_crawler::FixCrawler;
 pub use problem_crawler::ProblemCrawler;
+pub use problem_detail_crawler::ProblemDetailCrawler;
 pub use recent_crawler::RecentCrawler;
+pub use rejudge_crawler::RejudgeCrawler;
+pub use standings_crawler::StandingsCrawler;
 pub use virtual_contest_crawler::VirtualContestCrawler;
 pub use whole_contest_crawler::WholeContestCrawler;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use atcoder_client::{AtCoderClient, AtCoderProblem, AtCoderSubmission, ContestTypeSpecifier};
+use atcoder_client::{
+    AtCoderClient, AtCoderClientError, AtCoderContestResult, AtCoderProblem, AtCoderProblemDetail,
+    AtCoderStandingsRow, AtCoderSubmission, ContestTypeSpecifier,
+};
 use log::info;
-use sql_client::models::{Contest, ContestProblem, Problem, Submission};
+use sql_client::models::{
+    Contest, ContestProblem, ContestStanding, Problem, RatingHistoryEntry, Submission,
+};
 
 #[async_trait]
 pub trait AtCoderFetcher {
-    async fn fetch_submissions(&self, contest_id: &str, page: u32) -> (Vec<Submission>, u32);
+    /// Returns the submissions on the page and the number of the last page.
+    async fn fetch_submissions(
+        &self,
+        contest_id: &str,
+        page: u32,
+    ) -> Result<(Vec<Submission>, u32)>;
     async fn fetch_contests(&self, spf: ContestTypeSpecifier) -> Result<Vec<Contest>>;
     async fn fetch_problems(&self, contest_id: &str)
         -> Result<(Vec<Problem>, Vec<ContestProblem>)>;
+    async fn fetch_problem_detail(
+        &self,
+        contest_id: &str,
+        problem_id: &str,
+    ) -> Result<AtCoderProblemDetail>;
+    /// Returns `None` while the standings are not fixed.
+    async fn fetch_standings(&self, contest_id: &str) -> Result<Option<Vec<ContestStanding>>>;
+    async fn fetch_rating_history(&self, user_id: &str) -> Result<Vec<RatingHistoryEntry>>;
 }
 
 #[async_trait]
 impl AtCoderFetcher for AtCoderClient {
-    async fn fetch_submissions(&self, contest_id: &str, page: u32) -> (Vec<Submission>, u32) {
-        let (submissions, max_page) = retry_fetch_submissions(self, 9, contest_id, page).await;
+    async fn fetch_submissions(
+        &self,
+        contest_id: &str,
+        page: u32,
+    ) -> Result<(Vec<Submission>, u32)> {
+        let (submissions, max_page) = retry_fetch_submissions(self, 9, contest_id, page).await?;
         let submissions = submissions
             .into_iter()
             .map(|s| Submission {
@@ -44,7 +73,7 @@ impl AtCoderFetcher for AtCoderClient {
                 execution_time: s.execution_time.map(|t| t as i32),
             })
             .collect();
-        (submissions, max_page)
+        Ok((submissions, max_page))
     }
 
     async fn fetch_contests(&self, spf: ContestTypeSpecifier) -> Result<Vec<Contest>> {
@@ -79,11 +108,17 @@ impl AtCoderFetcher for AtCoderClient {
         contest_id: &str,
     ) -> Result<(Vec<Problem>, Vec<ContestProblem>)> {
         info!("Fetching problems from {} ...", contest_id);
-        let problems = self.fetch_problem_list(contest_id).await?;
-        let problems = problems
-            .into_iter()
-            .map(convert_problem)
-            .collect::<Vec<_>>();
+        let mut problems = Vec::new();
+        for problem in self.fetch_problem_list(contest_id).await? {
+            let detail = match self.fetch_problem_detail(contest_id, &problem.id).await {
+                Ok(detail) => Some(detail),
+                Err(e) => {
+                    log::error!("Failed to fetch the detail of {}: {:?}", problem.id, e);
+                    None
+                }
+            };
+            problems.push(convert_problem(problem, detail));
+        }
         let contest_problem = problems
             .iter()
             .map(|problem| ContestProblem {
@@ -93,42 +128,130 @@ impl AtCoderFetcher for AtCoderClient {
             .collect::<Vec<_>>();
         Ok((problems, contest_problem))
     }
+
+    async fn fetch_problem_detail(
+        &self,
+        contest_id: &str,
+        problem_id: &str,
+    ) -> Result<AtCoderProblemDetail> {
+        info!("Fetching the detail of {} ...", problem_id);
+        AtCoderClient::fetch_problem_detail(self, contest_id, problem_id).await
+    }
+
+    async fn fetch_standings(&self, contest_id: &str) -> Result<Option<Vec<ContestStanding>>> {
+        info!("Fetching standings of {} ...", contest_id);
+        let standings = self.fetch_contest_standings(contest_id).await?;
+        if !standings.fixed {
+            return Ok(None);
+        }
+        let standings = standings
+            .standings_data
+            .into_iter()
+            .map(|row| convert_standing(contest_id, row))
+            .collect();
+        Ok(Some(standings))
+    }
+
+    async fn fetch_rating_history(&self, user_id: &str) -> Result<Vec<RatingHistoryEntry>> {
+        info!("Fetching rating history of {} ...", user_id);
+        self.fetch_user_rating_history(user_id)
+            .await?
+            .into_iter()
+            .map(|result| convert_contest_result(user_id, result))
+            .collect()
+    }
 }
 
+/// Retries on network errors and throttling. The client itself waits for `Retry-After`.
 async fn retry_fetch_submissions(
     client: &AtCoderClient,
     retry_count: usize,
     contest_id: &str,
     page: u32,
-) -> (Vec<AtCoderSubmission>, u32) {
+) -> Result<(Vec<AtCoderSubmission>, u32)> {
     let mut sleep_second = 1;
-    for _ in 0..retry_count {
+    for _ in 1..retry_count {
         match client
             .fetch_atcoder_submission_list(contest_id, Some(page))
             .await
         {
             Ok(response) => {
-                return (response.submissions, response.max_page);
-            }
-            Err(e) => {
-                log::error!("Error when fetching {} {}: {:?} ", contest_id, page, e);
-                log::info!("Sleeping {}s before retry ...", sleep_second);
-                actix_web::rt::time::sleep(std::time::Duration::from_secs(sleep_second)).await;
-                sleep_second *= 2;
+                return Ok((response.submissions, response.max_page));
             }
+            Err(e) => match e.downcast_ref::<AtCoderClientError>() {
+                Some(AtCoderClientError::NotFound { .. })
+                | Some(AtCoderClientError::LayoutChanged { .. }) => {
+                    return Err(e.context(format!("Failed to fetch {} {}", contest_id, page)));
+                }
+                _ => {
+                    log::error!("Error when fetching {} {}: {:?} ", contest_id, page, e);
+                    log::info!("Sleeping {}s before retry ...", sleep_second);
+                    actix_web::rt::time::sleep(std::time::Duration::from_secs(sleep_second)).await;
+                    sleep_second *= 2;
+                }
+            },
         }
     }
-    (Vec::new(), 0)
+    let response = client
+        .fetch_atcoder_submission_list(contest_id, Some(page))
+        .await
+        .with_context(|| format!("Failed to fetch {} {}", contest_id, page))?;
+    Ok((response.submissions, response.max_page))
 }
 
-fn convert_problem(p: AtCoderProblem) -> Problem {
+/// Prefers the limits on the page of the problem, which are more precise than the ones on the list.
+fn convert_problem(p: AtCoderProblem, detail: Option<AtCoderProblemDetail>) -> Problem {
+    let (score, time_limit_ms, memory_limit_kb) = match detail {
+        Some(detail) => (
+            detail.score,
+            detail.time_limit_ms.or(p.time_limit_ms),
+            detail.memory_limit_kb.or(p.memory_limit_kb),
+        ),
+        None => (None, p.time_limit_ms, p.memory_limit_kb),
+    };
     Problem {
         id: p.id,
         contest_id: p.contest_id,
-        title: p.position + ". " + p.title.as_str(),
+        title: format!("{}. {}", p.position, p.title),
+        problem_index: p.position,
+        score,
+        time_limit_ms: time_limit_ms.map(|t| t as i32),
+        memory_limit_kb: memory_limit_kb.map(|m| m as i32),
+    }
+}
+
+fn convert_standing(contest_id: &str, row: AtCoderStandingsRow) -> ContestStanding {
+    ContestStanding {
+        contest_id: contest_id.to_owned(),
+        user_id: row.user_screen_name,
+        rank: row.rank as i32,
+        score: row.total_result.score as f64 / 100.0,
+        penalty: row.total_result.penalty as i32,
+        elapsed_second: row.total_result.elapsed / 1_000_000_000,
+        is_rated: row.is_rated,
+        old_rating: row.old_rating,
     }
 }
 
+fn convert_contest_result(
+    user_id: &str,
+    result: AtCoderContestResult,
+) -> Result<RatingHistoryEntry> {
+    Ok(RatingHistoryEntry {
+        user_id: user_id.to_owned(),
+        contest_id: result.contest_id().to_owned(),
+        is_rated: result.is_rated,
+        place: result.place as i32,
+        old_rating: result.old_rating,
+        new_rating: result.new_rating,
+        performance: result.performance,
+        inner_performance: result.inner_performance,
+        end_epoch_second: result
+            .end_epoch_second()
+            .with_context(|| format!("Invalid end time: {}", result.end_time))?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,10 +263,81 @@ mod tests {
             contest_id: "contest_id".to_owned(),
             title: "title".to_owned(),
             position: "A".to_owned(),
+            time_limit_ms: Some(2000),
+            memory_limit_kb: Some(1048576),
+        };
+        let converted = convert_problem(p.clone(), None);
+        assert_eq!(converted.id, "id".to_owned());
+        assert_eq!(converted.contest_id, "contest_id".to_owned());
+        assert_eq!(converted.title, "A. title".to_owned());
+        assert_eq!(converted.problem_index, "A".to_owned());
+        assert_eq!(converted.score, None);
+        assert_eq!(converted.time_limit_ms, Some(2000));
+
+        let detail = AtCoderProblemDetail {
+            score: Some(100.0),
+            time_limit_ms: Some(2500),
+            memory_limit_kb: None,
         };
-        let p = convert_problem(p);
-        assert_eq!(p.id, "id".to_owned());
-        assert_eq!(p.contest_id, "contest_id".to_owned());
-        assert_eq!(p.title, "A. title".to_owned());
+        let converted = convert_problem(p, Some(detail));
+        assert_eq!(converted.score, Some(100.0));
+        assert_eq!(converted.time_limit_ms, Some(2500));
+        assert_eq!(converted.memory_limit_kb, Some(1048576));
+    }
+
+    #[test]
+    fn test_convert_standing() {
+        let row: AtCoderStandingsRow = serde_json::from_value(serde_json::json!({
+            "Rank": 2,
+            "UserScreenName": "kenkoooo",
+            "IsRated": true,
+            "OldRating": 1850,
+            "Competitions": 40,
+            "TaskResults": {},
+            "TotalResult": {
+                "Count": 3,
+                "Accepted": 1,
+                "Penalty": 1,
+                "Score": 10050,
+                "Elapsed": 360000000000i64
+            }
+        }))
+        .unwrap();
+        let standing = convert_standing("abc107", row);
+        assert_eq!(
+            standing,
+            ContestStanding {
+                contest_id: "abc107".to_owned(),
+                user_id: "kenkoooo".to_owned(),
+                rank: 2,
+                score: 100.5,
+                penalty: 1,
+                elapsed_second: 360,
+                is_rated: true,
+                old_rating: 1850,
+            }
+        );
+    }
+
+    #[test]
+    fn test_convert_contest_result() {
+        let mut result = AtCoderContestResult {
+            is_rated: true,
+            place: 1024,
+            old_rating: 0,
+            new_rating: 345,
+            performance: 1234,
+            inner_performance: 1300,
+            contest_screen_name: "abc107.contest.atcoder.jp".to_owned(),
+            contest_name: "AtCoder Beginner Contest 107".to_owned(),
+            end_time: "2018-08-25T22:40:00+09:00".to_owned(),
+        };
+        let entry = convert_contest_result("user", result.clone()).unwrap();
+        assert_eq!(entry.contest_id, "abc107");
+        assert_eq!(entry.end_epoch_second, 1535204400);
+        assert_eq!(entry.inner_performance, 1300);
+
+        result.end_time = "broken".to_owned();
+        assert!(convert_contest_result("user", result).is_err());
     }
 }
@@ -5,7 +5,6 @@ use sql_client::contest_problem::ContestProblemClient;
 use sql_client::models::{Contest, ContestProblem, Problem};
 use sql_client::simple_client::SimpleClient;
 use std::collections::BTreeSet;
-use std::{thread, time};
 
 pub struct ProblemCrawler<C, F> {
     db: C,
@@ -36,8 +35,6 @@ where
                 log::error!("{:?}", e);
             }
         }
-        thread::sleep(time::Duration::from_millis(500));
-
         match self
             .fetcher
             .fetch_contests(ContestTypeSpecifier::Hidden)
@@ -68,7 +65,6 @@ where
                     break;
                 }
             }
-            thread::sleep(time::Duration::from_millis(500));
         }
 
         log::info!("There are {} contests.", contests.len());
@@ -92,7 +88,6 @@ where
                     log::error!("{:?}", e);
                 }
             }
-            thread::sleep(time::Duration::from_millis(500));
         }
 
         Ok(())
@@ -0,0 +1,181 @@
+use crate::crawler::AtCoderFetcher;
+use anyhow::Result;
+use sql_client::problem_detail::ProblemDetailClient;
+
+/// Fills the scores and the limits of the stored problems whose pages failed to be scraped, or
+/// were stored before the pages were scraped.
+///
+/// Only `batch_size` problems are fetched in a run, and the failed ones are retried after the
+/// others in the later runs.
+pub struct ProblemDetailCrawler<C, F> {
+    db: C,
+    fetcher: F,
+    batch_size: i64,
+    current_time_second: i64,
+}
+
+impl<C, F> ProblemDetailCrawler<C, F>
+where
+    C: ProblemDetailClient,
+    F: AtCoderFetcher,
+{
+    pub fn new(db: C, fetcher: F, batch_size: i64, current_time_second: i64) -> Self {
+        Self {
+            db,
+            fetcher,
+            batch_size,
+            current_time_second,
+        }
+    }
+
+    pub async fn crawl(&self) -> Result<()> {
+        log::info!("Starting...");
+        let problems = self
+            .db
+            .load_problems_without_detail(self.batch_size)
+            .await?;
+        log::info!("There are {} problems to fill.", problems.len());
+
+        for mut problem in problems {
+            match self
+                .fetcher
+                .fetch_problem_detail(&problem.contest_id, &problem.id)
+                .await
+            {
+                Ok(detail) => {
+                    problem.score = detail.score.or(problem.score);
+                    problem.time_limit_ms = detail
+                        .time_limit_ms
+                        .map(|t| t as i32)
+                        .or(problem.time_limit_ms);
+                    problem.memory_limit_kb = detail
+                        .memory_limit_kb
+                        .map(|m| m as i32)
+                        .or(problem.memory_limit_kb);
+                    self.db
+                        .update_problem_detail(&problem, self.current_time_second)
+                        .await?;
+                }
+                Err(e) => {
+                    log::error!("Failed to fetch the detail of {}: {:?}", problem.id, e);
+                    self.db
+                        .record_problem_detail_failure(&problem.id, self.current_time_second)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use atcoder_client::{AtCoderProblemDetail, ContestTypeSpecifier};
+    use sql_client::models::{
+        Contest, ContestProblem, ContestStanding, Problem, RatingHistoryEntry, Submission,
+    };
+    use std::sync::Mutex;
+
+    const NOW: i64 = 1000;
+
+    #[derive(Default)]
+    struct MockDB {
+        updated: Mutex<Vec<Problem>>,
+        failed: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ProblemDetailClient for MockDB {
+        async fn load_problems_without_detail(&self, limit: i64) -> Result<Vec<Problem>> {
+            assert_eq!(limit, 2);
+            Ok(vec![
+                Problem {
+                    id: "found".to_owned(),
+                    time_limit_ms: Some(2000),
+                    ..Default::default()
+                },
+                Problem {
+                    id: "missing".to_owned(),
+                    ..Default::default()
+                },
+            ])
+        }
+        async fn update_problem_detail(&self, problem: &Problem, epoch_second: i64) -> Result<()> {
+            assert_eq!(epoch_second, NOW);
+            self.updated.lock().unwrap().push(Problem {
+                id: problem.id.clone(),
+                score: problem.score,
+                time_limit_ms: problem.time_limit_ms,
+                memory_limit_kb: problem.memory_limit_kb,
+                ..Default::default()
+            });
+            Ok(())
+        }
+        async fn record_problem_detail_failure(
+            &self,
+            problem_id: &str,
+            epoch_second: i64,
+        ) -> Result<()> {
+            assert_eq!(epoch_second, NOW);
+            self.failed.lock().unwrap().push(problem_id.to_owned());
+            Ok(())
+        }
+    }
+
+    struct MockFetcher;
+
+    #[async_trait]
+    impl AtCoderFetcher for MockFetcher {
+        async fn fetch_submissions(&self, _: &str, _: u32) -> Result<(Vec<Submission>, u32)> {
+            unimplemented!()
+        }
+        async fn fetch_contests(&self, _: ContestTypeSpecifier) -> Result<Vec<Contest>> {
+            unimplemented!()
+        }
+        async fn fetch_problems(&self, _: &str) -> Result<(Vec<Problem>, Vec<ContestProblem>)> {
+            unimplemented!()
+        }
+        async fn fetch_problem_detail(
+            &self,
+            _: &str,
+            problem_id: &str,
+        ) -> Result<AtCoderProblemDetail> {
+            match problem_id {
+                "found" => Ok(AtCoderProblemDetail {
+                    score: Some(100.0),
+                    time_limit_ms: None,
+                    memory_limit_kb: Some(1024),
+                }),
+                _ => Err(anyhow::anyhow!("404")),
+            }
+        }
+        async fn fetch_standings(&self, _: &str) -> Result<Option<Vec<ContestStanding>>> {
+            unimplemented!()
+        }
+        async fn fetch_rating_history(&self, _: &str) -> Result<Vec<RatingHistoryEntry>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_problem_detail_crawler() {
+        let crawler = ProblemDetailCrawler::new(MockDB::default(), MockFetcher, 2, NOW);
+        let rt = actix_web::rt::Runtime::new().unwrap();
+        rt.block_on(crawler.crawl()).unwrap();
+
+        assert_eq!(
+            *crawler.db.updated.lock().unwrap(),
+            vec![Problem {
+                id: "found".to_owned(),
+                score: Some(100.0),
+                time_limit_ms: Some(2000),
+                memory_limit_kb: Some(1024),
+                ..Default::default()
+            }],
+            "The stored limit should be kept if the page does not state it."
+        );
+        assert_eq!(*crawler.db.failed.lock().unwrap(), vec!["missing"]);
+    }
+}
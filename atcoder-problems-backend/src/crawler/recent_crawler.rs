@@ -1,10 +1,9 @@
 use crate::crawler::AtCoderFetcher;
 use anyhow::Result;
 
-use log::info;
+use log::{error, info};
 use sql_client::simple_client::SimpleClient;
 use sql_client::submission_client::SubmissionClient;
-use std::{thread, time};
 
 pub struct RecentCrawler<C, F> {
     db: C,
@@ -27,7 +26,13 @@ where
             for page in 1.. {
                 info!("Crawling {}-{} ...", contest.id, page);
                 let (submissions, max_page) =
-                    self.fetcher.fetch_submissions(&contest.id, page).await;
+                    match self.fetcher.fetch_submissions(&contest.id, page).await {
+                        Ok(fetched) => fetched,
+                        Err(e) => {
+                            error!("Skipping {}: {:?}", contest.id, e);
+                            break;
+                        }
+                    };
                 if submissions.is_empty() {
                     info!("There is no submission on {}-{}", contest.id, page);
                     break;
@@ -36,7 +41,6 @@ where
                 let min_id = submissions.iter().map(|s| s.id).min().unwrap();
                 let exists = self.db.count_stored_submissions(&[min_id]).await? != 0;
                 self.db.update_submissions(&submissions).await?;
-                thread::sleep(time::Duration::from_millis(200));
 
                 if exists {
                     info!("Finished crawling {}", contest.id);
@@ -71,7 +75,7 @@ mod tests {
         let fetcher = MockFetcher(|contest_id: &str, page: u32| {
             assert_eq!(contest_id, "contest");
             assert_eq!(page, 1);
-            vec![
+            Ok(vec![
                 Submission {
                     id: 0,
                     ..Default::default()
@@ -80,7 +84,7 @@ mod tests {
                     id: 1,
                     ..Default::default()
                 },
-            ]
+            ])
         });
 
         struct MockDB;
@@ -126,6 +130,9 @@ mod tests {
                     ..Default::default()
                 }])
             }
+            async fn load_contests_ending_after(&self, _: i64) -> Result<Vec<Contest>> {
+                unimplemented!()
+            }
         }
 
         let crawler = RecentCrawler::new(MockDB, fetcher);
@@ -0,0 +1,207 @@
+use crate::crawler::AtCoderFetcher;
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use sql_client::crawl_checkpoint::CrawlCheckpointClient;
+use sql_client::models::Submission;
+use sql_client::rejudge::{Rejudge, RejudgeClient};
+use sql_client::simple_client::SimpleClient;
+use sql_client::submission_client::{SubmissionClient, SubmissionRequest};
+use std::collections::BTreeMap;
+
+/// The verdicts which will not change unless the submission is rejudged.
+const FINAL_RESULTS: [&str; 10] = [
+    "AC", "WA", "TLE", "CE", "RE", "MLE", "OLE", "QLE", "IE", "NG",
+];
+
+/// Samples pages of the closed contests, and records the submissions whose verdicts differ from
+/// the stored ones.
+///
+/// The users of the rejudged submissions are marked for recomputation, and the checkpoints of the
+/// contests are reset so that the whole contests are crawled again.
+pub struct RejudgeCrawler<'a, C, F, R> {
+    db: C,
+    fetcher: F,
+    rng: &'a mut R,
+    contest_count: usize,
+    current_time_second: i64,
+}
+
+impl<'a, C, F, R> RejudgeCrawler<'a, C, F, R>
+where
+    C: SimpleClient + SubmissionClient + RejudgeClient + CrawlCheckpointClient,
+    F: AtCoderFetcher,
+    R: Rng,
+{
+    pub fn new(
+        db: C,
+        fetcher: F,
+        rng: &'a mut R,
+        contest_count: usize,
+        current_time_second: i64,
+    ) -> Self {
+        Self {
+            db,
+            fetcher,
+            rng,
+            contest_count,
+            current_time_second,
+        }
+    }
+
+    pub async fn crawl(&mut self) -> Result<()> {
+        log::info!("Starting...");
+        let contests = self.db.load_contests().await?;
+        let closed_contest_ids = contests
+            .iter()
+            .filter(|c| c.start_epoch_second + c.duration_second < self.current_time_second)
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>();
+        let contest_ids = closed_contest_ids
+            .choose_multiple(self.rng, self.contest_count)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for contest_id in contest_ids {
+            match self.fetch_sample(&contest_id).await {
+                Ok(submissions) => self.check_submissions(&contest_id, submissions).await?,
+                Err(e) => log::error!("Skipping {}: {:?}", contest_id, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the first page and a random one of the rest.
+    async fn fetch_sample(&mut self, contest_id: &str) -> Result<Vec<Submission>> {
+        let (mut submissions, max_page) = self.fetcher.fetch_submissions(contest_id, 1).await?;
+        if max_page > 1 {
+            let page = self.rng.gen_range(2, max_page + 1);
+            let (page_submissions, _) = self.fetcher.fetch_submissions(contest_id, page).await?;
+            submissions.extend(page_submissions);
+        }
+        Ok(submissions)
+    }
+
+    async fn check_submissions(
+        &self,
+        contest_id: &str,
+        submissions: Vec<Submission>,
+    ) -> Result<()> {
+        // The submissions being rejudged now will be checked again after the judge finishes.
+        let submissions = submissions
+            .into_iter()
+            .filter(|s| FINAL_RESULTS.contains(&s.result.as_str()))
+            .collect::<Vec<_>>();
+        let ids = submissions.iter().map(|s| s.id).collect::<Vec<_>>();
+        let stored = self
+            .db
+            .get_submissions(SubmissionRequest::ByIds { ids: &ids })
+            .await?;
+
+        let rejudges = detect_rejudges(&submissions, &stored, self.current_time_second);
+        if rejudges.is_empty() {
+            return Ok(());
+        }
+        log::info!(
+            "Found {} rejudged submissions in {}",
+            rejudges.len(),
+            contest_id
+        );
+        self.db.update_submissions(&submissions).await?;
+        self.db.record_rejudges(&rejudges).await?;
+        self.db
+            .reset_crawl_checkpoints(&[contest_id.to_owned()])
+            .await?;
+        Ok(())
+    }
+}
+
+/// Compares the fetched submissions with the stored ones. The submissions which have not been
+/// stored yet are ignored.
+fn detect_rejudges(
+    fetched: &[Submission],
+    stored: &[Submission],
+    detected_epoch_second: i64,
+) -> Vec<Rejudge> {
+    let stored = stored.iter().map(|s| (s.id, s)).collect::<BTreeMap<_, _>>();
+    fetched
+        .iter()
+        .filter_map(|new| {
+            let old = stored.get(&new.id)?;
+            if old.result == new.result && old.point == new.point {
+                return None;
+            }
+            Some(Rejudge {
+                submission_id: new.id,
+                detected_epoch_second,
+                contest_id: new.contest_id.clone(),
+                problem_id: new.problem_id.clone(),
+                user_id: new.user_id.clone(),
+                old_result: old.result.clone(),
+                new_result: new.result.clone(),
+                old_point: old.point,
+                new_point: new.point,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(id: i64, result: &str, point: f64) -> Submission {
+        Submission {
+            id,
+            contest_id: "contest".to_string(),
+            problem_id: "problem".to_string(),
+            user_id: "user".to_string(),
+            result: result.to_string(),
+            point,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_rejudges() {
+        let fetched = vec![
+            submission(1, "AC", 100.0),
+            submission(2, "WA", 0.0),
+            submission(3, "AC", 50.0),
+            submission(4, "AC", 100.0),
+        ];
+        let stored = vec![
+            submission(1, "AC", 100.0),
+            submission(2, "AC", 100.0),
+            submission(3, "AC", 100.0),
+        ];
+        let rejudges = detect_rejudges(&fetched, &stored, 1000);
+        assert_eq!(
+            rejudges,
+            vec![
+                Rejudge {
+                    submission_id: 2,
+                    detected_epoch_second: 1000,
+                    contest_id: "contest".to_string(),
+                    problem_id: "problem".to_string(),
+                    user_id: "user".to_string(),
+                    old_result: "AC".to_string(),
+                    new_result: "WA".to_string(),
+                    old_point: 100.0,
+                    new_point: 0.0,
+                },
+                Rejudge {
+                    submission_id: 3,
+                    detected_epoch_second: 1000,
+                    contest_id: "contest".to_string(),
+                    problem_id: "problem".to_string(),
+                    user_id: "user".to_string(),
+                    old_result: "AC".to_string(),
+                    new_result: "AC".to_string(),
+                    old_point: 100.0,
+                    new_point: 50.0,
+                },
+            ]
+        );
+    }
+}
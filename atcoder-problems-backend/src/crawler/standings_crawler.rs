@@ -0,0 +1,303 @@
+use crate::crawler::AtCoderFetcher;
+use anyhow::Result;
+use sql_client::contest_result::ContestResultClient;
+use sql_client::models::Contest;
+use sql_client::simple_client::SimpleClient;
+use std::collections::BTreeSet;
+
+/// Crawls the final standings of the finished contests, and the rating history of the rated
+/// participants.
+///
+/// The histories are fetched only for the participants whose stored history does not include the
+/// contest yet, so the ones which failed are retried in the next run.
+pub struct StandingsCrawler<C, F> {
+    db: C,
+    fetcher: F,
+    from_second: i64,
+    current_time_second: i64,
+}
+
+impl<C, F> StandingsCrawler<C, F>
+where
+    C: SimpleClient + ContestResultClient,
+    F: AtCoderFetcher,
+{
+    /// Targets the contests which ended between `from_second` and `current_time_second`.
+    pub fn new(db: C, fetcher: F, from_second: i64, current_time_second: i64) -> Self {
+        Self {
+            db,
+            fetcher,
+            from_second,
+            current_time_second,
+        }
+    }
+
+    pub async fn crawl(&self) -> Result<()> {
+        log::info!("Starting...");
+        let contests = self.db.load_contests_ending_after(self.from_second).await?;
+        let crawled = self
+            .db
+            .load_standings_contest_ids()
+            .await?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let targets = extract_target_contests(&contests, &crawled, self.current_time_second);
+        log::info!("There are {} contests to crawl.", targets.len());
+
+        for contest in targets {
+            if let Err(e) = self.crawl_standings(contest).await {
+                log::error!("Failed to crawl the standings of {}: {:?}", contest.id, e);
+            }
+        }
+        for contest in contests.iter().filter(|c| c.is_rated()) {
+            if let Err(e) = self.crawl_rating_histories(contest).await {
+                log::error!("Failed to crawl the histories of {}: {:?}", contest.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn crawl_standings(&self, contest: &Contest) -> Result<()> {
+        let standings = match self.fetcher.fetch_standings(&contest.id).await? {
+            Some(standings) => standings,
+            None => {
+                log::info!("The standings of {} are not fixed yet.", contest.id);
+                return Ok(());
+            }
+        };
+
+        log::info!(
+            "Saving {} rows of the standings of {}",
+            standings.len(),
+            contest.id
+        );
+        self.db
+            .update_contest_standings(&contest.id, &standings)
+            .await?;
+        Ok(())
+    }
+
+    async fn crawl_rating_histories(&self, contest: &Contest) -> Result<()> {
+        let user_ids = self
+            .db
+            .load_rated_users_without_history(&contest.id)
+            .await?;
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        log::info!(
+            "Fetching the histories of {} users in {}",
+            user_ids.len(),
+            contest.id
+        );
+        for user_id in user_ids {
+            match self.fetcher.fetch_rating_history(&user_id).await {
+                Ok(history) => self.db.update_rating_history(&user_id, &history).await?,
+                Err(e) => log::error!("Failed to fetch the history of {}: {:?}", user_id, e),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn extract_target_contests<'a>(
+    contests: &'a [Contest],
+    crawled: &BTreeSet<String>,
+    current_time_second: i64,
+) -> Vec<&'a Contest> {
+    contests
+        .iter()
+        .filter(|c| c.start_epoch_second + c.duration_second <= current_time_second)
+        .filter(|c| !crawled.contains(&c.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use atcoder_client::{AtCoderProblemDetail, ContestTypeSpecifier};
+    use sql_client::models::{
+        ContestProblem, ContestStanding, Problem, RatingHistoryEntry, Submission,
+    };
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_extract_target_contests() {
+        let contest = |id: &str, start_epoch_second: i64| Contest {
+            id: id.to_owned(),
+            start_epoch_second,
+            duration_second: 100,
+            ..Default::default()
+        };
+        let contests = vec![
+            contest("finished", 0),
+            contest("crawled", 0),
+            contest("running", 950),
+        ];
+        let crawled = vec!["crawled".to_owned()].into_iter().collect();
+        let targets = extract_target_contests(&contests, &crawled, 1000);
+        assert_eq!(targets, vec![&contests[0]]);
+    }
+
+    const NOW: i64 = 2_000_000_000;
+
+    #[derive(Default)]
+    struct MockDB {
+        standings: Mutex<Vec<ContestStanding>>,
+        histories: Mutex<BTreeMap<String, Vec<RatingHistoryEntry>>>,
+    }
+
+    #[async_trait]
+    impl SimpleClient for MockDB {
+        async fn insert_contests(&self, _: &[Contest]) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn insert_problems(&self, _: &[Problem]) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn load_problems(&self) -> Result<Vec<Problem>> {
+            unimplemented!()
+        }
+        async fn load_contests(&self) -> Result<Vec<Contest>> {
+            unimplemented!()
+        }
+        async fn load_contests_ending_after(&self, _: i64) -> Result<Vec<Contest>> {
+            Ok(vec![Contest {
+                id: "contest".to_owned(),
+                start_epoch_second: NOW - 1000,
+                duration_second: 100,
+                rate_change: "All".to_owned(),
+                ..Default::default()
+            }])
+        }
+    }
+
+    #[async_trait]
+    impl ContestResultClient for MockDB {
+        async fn update_contest_standings(
+            &self,
+            _: &str,
+            standings: &[ContestStanding],
+        ) -> Result<()> {
+            *self.standings.lock().unwrap() = standings.to_vec();
+            Ok(())
+        }
+        async fn load_contest_standings(&self, _: &str) -> Result<Vec<ContestStanding>> {
+            unimplemented!()
+        }
+        async fn load_user_standings(&self, _: &str) -> Result<Vec<ContestStanding>> {
+            unimplemented!()
+        }
+        async fn load_standings_contest_ids(&self) -> Result<Vec<String>> {
+            Ok(vec![])
+        }
+        async fn load_rated_users_without_history(&self, contest_id: &str) -> Result<Vec<String>> {
+            let histories = self.histories.lock().unwrap();
+            let user_ids = self
+                .standings
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| s.is_rated)
+                .filter(|s| {
+                    histories
+                        .get(&s.user_id)
+                        .map(|h| h.iter().all(|h| h.contest_id != contest_id))
+                        .unwrap_or(true)
+                })
+                .map(|s| s.user_id.clone())
+                .collect();
+            Ok(user_ids)
+        }
+        async fn update_rating_history(
+            &self,
+            user_id: &str,
+            history: &[RatingHistoryEntry],
+        ) -> Result<()> {
+            self.histories
+                .lock()
+                .unwrap()
+                .insert(user_id.to_owned(), history.to_vec());
+            Ok(())
+        }
+        async fn load_rating_history(&self, _: &str) -> Result<Vec<RatingHistoryEntry>> {
+            unimplemented!()
+        }
+    }
+
+    struct MockFetcher;
+
+    #[async_trait]
+    impl AtCoderFetcher for MockFetcher {
+        async fn fetch_submissions(&self, _: &str, _: u32) -> Result<(Vec<Submission>, u32)> {
+            unimplemented!()
+        }
+        async fn fetch_contests(&self, _: ContestTypeSpecifier) -> Result<Vec<Contest>> {
+            unimplemented!()
+        }
+        async fn fetch_problems(&self, _: &str) -> Result<(Vec<Problem>, Vec<ContestProblem>)> {
+            unimplemented!()
+        }
+        async fn fetch_problem_detail(&self, _: &str, _: &str) -> Result<AtCoderProblemDetail> {
+            unimplemented!()
+        }
+        async fn fetch_standings(&self, contest_id: &str) -> Result<Option<Vec<ContestStanding>>> {
+            let standings = ["user1", "user2"]
+                .iter()
+                .enumerate()
+                .map(|(i, user_id)| ContestStanding {
+                    contest_id: contest_id.to_owned(),
+                    user_id: user_id.to_string(),
+                    rank: i as i32 + 1,
+                    score: 100.0,
+                    penalty: 0,
+                    elapsed_second: 60,
+                    is_rated: true,
+                    old_rating: 1200,
+                })
+                .collect();
+            Ok(Some(standings))
+        }
+        async fn fetch_rating_history(&self, user_id: &str) -> Result<Vec<RatingHistoryEntry>> {
+            if user_id == "user1" {
+                return Err(anyhow::anyhow!("Failed to fetch {}", user_id));
+            }
+            Ok(vec![RatingHistoryEntry {
+                user_id: user_id.to_owned(),
+                contest_id: "contest".to_owned(),
+                is_rated: true,
+                place: 2,
+                old_rating: 1200,
+                new_rating: 1250,
+                performance: 1500,
+                inner_performance: 1500,
+                end_epoch_second: NOW - 900,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_crawl_with_history_failure() {
+        let crawler = StandingsCrawler::new(MockDB::default(), MockFetcher, 0, NOW);
+        let rt = actix_web::rt::Runtime::new().unwrap();
+        rt.block_on(crawler.crawl()).unwrap();
+
+        assert_eq!(
+            crawler.db.standings.lock().unwrap().len(),
+            2,
+            "The standings should be stored even if a history is not fetched."
+        );
+        let histories = crawler.db.histories.lock().unwrap();
+        assert!(!histories.contains_key("user1"));
+        assert!(histories.contains_key("user2"));
+        drop(histories);
+        assert_eq!(
+            rt.block_on(crawler.db.load_rated_users_without_history("contest"))
+                .unwrap(),
+            vec!["user1"],
+            "The failed history should be fetched in the next run."
+        );
+    }
+}
@@ -1,18 +1,24 @@
 use crate::crawler::AtCoderFetcher;
 use anyhow::Result;
 use async_trait::async_trait;
-use atcoder_client::ContestTypeSpecifier;
-use sql_client::models::{Contest, ContestProblem, Problem, Submission};
+use atcoder_client::{AtCoderProblemDetail, ContestTypeSpecifier};
+use sql_client::models::{
+    Contest, ContestProblem, ContestStanding, Problem, RatingHistoryEntry, Submission,
+};
 
-pub(crate) struct MockFetcher<F: Fn(&str, u32) -> Vec<Submission>>(pub(crate) F);
+pub(crate) struct MockFetcher<F: Fn(&str, u32) -> Result<Vec<Submission>>>(pub(crate) F);
 
 #[async_trait]
 impl<F> AtCoderFetcher for MockFetcher<F>
 where
-    F: Fn(&str, u32) -> Vec<Submission> + Send + Sync,
+    F: Fn(&str, u32) -> Result<Vec<Submission>> + Send + Sync,
 {
-    async fn fetch_submissions(&self, contest_id: &str, page: u32) -> (Vec<Submission>, u32) {
-        ((self.0)(contest_id, page), 0)
+    async fn fetch_submissions(
+        &self,
+        contest_id: &str,
+        page: u32,
+    ) -> Result<(Vec<Submission>, u32)> {
+        Ok(((self.0)(contest_id, page)?, 0))
     }
 
     async fn fetch_contests(&self, _: ContestTypeSpecifier) -> Result<Vec<Contest>> {
@@ -22,4 +28,16 @@ where
     async fn fetch_problems(&self, _: &str) -> Result<(Vec<Problem>, Vec<ContestProblem>)> {
         unimplemented!()
     }
+
+    async fn fetch_problem_detail(&self, _: &str, _: &str) -> Result<AtCoderProblemDetail> {
+        unimplemented!()
+    }
+
+    async fn fetch_standings(&self, _: &str) -> Result<Option<Vec<ContestStanding>>> {
+        unimplemented!()
+    }
+
+    async fn fetch_rating_history(&self, _: &str) -> Result<Vec<RatingHistoryEntry>> {
+        unimplemented!()
+    }
 }
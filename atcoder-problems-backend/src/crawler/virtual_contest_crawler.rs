@@ -7,7 +7,6 @@ use sql_client::contest_problem::ContestProblemClient;
 use sql_client::internal::virtual_contest_manager::VirtualContestManager;
 use sql_client::submission_client::SubmissionClient;
 use std::collections::BTreeSet;
-use std::{thread, time};
 
 const CRAWLED_STREAK: usize = 3;
 const CONTEST_LENGTH_LIMIT_SECOND: i64 = 60 * 60 * 5;
@@ -66,7 +65,14 @@ where
             let mut streak = 0;
             for page in 1.. {
                 log::info!("Fetching from {} {} ...", contest, page);
-                let (submissions, max_page) = self.fetcher.fetch_submissions(&contest, page).await;
+                let (submissions, max_page) =
+                    match self.fetcher.fetch_submissions(&contest, page).await {
+                        Ok(fetched) => fetched,
+                        Err(e) => {
+                            log::error!("Skipping {}: {:?}", contest, e);
+                            break;
+                        }
+                    };
                 if submissions.is_empty() {
                     log::info!("No submission is fetched");
                     break;
@@ -89,8 +95,6 @@ where
                 if streak >= CRAWLED_STREAK || page == max_page {
                     break;
                 }
-                log::info!("Sleeping for 200ms");
-                thread::sleep(time::Duration::from_millis(200));
             }
             log::info!("Finished {}", contest);
         }
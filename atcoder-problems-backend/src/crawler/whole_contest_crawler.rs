@@ -1,9 +1,10 @@
 use crate::crawler::AtCoderFetcher;
 use anyhow::Result;
 
+use chrono::Utc;
 use log::info;
+use sql_client::crawl_checkpoint::CrawlCheckpointClient;
 use sql_client::submission_client::SubmissionClient;
-use std::{thread, time};
 
 pub struct WholeContestCrawler<C, F> {
     db: C,
@@ -27,8 +28,10 @@ where
     pub async fn crawl(&self) -> Result<()> {
         for page in 1.. {
             info!("Crawling {} {} ...", self.contest_id, page);
-            let (submissions, max_page) =
-                self.fetcher.fetch_submissions(&self.contest_id, page).await;
+            let (submissions, max_page) = self
+                .fetcher
+                .fetch_submissions(&self.contest_id, page)
+                .await?;
             if submissions.is_empty() {
                 info!("{}-{} is empty.", self.contest_id, page);
                 break;
@@ -39,7 +42,6 @@ where
             }
 
             self.db.update_submissions(&submissions).await?;
-            thread::sleep(time::Duration::from_millis(200));
         }
 
         info!("Finished");
@@ -47,14 +49,64 @@ where
     }
 }
 
+impl<C, F> WholeContestCrawler<C, F>
+where
+    C: SubmissionClient + CrawlCheckpointClient,
+    F: AtCoderFetcher,
+{
+    /// Crawls from the page after the last stored one, recording the progress after every page.
+    ///
+    /// The crawl is marked as finished only after the last page, so a failed fetch leaves the
+    /// checkpoint at the last stored page.
+    pub async fn crawl_from_checkpoint(&self) -> Result<()> {
+        let start_page = match self.db.load_crawl_checkpoint(&self.contest_id).await? {
+            Some(checkpoint) if checkpoint.finished_epoch_second.is_none() => {
+                checkpoint.last_page as u32 + 1
+            }
+            _ => 1,
+        };
+        if start_page > 1 {
+            info!("Resuming {} from {}", self.contest_id, start_page);
+        }
+
+        for page in start_page.. {
+            info!("Crawling {} {} ...", self.contest_id, page);
+            let (submissions, max_page) = self
+                .fetcher
+                .fetch_submissions(&self.contest_id, page)
+                .await?;
+            if submissions.is_empty() {
+                info!("{}-{} is empty.", self.contest_id, page);
+                break;
+            }
+
+            self.db.update_submissions(&submissions).await?;
+            self.db
+                .save_crawl_progress(&self.contest_id, page as i32)
+                .await?;
+            if page == max_page {
+                break;
+            }
+        }
+
+        self.db
+            .finish_crawl(&self.contest_id, Utc::now().timestamp())
+            .await?;
+        info!("Finished crawling {}", self.contest_id);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crawler::utils::MockFetcher;
     use async_trait::async_trait;
+    use sql_client::crawl_checkpoint::CrawlCheckpoint;
     use sql_client::models::Submission;
     use sql_client::submission_client::SubmissionRequest;
     use std::ops::Range;
+    use std::sync::Mutex;
 
     struct MockDB;
 
@@ -79,15 +131,116 @@ mod tests {
     fn whole_contest_crawler() {
         let fetcher = MockFetcher(|_, page| {
             if page == 1 {
-                vec![Submission {
+                Ok(vec![Submission {
                     ..Default::default()
-                }]
+                }])
             } else {
-                vec![]
+                Ok(vec![])
             }
         });
         let crawler = WholeContestCrawler::new(MockDB, fetcher, "contest-id");
         let rt = actix_web::rt::Runtime::new().unwrap();
         assert!(rt.block_on(crawler.crawl()).is_ok());
     }
+
+    struct MockCheckpointDB(Mutex<Option<CrawlCheckpoint>>);
+
+    #[async_trait]
+    impl SubmissionClient for MockCheckpointDB {
+        async fn get_submissions<'a>(&self, _: SubmissionRequest<'a>) -> Result<Vec<Submission>> {
+            unimplemented!()
+        }
+        async fn update_submissions(&self, submissions: &[Submission]) -> Result<usize> {
+            Ok(submissions.len())
+        }
+        async fn count_stored_submissions(&self, _: &[i64]) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn get_user_submission_count(&self, _: &str, _: Range<i64>) -> Result<usize> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl CrawlCheckpointClient for MockCheckpointDB {
+        async fn load_crawl_checkpoints(&self) -> Result<Vec<CrawlCheckpoint>> {
+            unimplemented!()
+        }
+        async fn load_crawl_checkpoint(&self, _: &str) -> Result<Option<CrawlCheckpoint>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+        async fn save_crawl_progress(&self, contest_id: &str, page: i32) -> Result<()> {
+            *self.0.lock().unwrap() = Some(CrawlCheckpoint {
+                contest_id: contest_id.to_string(),
+                last_page: page,
+                finished_epoch_second: None,
+            });
+            Ok(())
+        }
+        async fn finish_crawl(&self, _: &str, finished_epoch_second: i64) -> Result<()> {
+            let mut checkpoint = self.0.lock().unwrap();
+            let checkpoint = checkpoint.as_mut().unwrap();
+            checkpoint.last_page = 0;
+            checkpoint.finished_epoch_second = Some(finished_epoch_second);
+            Ok(())
+        }
+        async fn reset_crawl_checkpoints(&self, _: &[String]) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_crawl_from_checkpoint() {
+        let fetcher = MockFetcher(|_, page| {
+            assert!(
+                page >= 3,
+                "The pages before the checkpoint should be skipped."
+            );
+            if page <= 4 {
+                Ok(vec![Submission {
+                    id: page as i64,
+                    ..Default::default()
+                }])
+            } else {
+                Ok(vec![])
+            }
+        });
+        let db = MockCheckpointDB(Mutex::new(Some(CrawlCheckpoint {
+            contest_id: "contest-id".to_string(),
+            last_page: 2,
+            finished_epoch_second: None,
+        })));
+        let crawler = WholeContestCrawler::new(db, fetcher, "contest-id");
+        let rt = actix_web::rt::Runtime::new().unwrap();
+        rt.block_on(crawler.crawl_from_checkpoint()).unwrap();
+
+        let checkpoint = crawler.db.0.lock().unwrap().clone().unwrap();
+        assert_eq!(checkpoint.last_page, 0);
+        assert!(checkpoint.finished_epoch_second.is_some());
+    }
+
+    #[test]
+    fn test_crawl_from_checkpoint_failure() {
+        let fetcher = MockFetcher(|_, page| {
+            if page <= 3 {
+                Ok(vec![Submission {
+                    id: page as i64,
+                    ..Default::default()
+                }])
+            } else {
+                Err(anyhow::anyhow!("Failed to fetch the page {}", page))
+            }
+        });
+        let db = MockCheckpointDB(Mutex::new(None));
+        let crawler = WholeContestCrawler::new(db, fetcher, "contest-id");
+        let rt = actix_web::rt::Runtime::new().unwrap();
+        assert!(rt.block_on(crawler.crawl_from_checkpoint()).is_err());
+
+        let checkpoint = crawler.db.0.lock().unwrap().clone().unwrap();
+        assert_eq!(
+            checkpoint.last_page, 3,
+            "The checkpoint should stay at the last stored page."
+        );
+        assert!(checkpoint.finished_epoch_second.is_none());
+    }
 }
@@ -1,5 +1,8 @@
 pub mod config;
 pub mod crawler;
+pub mod dump;
 pub mod s3;
+pub mod scheduler;
 pub mod server;
+pub mod updater;
 pub mod utils;
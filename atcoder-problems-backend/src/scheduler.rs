@@ -0,0 +1,350 @@
+use crate::crawler::{
+    FixCrawler, ProblemCrawler, ProblemDetailCrawler, RecentCrawler, RejudgeCrawler,
+    StandingsCrawler, VirtualContestCrawler,
+};
+use crate::dump::dump_json;
+use crate::s3::S3Client;
+use crate::updater::{batch_update, delta_update};
+use anyhow::{anyhow, Context, Result};
+use atcoder_client::AtCoderClient;
+use chrono::Utc;
+use rand::thread_rng;
+use sql_client::scheduler_job::{JobLock, JobStatusClient};
+use sql_client::PgPool;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+
+const FIX_RANGE_SECOND: i64 = 10 * 60;
+const STANDINGS_RANGE_SECOND: i64 = 7 * 24 * 60 * 60;
+const REJUDGE_SAMPLE_CONTESTS: usize = 5;
+const PROBLEM_DETAIL_BATCH_SIZE: i64 = 20;
+
+/// The crawlers and updaters run periodically by the scheduler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Job {
+    RecentCrawler,
+    FixCrawler,
+    ProblemCrawler,
+    ProblemDetailCrawler,
+    VirtualContestCrawler,
+    StandingsCrawler,
+    RejudgeCrawler,
+    BatchUpdate,
+    DeltaUpdate,
+    DumpJson,
+}
+
+impl Job {
+    pub const ALL: [Job; 10] = [
+        Job::RecentCrawler,
+        Job::FixCrawler,
+        Job::ProblemCrawler,
+        Job::ProblemDetailCrawler,
+        Job::VirtualContestCrawler,
+        Job::StandingsCrawler,
+        Job::RejudgeCrawler,
+        Job::BatchUpdate,
+        Job::DeltaUpdate,
+        Job::DumpJson,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Job::RecentCrawler => "recent_crawler",
+            Job::FixCrawler => "fix_crawler",
+            Job::ProblemCrawler => "problem_crawler",
+            Job::ProblemDetailCrawler => "problem_detail_crawler",
+            Job::VirtualContestCrawler => "virtual_contest_crawler",
+            Job::StandingsCrawler => "standings_crawler",
+            Job::RejudgeCrawler => "rejudge_crawler",
+            Job::BatchUpdate => "batch_update",
+            Job::DeltaUpdate => "delta_update",
+            Job::DumpJson => "dump_json",
+        }
+    }
+
+    /// The name of the lock which the job holds while running.
+    ///
+    /// The updaters share one lock, since both of them rewrite the aggregates and the watermark.
+    pub fn lock_name(self) -> &'static str {
+        match self {
+            Job::BatchUpdate | Job::DeltaUpdate => "aggregate_update",
+            _ => self.name(),
+        }
+    }
+
+    pub fn default_interval(self) -> Duration {
+        let seconds = match self {
+            Job::RecentCrawler => 10,
+            Job::FixCrawler => 60,
+            Job::ProblemCrawler => 60 * 60,
+            Job::ProblemDetailCrawler => 10 * 60,
+            Job::VirtualContestCrawler => 10,
+            Job::StandingsCrawler => 60 * 60,
+            Job::RejudgeCrawler => 10 * 60,
+            Job::BatchUpdate => 24 * 60 * 60,
+            Job::DeltaUpdate => 60,
+            Job::DumpJson => 60 * 60,
+        };
+        Duration::from_secs(seconds)
+    }
+}
+
+impl FromStr for Job {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Job::ALL
+            .iter()
+            .copied()
+            .find(|job| job.name() == s)
+            .ok_or_else(|| anyhow!("Unknown job: {}", s))
+    }
+}
+
+/// Parses the intervals in the form of `recent_crawler=30,dump_json=0`.
+///
+/// The jobs which are not listed run on their default intervals, and `0` disables a job.
+pub fn parse_intervals(config: &str) -> Result<BTreeMap<Job, Duration>> {
+    let mut intervals = Job::ALL
+        .iter()
+        .map(|&job| (job, job.default_interval()))
+        .collect::<BTreeMap<_, _>>();
+    for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, seconds) = entry
+            .split_once('=')
+            .with_context(|| format!("Invalid interval: {}", entry))?;
+        let job = name.trim().parse::<Job>()?;
+        let seconds = seconds
+            .trim()
+            .parse::<u64>()
+            .with_context(|| format!("Invalid interval: {}", entry))?;
+        if seconds == 0 {
+            intervals.remove(&job);
+        } else {
+            intervals.insert(job, Duration::from_secs(seconds));
+        }
+    }
+    Ok(intervals)
+}
+
+/// Runs `f` while holding the advisory lock of `lock_name`, and records the result of `job_name`.
+///
+/// The lock is held on a connection of `lock_pool`, so that the locks do not take the connections
+/// of `pool` away from the jobs. Returns `false` without running `f` if the lock is held in
+/// another process. The lock is released even if recording fails or `f` panics.
+pub async fn run_exclusively<F, Fut>(
+    pool: &PgPool,
+    lock_pool: &PgPool,
+    lock_name: &str,
+    job_name: &str,
+    f: F,
+) -> Result<bool>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let lock = match JobLock::try_acquire(lock_pool, lock_name).await? {
+        Some(lock) => lock,
+        None => return Ok(false),
+    };
+
+    let result = match pool
+        .record_job_started(job_name, Utc::now().timestamp())
+        .await
+    {
+        Ok(()) => f().await,
+        Err(e) => Err(e.context("Failed to record the start")),
+    };
+    let now = Utc::now().timestamp();
+    let recorded = match &result {
+        Ok(()) => pool.record_job_success(job_name, now).await,
+        Err(e) => {
+            log::error!("{} failed: {:?}", job_name, e);
+            pool.record_job_error(job_name, now, &format!("{:?}", e))
+                .await
+        }
+    };
+    lock.release().await?;
+    recorded?;
+    Ok(true)
+}
+
+/// Calls `f` forever, waiting for `interval` after each call.
+///
+/// Each call runs in its own task, so a panic is logged and `f` is called again after `interval`.
+pub async fn run_periodically<F, Fut>(name: &str, interval: Duration, f: F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    loop {
+        if let Err(e) = actix_web::rt::spawn(f()).await {
+            log::error!("{} panicked: {:?}", name, e);
+        }
+        actix_web::rt::time::sleep(interval).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct Scheduler {
+    pool: PgPool,
+    lock_pool: PgPool,
+    client: AtCoderClient,
+}
+
+impl Scheduler {
+    /// `lock_pool` holds a connection for each running job, so it needs as many connections as
+    /// the jobs.
+    pub fn new(pool: PgPool, lock_pool: PgPool, client: AtCoderClient) -> Self {
+        Self {
+            pool,
+            lock_pool,
+            client,
+        }
+    }
+
+    /// Runs every job repeatedly, waiting for its interval after each run.
+    pub async fn run(self, intervals: BTreeMap<Job, Duration>) {
+        let handles = intervals
+            .into_iter()
+            .map(|(job, interval)| {
+                let scheduler = self.clone();
+                actix_web::rt::spawn(async move {
+                    run_periodically(job.name(), interval, || {
+                        let scheduler = scheduler.clone();
+                        async move { scheduler.run_once(job).await }
+                    })
+                    .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            if let Err(e) = handle.await {
+                log::error!("{:?}", e);
+            }
+        }
+    }
+
+    async fn run_once(&self, job: Job) {
+        log::info!("Starting {}", job.name());
+        let ran = run_exclusively(
+            &self.pool,
+            &self.lock_pool,
+            job.lock_name(),
+            job.name(),
+            || self.run_job(job),
+        )
+        .await;
+        match ran {
+            Ok(true) => log::info!("Finished {}", job.name()),
+            Ok(false) => log::info!(
+                "Skipped {} since {} is held elsewhere",
+                job.name(),
+                job.lock_name()
+            ),
+            Err(e) => log::error!("Failed to schedule {}: {:?}", job.name(), e),
+        }
+    }
+
+    async fn run_job(&self, job: Job) -> Result<()> {
+        let pool = self.pool.clone();
+        let client = self.client.clone();
+        match job {
+            Job::RecentCrawler => RecentCrawler::new(pool, client).crawl().await,
+            Job::FixCrawler => {
+                let from = Utc::now().timestamp() - FIX_RANGE_SECOND;
+                FixCrawler::new(pool, client, from).crawl().await
+            }
+            Job::ProblemCrawler => ProblemCrawler::new(pool, client).crawl().await,
+            Job::ProblemDetailCrawler => {
+                let now = Utc::now().timestamp();
+                ProblemDetailCrawler::new(pool, client, PROBLEM_DETAIL_BATCH_SIZE, now)
+                    .crawl()
+                    .await
+            }
+            Job::VirtualContestCrawler => {
+                let mut rng = thread_rng();
+                VirtualContestCrawler::new(pool, client, &mut rng)
+                    .crawl()
+                    .await
+            }
+            Job::StandingsCrawler => {
+                let now = Utc::now().timestamp();
+                StandingsCrawler::new(pool, client, now - STANDINGS_RANGE_SECOND, now)
+                    .crawl()
+                    .await
+            }
+            Job::RejudgeCrawler => {
+                let mut rng = thread_rng();
+                let now = Utc::now().timestamp();
+                RejudgeCrawler::new(pool, client, &mut rng, REJUDGE_SAMPLE_CONTESTS, now)
+                    .crawl()
+                    .await
+            }
+            Job::BatchUpdate => batch_update(&pool).await,
+            Job::DeltaUpdate => delta_update(&pool).await,
+            Job::DumpJson => dump_json(&pool, &S3Client::new()?).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_intervals() {
+        let intervals = parse_intervals("").unwrap();
+        assert_eq!(intervals.len(), Job::ALL.len());
+        assert_eq!(intervals[&Job::DumpJson], Job::DumpJson.default_interval());
+
+        let intervals = parse_intervals("recent_crawler=30, dump_json=0").unwrap();
+        assert_eq!(intervals.len(), Job::ALL.len() - 1);
+        assert_eq!(intervals[&Job::RecentCrawler], Duration::from_secs(30));
+        assert!(!intervals.contains_key(&Job::DumpJson));
+
+        assert!(parse_intervals("unknown=10").is_err());
+        assert!(parse_intervals("recent_crawler").is_err());
+        assert!(parse_intervals("recent_crawler=-1").is_err());
+    }
+
+    #[test]
+    fn test_updaters_share_lock() {
+        assert_eq!(Job::BatchUpdate.lock_name(), Job::DeltaUpdate.lock_name());
+        assert_ne!(Job::RecentCrawler.lock_name(), Job::FixCrawler.lock_name());
+    }
+
+    #[test]
+    fn test_run_periodically_after_panic() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counted = count.clone();
+        let rt = actix_web::rt::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let job = run_periodically("job", Duration::from_millis(10), move || {
+                let count = counted.clone();
+                async move {
+                    if count.fetch_add(1, Ordering::SeqCst) == 0 {
+                        panic!("The first run panics.");
+                    }
+                }
+            });
+            let restarted = async {
+                while count.load(Ordering::SeqCst) < 2 {
+                    actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+                }
+            };
+            actix_web::rt::time::timeout(
+                Duration::from_secs(10),
+                future::select(Box::pin(job), Box::pin(restarted)),
+            )
+            .await
+            .expect("The job should run again after a panic.");
+        });
+    }
+}
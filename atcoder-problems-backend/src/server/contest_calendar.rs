@@ -0,0 +1,68 @@
+use crate::server::ical::{self, CalendarEvent};
+use actix_web::{error, web, HttpResponse, Result};
+use serde::Deserialize;
+use sql_client::{
+    models::{Contest, ContestSeries},
+    simple_client::SimpleClient,
+    PgPool,
+};
+
+/// Contests which ended within this duration are still listed in the calendar.
+const RECENT_DURATION_SECOND: i64 = 30 * 24 * 3600;
+
+#[derive(Deserialize)]
+pub(crate) struct Query {
+    rated: Option<bool>,
+    /// Comma separated list of `abc`, `arc`, `agc` and `other`.
+    series: Option<String>,
+}
+
+pub(crate) async fn get_contest_calendar(
+    pool: web::Data<PgPool>,
+    query: web::Query<Query>,
+) -> Result<HttpResponse> {
+    let series = query
+        .series
+        .as_deref()
+        .map(|series| {
+            series
+                .split(',')
+                .map(|s| s.parse::<ContestSeries>())
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .transpose()
+        .map_err(error::ErrorBadRequest)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let contests = pool
+        .load_contests_ending_after(now - RECENT_DURATION_SECOND)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let events = contests
+        .iter()
+        .filter(|contest| match query.rated {
+            Some(rated) => contest.is_rated() == rated,
+            None => true,
+        })
+        .filter(|contest| match &series {
+            Some(series) => series.contains(&contest.series()),
+            None => true,
+        })
+        .map(to_event)
+        .collect::<Vec<_>>();
+    let response = HttpResponse::Ok()
+        .content_type(ical::CONTENT_TYPE)
+        .body(ical::render_calendar("AtCoder Contests", &events, now));
+    Ok(response)
+}
+
+fn to_event(contest: &Contest) -> CalendarEvent {
+    CalendarEvent {
+        uid: format!("{}@atcoder.jp", contest.id),
+        title: contest.title.clone(),
+        description: format!("Rated: {}", contest.rate_change),
+        url: format!("https://atcoder.jp/contests/{}", contest.id),
+        start_epoch_second: contest.start_epoch_second,
+        end_epoch_second: contest.start_epoch_second + contest.duration_second,
+    }
+}
@@ -0,0 +1,52 @@
+use crate::server::MakeCors;
+
+use actix_web::{error, web, HttpResponse, Result};
+use serde::Deserialize;
+use sql_client::contest_result::ContestResultClient;
+use sql_client::PgPool;
+
+#[derive(Deserialize)]
+pub(crate) struct UserQuery {
+    user: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ContestQuery {
+    contest: String,
+}
+
+pub(crate) async fn get_user_rating_history(
+    pool: web::Data<PgPool>,
+    query: web::Query<UserQuery>,
+) -> Result<HttpResponse> {
+    let history = pool
+        .load_rating_history(&query.user)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let response = HttpResponse::Ok().make_cors().json(&history);
+    Ok(response)
+}
+
+pub(crate) async fn get_user_contest_standings(
+    pool: web::Data<PgPool>,
+    query: web::Query<UserQuery>,
+) -> Result<HttpResponse> {
+    let standings = pool
+        .load_user_standings(&query.user)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let response = HttpResponse::Ok().make_cors().json(&standings);
+    Ok(response)
+}
+
+pub(crate) async fn get_contest_standings(
+    pool: web::Data<PgPool>,
+    query: web::Query<ContestQuery>,
+) -> Result<HttpResponse> {
+    let standings = pool
+        .load_contest_standings(&query.contest)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let response = HttpResponse::Ok().make_cors().json(&standings);
+    Ok(response)
+}
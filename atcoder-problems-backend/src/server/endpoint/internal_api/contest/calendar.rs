@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use actix_web::{get, web, HttpResponse, Result};
+use sql_client::{
+    internal::{
+        user_manager::UserManager,
+        virtual_contest_manager::{VirtualContestInfo, VirtualContestManager},
+        virtual_contest_team_manager::VirtualContestTeamManager,
+    },
+    PgPool,
+};
+
+use crate::server::{
+    error::ApiResult,
+    ical::{self, CalendarEvent},
+};
+
+const CONTEST_URL_PREFIX: &str = "https://kenkoooo.com/atcoder/#/contest/show/";
+
+fn to_event(contest: &VirtualContestInfo) -> CalendarEvent {
+    CalendarEvent {
+        uid: format!("{}@virtual-contest.atcoder-problems", contest.id),
+        title: contest.title.clone(),
+        description: contest.memo.clone(),
+        url: format!("{}{}", CONTEST_URL_PREFIX, contest.id),
+        start_epoch_second: contest.start_epoch_second,
+        end_epoch_second: contest.start_epoch_second + contest.duration_second,
+    }
+}
+
+fn calendar_response(name: &str, contests: &[VirtualContestInfo]) -> HttpResponse {
+    let events = contests.iter().map(to_event).collect::<Vec<_>>();
+    let now = chrono::Utc::now().timestamp();
+    HttpResponse::Ok()
+        .content_type(ical::CONTENT_TYPE)
+        .body(ical::render_calendar(name, &events, now))
+}
+
+/// The unarchived contests which the user owns or has joined by themselves or through a team.
+/// Calendar applications cannot send the login cookie,
+/// so the user is identified by the secret calendar token in the path.
+#[get("/internal-api/contest/calendar/user/{calendar_token}")]
+pub async fn get_user_calendar(
+    pool: web::Data<PgPool>,
+    calendar_token: web::Path<String>,
+) -> Result<HttpResponse> {
+    let internal_user_id = pool
+        .get_user_by_calendar_token(&calendar_token)
+        .await
+        .map_internal_server_err()?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("The calendar token is invalid."))?;
+    let own_contests = pool
+        .get_own_contests(&internal_user_id)
+        .await
+        .map_internal_server_err()?;
+    let participated_contests = pool
+        .get_participated_contests(&internal_user_id)
+        .await
+        .map_internal_server_err()?;
+    let team_contests = pool
+        .get_team_participated_contests(&internal_user_id)
+        .await
+        .map_internal_server_err()?;
+    let contests = own_contests
+        .into_iter()
+        .chain(participated_contests)
+        .chain(team_contests)
+        .filter(|contest| !contest.is_archived)
+        .map(|contest| (contest.id.clone(), contest))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+    Ok(calendar_response(
+        "AtCoder Problems Virtual Contests",
+        &contests,
+    ))
+}
+
+#[get("/internal-api/contest/calendar/recent")]
+pub async fn get_recent_calendar(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let now = chrono::Utc::now().timestamp();
+    let contests = pool
+        .get_recent_contest_info()
+        .await
+        .map_internal_server_err()?
+        .into_iter()
+        .filter(|contest| contest.start_epoch_second + contest.duration_second > now)
+        .collect::<Vec<_>>();
+    Ok(calendar_response(
+        "AtCoder Problems Public Virtual Contests",
+        &contests,
+    ))
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use actix_web::{get, http::header, web, HttpResponse, Result};
+use futures_util::{future, stream, StreamExt};
+use sql_client::{
+    internal::{
+        virtual_contest_manager::VirtualContestManager,
+        virtual_contest_team_manager::VirtualContestTeamManager,
+    },
+    PgPool,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::server::{
+    error::{ApiResult, VirtualContestApiResult},
+    submission_broadcaster::SubmissionBroadcaster,
+};
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Streams the submissions of the participants to the contest problems as Server-Sent Events.
+///
+/// The participants are fixed when the stream opens, and the stream is closed after the contest.
+#[get("/internal-api/contest/events/{contest_id}")]
+pub async fn get_events(
+    pool: web::Data<PgPool>,
+    broadcaster: web::Data<SubmissionBroadcaster>,
+    contest_id: web::Path<String>,
+) -> Result<HttpResponse> {
+    // Subscribe first not to miss the submissions stored while loading the contest.
+    let receiver = broadcaster.subscribe();
+
+    let info = pool
+        .get_single_contest_info(&contest_id)
+        .await
+        .map_virtual_contest_err()?;
+    let mut users = pool
+        .get_single_contest_participants(&contest_id)
+        .await
+        .map_internal_server_err()?
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    let teams = pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .map_internal_server_err()?;
+    users.extend(teams.into_iter().flat_map(|team| team.members));
+    let problems = pool
+        .get_single_contest_problems(&contest_id)
+        .await
+        .map_internal_server_err()?
+        .into_iter()
+        .map(|item| item.id)
+        .collect::<BTreeSet<_>>();
+    let start_epoch_second = info.start_epoch_second;
+    let end_epoch_second = info.start_epoch_second + info.duration_second;
+
+    let submissions = stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(submissions) => return Some((submissions, receiver)),
+                // The dropped submissions can be fetched by reloading the standings.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .flat_map(move |submissions| {
+        let events = submissions
+            .iter()
+            .filter(|s| users.contains(&s.user_id) && problems.contains(&s.problem_id))
+            .filter(|s| start_epoch_second <= s.epoch_second && s.epoch_second <= end_epoch_second)
+            .map(|s| {
+                let data = serde_json::to_string(s).expect("Submission is serializable");
+                format!("event: submission\ndata: {}\n\n", data)
+            })
+            .collect::<Vec<_>>();
+        stream::iter(events)
+    });
+    let keep_alive = stream::unfold(
+        actix_web::rt::time::interval(KEEP_ALIVE_INTERVAL),
+        |mut interval| async move {
+            interval.tick().await;
+            Some((": keep-alive\n\n".to_string(), interval))
+        },
+    );
+    let events = stream::select(submissions, keep_alive)
+        .take_while(move |_| future::ready(chrono::Utc::now().timestamp() <= end_epoch_second))
+        .map(|event| Ok::<_, actix_web::Error>(web::Bytes::from(event)));
+
+    let response = HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events);
+    Ok(response)
+}
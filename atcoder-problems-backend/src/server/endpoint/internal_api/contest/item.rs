@@ -5,7 +5,7 @@ use sql_client::{
     PgPool,
 };
 
-use crate::server::{error::ApiResult, middleware::github_auth::GithubToken};
+use crate::server::{error::VirtualContestApiResult, middleware::github_auth::GithubToken};
 
 #[derive(Deserialize)]
 pub struct UpdateItemsQuery {
@@ -22,7 +22,7 @@ pub async fn update_items(
     let user_id = token.id.to_string();
     pool.update_items(&query.contest_id, &query.problems, &user_id)
         .await
-        .map_internal_server_err()?;
+        .map_virtual_contest_err()?;
     let response = HttpResponse::Ok().finish();
     Ok(response)
 }
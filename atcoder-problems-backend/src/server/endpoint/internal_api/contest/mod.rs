@@ -1,15 +1,25 @@
+pub mod calendar;
+pub mod events;
 pub mod item;
+pub mod standings;
+pub mod team;
 
 use actix_web::{get, post, web, HttpResponse, Responder, Result};
 use serde::{Deserialize, Serialize};
 use sql_client::{
-    internal::virtual_contest_manager::{
-        VirtualContestInfo, VirtualContestItem, VirtualContestManager,
+    internal::{
+        virtual_contest_manager::{
+            VirtualContestInfo, VirtualContestItem, VirtualContestManager, VirtualContestMode,
+        },
+        virtual_contest_team_manager::{VirtualContestTeam, VirtualContestTeamManager},
     },
     PgPool,
 };
 
-use crate::server::{error::ApiResult, middleware::github_auth::GithubToken};
+use crate::server::{
+    error::{ApiResult, VirtualContestApiResult},
+    middleware::github_auth::GithubToken,
+};
 
 #[derive(Deserialize)]
 pub struct CreateContestQuery {
@@ -17,7 +27,7 @@ pub struct CreateContestQuery {
     memo: String,
     start_epoch_second: i64,
     duration_second: i64,
-    mode: Option<String>,
+    mode: Option<VirtualContestMode>,
     is_public: Option<bool>,
     penalty_second: i64,
 }
@@ -36,7 +46,7 @@ pub async fn create_contest(
             &user_id,
             query.start_epoch_second,
             query.duration_second,
-            query.mode.as_deref(),
+            query.mode.unwrap_or(VirtualContestMode::Normal),
             query.is_public.unwrap_or(true),
             query.penalty_second,
         )
@@ -47,6 +57,28 @@ pub async fn create_contest(
     Ok(response)
 }
 
+#[derive(Deserialize)]
+pub struct CloneContestQuery {
+    contest_id: String,
+    start_epoch_second: i64,
+}
+
+#[post("/internal-api/contest/clone")]
+pub async fn clone_contest(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<CloneContestQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    let contest_id = pool
+        .clone_contest(&query.contest_id, &user_id, query.start_epoch_second)
+        .await
+        .map_virtual_contest_err()?;
+    let body = serde_json::json!({ "contest_id": contest_id });
+    let response = HttpResponse::Ok().json(&body);
+    Ok(response)
+}
+
 #[derive(Deserialize)]
 pub struct UpdateContestQuery {
     id: String,
@@ -54,30 +86,31 @@ pub struct UpdateContestQuery {
     memo: String,
     start_epoch_second: i64,
     duration_second: i64,
-    mode: Option<String>,
+    mode: Option<VirtualContestMode>,
     is_public: Option<bool>,
     penalty_second: i64,
 }
 
 #[post("/internal-api/contest/update")]
 pub async fn update_contest(
-    _: web::ReqData<GithubToken>,
+    token: web::ReqData<GithubToken>,
     pool: web::Data<PgPool>,
     query: web::Json<UpdateContestQuery>,
 ) -> Result<impl Responder> {
-    // TODO authorize
+    let user_id = token.id.to_string();
     pool.update_contest(
         &query.id,
+        &user_id,
         &query.title,
         &query.memo,
         query.start_epoch_second,
         query.duration_second,
-        query.mode.as_deref(),
+        query.mode.unwrap_or(VirtualContestMode::Normal),
         query.is_public.unwrap_or(true),
         query.penalty_second,
     )
     .await
-    .map_internal_server_err()?;
+    .map_virtual_contest_err()?;
     let response = HttpResponse::Ok().finish();
     Ok(response)
 }
@@ -92,11 +125,12 @@ pub async fn get_single_contest(
         info: VirtualContestInfo,
         problems: Vec<VirtualContestItem>,
         participants: Vec<String>,
+        teams: Vec<VirtualContestTeam>,
     }
     let info = pool
         .get_single_contest_info(&contest_id)
         .await
-        .map_internal_server_err()?;
+        .map_virtual_contest_err()?;
     let participants = pool
         .get_single_contest_participants(&contest_id)
         .await
@@ -105,10 +139,15 @@ pub async fn get_single_contest(
         .get_single_contest_problems(&contest_id)
         .await
         .map_internal_server_err()?;
+    let teams = pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .map_internal_server_err()?;
     let contest = VirtualContestDetails {
         info,
         problems,
         participants,
+        teams,
     };
     let response = HttpResponse::Ok().json(&contest);
     Ok(response)
@@ -119,16 +158,22 @@ pub struct SingleContestQuery {
     contest_id: String,
 }
 
+#[derive(Deserialize)]
+pub struct JoinContestQuery {
+    contest_id: String,
+    invite_token: Option<String>,
+}
+
 #[post("/internal-api/contest/join")]
 pub async fn join_contest(
     token: web::ReqData<GithubToken>,
     pool: web::Data<PgPool>,
-    query: web::Json<SingleContestQuery>,
+    query: web::Json<JoinContestQuery>,
 ) -> Result<HttpResponse> {
     let user_id = token.id.to_string();
-    pool.join_contest(&query.contest_id, &user_id)
+    pool.join_contest(&query.contest_id, &user_id, query.invite_token.as_deref())
         .await
-        .map_internal_server_err()?;
+        .map_virtual_contest_err()?;
     let response = HttpResponse::Ok().finish();
     Ok(response)
 }
@@ -147,6 +192,142 @@ pub async fn leave_contest(
     Ok(response)
 }
 
+#[post("/internal-api/contest/delete")]
+pub async fn delete_contest(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.delete_contest(&query.contest_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[post("/internal-api/contest/archive")]
+pub async fn archive_contest(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.set_contest_archived(&query.contest_id, &user_id, true)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[post("/internal-api/contest/unarchive")]
+pub async fn unarchive_contest(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.set_contest_archived(&query.contest_id, &user_id, false)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[post("/internal-api/contest/invitation/create")]
+pub async fn create_invite_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    let invite_token = pool
+        .create_invite_token(&query.contest_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let body = serde_json::json!({ "invite_token": invite_token });
+    let response = HttpResponse::Ok().json(&body);
+    Ok(response)
+}
+
+#[get("/internal-api/contest/invitation/get/{contest_id}")]
+pub async fn get_invite_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    contest_id: web::Path<String>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    let invite_token = pool
+        .get_invite_token(&contest_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let body = serde_json::json!({ "invite_token": invite_token });
+    let response = HttpResponse::Ok().json(&body);
+    Ok(response)
+}
+
+#[post("/internal-api/contest/invitation/delete")]
+pub async fn delete_invite_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.delete_invite_token(&query.contest_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[post("/internal-api/contest/registration/close")]
+pub async fn close_registration(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.set_registration_closed(&query.contest_id, &user_id, true)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[post("/internal-api/contest/registration/open")]
+pub async fn open_registration(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleContestQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.set_registration_closed(&query.contest_id, &user_id, false)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct KickParticipantQuery {
+    contest_id: String,
+    atcoder_user_id: String,
+}
+
+#[post("/internal-api/contest/participant/kick")]
+pub async fn kick_participant(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<KickParticipantQuery>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    pool.kick_participant(&query.contest_id, &user_id, &query.atcoder_user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
 #[get("/internal-api/contest/my")]
 pub async fn get_my_contests(
     token: web::ReqData<GithubToken>,
@@ -0,0 +1,510 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use actix_web::{get, web, HttpResponse, Result};
+use serde::Serialize;
+use sql_client::{
+    internal::{
+        virtual_contest_manager::{
+            VirtualContestInfo, VirtualContestItem, VirtualContestManager, VirtualContestMode,
+        },
+        virtual_contest_team_manager::{VirtualContestTeam, VirtualContestTeamManager},
+    },
+    models::Submission,
+    PgPool,
+};
+
+use crate::server::error::{ApiResult, VirtualContestApiResult};
+
+const ACCEPTED: &str = "AC";
+const COMPILE_ERROR: &str = "CE";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ProblemResult {
+    pub point: f64,
+    pub accepted: bool,
+    pub trials: i64,
+    pub penalties: i64,
+    pub first_accepted_epoch_second: Option<i64>,
+    pub last_updated_epoch_second: i64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ParticipantResult {
+    pub rank: usize,
+    pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<VirtualContestTeam>,
+    pub point: f64,
+    pub penalties: i64,
+    pub time_second: i64,
+    pub results: BTreeMap<String, ProblemResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unlocked_problem_count: Option<usize>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct VirtualContestStandings {
+    pub mode: VirtualContestMode,
+    pub problems: Vec<VirtualContestItem>,
+    pub standings: Vec<ParticipantResult>,
+}
+
+/// Builds the scoreboard of a virtual contest from the submissions made during the contest.
+///
+/// For each problem, only the submission which improved the score last counts,
+/// and every submission before it except CE is counted as a penalty.
+/// If `user_defined_point` is set, an AC is worth that point and other verdicts are worth 0.
+/// A team is ranked as a single entry whose `user_id` is the team id and whose `team` is set, and
+/// the submissions of all its members count for it. A participant who is also a member of a
+/// registered team is ranked only as a part of the team.
+///
+/// In lockout mode, a problem is worth its point only for the entry which solved it first.
+/// In training mode, every problem is worth 1 point, and submissions to a problem are ignored
+/// until all the problems before it are solved.
+/// Penalties affect the ranking only in normal mode.
+pub fn compute_standings(
+    info: &VirtualContestInfo,
+    problems: Vec<VirtualContestItem>,
+    participants: &[String],
+    teams: &[VirtualContestTeam],
+    submissions: &[Submission],
+) -> VirtualContestStandings {
+    let point_overrides = problems
+        .iter()
+        .filter_map(|item| {
+            let point = match info.mode {
+                VirtualContestMode::Training => Some(1.0),
+                _ => item.point.map(|point| point as f64),
+            };
+            point.map(|point| (item.id.as_str(), point))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let problem_indices = problems
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.id.as_str(), i))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut submissions = submissions
+        .iter()
+        .filter(|s| s.result != COMPILE_ERROR)
+        .collect::<Vec<_>>();
+    submissions.sort_by_key(|s| s.id);
+
+    let team_members = teams
+        .iter()
+        .flat_map(|team| team.members.iter().map(|member| member.as_str()))
+        .collect::<BTreeSet<_>>();
+    let entries = participants
+        .iter()
+        .filter(|user_id| !team_members.contains(user_id.as_str()))
+        .map(|user_id| (user_id.as_str(), None))
+        .chain(teams.iter().map(|team| (team.id.as_str(), Some(team))))
+        .collect::<Vec<_>>();
+    let mut entries_by_user = BTreeMap::new();
+    for (i, (user_id, team)) in entries.iter().enumerate() {
+        let members = match team {
+            Some(team) => team.members.iter().map(|member| member.as_str()).collect(),
+            None => vec![*user_id],
+        };
+        for member in members {
+            entries_by_user
+                .entry(member)
+                .or_insert_with(Vec::new)
+                .push(i);
+        }
+    }
+
+    let mut entry_results = entries
+        .iter()
+        .map(|_| (BTreeMap::new(), 0))
+        .collect::<Vec<(BTreeMap<String, ProblemResult>, usize)>>();
+    let mut first_solvers = BTreeMap::new();
+    for submission in submissions.iter() {
+        let entry_indices = match entries_by_user.get(submission.user_id.as_str()) {
+            Some(entry_indices) => entry_indices,
+            None => continue,
+        };
+        let accepted = submission.result == ACCEPTED;
+        for &i in entry_indices.iter() {
+            let (results, solved_prefix) = &mut entry_results[i];
+            if info.mode == VirtualContestMode::Training {
+                let index = match problem_indices.get(submission.problem_id.as_str()) {
+                    Some(&index) if index <= *solved_prefix => index,
+                    _ => continue,
+                };
+                if accepted && index == *solved_prefix {
+                    *solved_prefix += 1;
+                }
+            }
+            if accepted {
+                first_solvers
+                    .entry(submission.problem_id.as_str())
+                    .or_insert(i);
+            }
+
+            let point = match point_overrides.get(submission.problem_id.as_str()) {
+                Some(&point) if accepted => point,
+                Some(_) => 0.0,
+                None => submission.point,
+            };
+            let result = results
+                .entry(submission.problem_id.clone())
+                .or_insert(ProblemResult {
+                    point: 0.0,
+                    accepted: false,
+                    trials: 0,
+                    penalties: 0,
+                    first_accepted_epoch_second: None,
+                    last_updated_epoch_second: submission.epoch_second,
+                });
+            if result.trials == 0 || result.point < point {
+                result.point = point;
+                result.penalties = result.trials;
+                result.last_updated_epoch_second = submission.epoch_second;
+            }
+            if accepted && !result.accepted {
+                result.accepted = true;
+                result.first_accepted_epoch_second = Some(submission.epoch_second);
+            }
+            result.trials += 1;
+        }
+    }
+
+    if info.mode == VirtualContestMode::Lockout {
+        for (i, (results, _)) in entry_results.iter_mut().enumerate() {
+            for (problem_id, result) in results.iter_mut() {
+                if first_solvers.get(problem_id.as_str()) != Some(&i) {
+                    result.point = 0.0;
+                }
+            }
+        }
+    }
+
+    let penalty_second = match info.mode {
+        VirtualContestMode::Normal => info.penalty_second,
+        _ => 0,
+    };
+    let mut standings = entries
+        .into_iter()
+        .zip(entry_results)
+        .map(|((user_id, team), (results, solved_prefix))| {
+            let scored = results.values().filter(|result| result.point > 0.0);
+            let point = scored.clone().map(|result| result.point).sum::<f64>();
+            let penalties = scored.clone().map(|result| result.penalties).sum::<i64>();
+            let time_second = scored
+                .map(|result| result.last_updated_epoch_second - info.start_epoch_second)
+                .max()
+                .map(|last| last + penalties * penalty_second)
+                .unwrap_or(0);
+            let unlocked_problem_count = match info.mode {
+                VirtualContestMode::Training => Some(problems.len().min(solved_prefix + 1)),
+                _ => None,
+            };
+            ParticipantResult {
+                rank: 0,
+                user_id: user_id.to_string(),
+                team: team.cloned(),
+                point,
+                penalties,
+                time_second,
+                results,
+                unlocked_problem_count,
+            }
+        })
+        .collect::<Vec<_>>();
+    standings.sort_by(|a, b| compare_results(a, b).then_with(|| a.user_id.cmp(&b.user_id)));
+
+    for i in 0..standings.len() {
+        standings[i].rank = if i > 0 && compare_results(&standings[i - 1], &standings[i]).is_eq() {
+            standings[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+
+    VirtualContestStandings {
+        mode: info.mode,
+        problems,
+        standings,
+    }
+}
+
+fn compare_results(a: &ParticipantResult, b: &ParticipantResult) -> Ordering {
+    b.point
+        .partial_cmp(&a.point)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.time_second.cmp(&b.time_second))
+        .then_with(|| a.penalties.cmp(&b.penalties))
+}
+
+#[get("/internal-api/contest/standings/{contest_id}")]
+pub async fn get_standings(
+    pool: web::Data<PgPool>,
+    contest_id: web::Path<String>,
+) -> Result<HttpResponse> {
+    let info = pool
+        .get_single_contest_info(&contest_id)
+        .await
+        .map_virtual_contest_err()?;
+    let participants = pool
+        .get_single_contest_participants(&contest_id)
+        .await
+        .map_internal_server_err()?;
+    let problems = pool
+        .get_single_contest_problems(&contest_id)
+        .await
+        .map_internal_server_err()?;
+    let teams = pool
+        .get_single_contest_teams(&contest_id)
+        .await
+        .map_internal_server_err()?;
+    let submissions = pool
+        .get_single_contest_submissions(&contest_id)
+        .await
+        .map_internal_server_err()?;
+    let standings = compute_standings(&info, problems, &participants, &teams, &submissions);
+    let response = HttpResponse::Ok().json(&standings);
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contest(mode: VirtualContestMode) -> VirtualContestInfo {
+        VirtualContestInfo {
+            id: "contest".to_owned(),
+            title: "".to_owned(),
+            memo: "".to_owned(),
+            owner_user_id: "owner".to_owned(),
+            start_epoch_second: 100,
+            duration_second: 1000,
+            mode,
+            is_public: true,
+            penalty_second: 300,
+            is_archived: false,
+            is_invitation_required: false,
+            is_registration_closed: false,
+        }
+    }
+
+    fn items(problem_ids: &[&str]) -> Vec<VirtualContestItem> {
+        problem_ids
+            .iter()
+            .map(|id| VirtualContestItem {
+                id: id.to_string(),
+                point: None,
+                order: None,
+            })
+            .collect()
+    }
+
+    fn submission(id: i64, user_id: &str, problem_id: &str, result: &str) -> Submission {
+        Submission {
+            id,
+            epoch_second: 100 + id * 10,
+            problem_id: problem_id.to_owned(),
+            user_id: user_id.to_owned(),
+            result: result.to_owned(),
+            point: if result == ACCEPTED { 100.0 } else { 0.0 },
+            ..Default::default()
+        }
+    }
+
+    fn points(standings: &VirtualContestStandings) -> Vec<(&str, f64)> {
+        standings
+            .standings
+            .iter()
+            .map(|result| (result.user_id.as_str(), result.point))
+            .collect()
+    }
+
+    #[test]
+    fn test_lockout_standings() {
+        let participants = vec!["u1".to_owned(), "u2".to_owned()];
+        let submissions = vec![
+            submission(1, "u2", "p1", "WA"),
+            submission(2, "u1", "p1", "AC"),
+            submission(3, "u2", "p1", "AC"),
+            submission(4, "u2", "p2", "AC"),
+            submission(5, "u2", "p3", "AC"),
+            submission(6, "u1", "p3", "AC"),
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Lockout),
+            items(&["p1", "p2", "p3"]),
+            &participants,
+            &[],
+            &submissions,
+        );
+        assert_eq!(points(&standings), vec![("u2", 200.0), ("u1", 100.0)]);
+
+        let u1 = &standings.standings[1];
+        assert_eq!(u1.results["p1"].point, 100.0);
+        assert_eq!(u1.results["p3"].point, 0.0);
+        assert!(u1.results["p3"].accepted);
+        assert_eq!(u1.time_second, 20);
+
+        let u2 = &standings.standings[0];
+        assert_eq!(u2.results["p1"].point, 0.0);
+        assert_eq!(u2.penalties, 0);
+        assert_eq!(u2.time_second, 50);
+    }
+
+    #[test]
+    fn test_training_standings() {
+        let participants = vec!["u1".to_owned(), "u2".to_owned()];
+        let submissions = vec![
+            submission(1, "u1", "p2", "AC"),
+            submission(2, "u1", "p1", "WA"),
+            submission(3, "u1", "p1", "AC"),
+            submission(4, "u1", "p2", "WA"),
+            submission(5, "u1", "p2", "AC"),
+            submission(6, "u2", "p1", "AC"),
+            submission(7, "u2", "p3", "AC"),
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Training),
+            items(&["p1", "p2", "p3"]),
+            &participants,
+            &[],
+            &submissions,
+        );
+        assert_eq!(points(&standings), vec![("u1", 2.0), ("u2", 1.0)]);
+
+        let u1 = &standings.standings[0];
+        assert_eq!(u1.unlocked_problem_count, Some(3));
+        assert_eq!(u1.results["p2"].trials, 2);
+        assert_eq!(u1.results["p2"].penalties, 1);
+        assert_eq!(u1.time_second, 50);
+
+        let u2 = &standings.standings[1];
+        assert_eq!(u2.unlocked_problem_count, Some(2));
+        assert!(!u2.results.contains_key("p3"));
+    }
+
+    #[test]
+    fn test_normal_standings_rank_ties() {
+        let participants = vec!["u1".to_owned(), "u2".to_owned(), "u3".to_owned()];
+        let submissions = vec![
+            Submission {
+                epoch_second: 150,
+                ..submission(1, "u1", "p1", "AC")
+            },
+            Submission {
+                epoch_second: 150,
+                ..submission(2, "u2", "p1", "AC")
+            },
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Normal),
+            items(&["p1"]),
+            &participants,
+            &[],
+            &submissions,
+        );
+        let ranks = standings
+            .standings
+            .iter()
+            .map(|result| (result.user_id.as_str(), result.rank))
+            .collect::<Vec<_>>();
+        assert_eq!(ranks, vec![("u1", 1), ("u2", 1), ("u3", 3)]);
+        assert_eq!(standings.standings[2].unlocked_problem_count, None);
+    }
+
+    #[test]
+    fn test_team_standings() {
+        let participants = vec!["u1".to_owned()];
+        let teams = vec![VirtualContestTeam {
+            id: "team".to_owned(),
+            name: "team name".to_owned(),
+            owner_user_id: "owner".to_owned(),
+            members: vec!["u2".to_owned(), "u3".to_owned()],
+        }];
+        let submissions = vec![
+            submission(1, "u2", "p1", "WA"),
+            submission(2, "u3", "p1", "AC"),
+            submission(3, "u2", "p2", "AC"),
+            submission(4, "u1", "p1", "AC"),
+            submission(5, "u4", "p2", "AC"),
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Normal),
+            items(&["p1", "p2"]),
+            &participants,
+            &teams,
+            &submissions,
+        );
+        assert_eq!(points(&standings), vec![("team", 200.0), ("u1", 100.0)]);
+
+        let team = &standings.standings[0];
+        assert_eq!(team.team.as_ref(), Some(&teams[0]));
+        assert_eq!(team.results["p1"].trials, 2);
+        assert_eq!(team.penalties, 1);
+        assert_eq!(team.time_second, 30 + 300);
+        assert_eq!(standings.standings[1].team, None);
+    }
+
+    #[test]
+    fn test_team_standings_name_collision() {
+        let participants = vec!["dup".to_owned()];
+        let team = |id: &str, member: &str| VirtualContestTeam {
+            id: id.to_owned(),
+            name: "dup".to_owned(),
+            owner_user_id: "owner".to_owned(),
+            members: vec![member.to_owned()],
+        };
+        let teams = vec![team("team1", "u1"), team("team2", "u2")];
+        let submissions = vec![
+            submission(1, "u1", "p1", "AC"),
+            submission(2, "u1", "p2", "AC"),
+            submission(3, "u2", "p1", "AC"),
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Normal),
+            items(&["p1", "p2"]),
+            &participants,
+            &teams,
+            &submissions,
+        );
+        assert_eq!(
+            points(&standings),
+            vec![("team1", 200.0), ("team2", 100.0), ("dup", 0.0)]
+        );
+        let names = standings
+            .standings
+            .iter()
+            .map(|result| result.team.as_ref().map(|team| team.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("dup"), Some("dup"), None]);
+    }
+
+    #[test]
+    fn test_team_standings_member_also_participant() {
+        let participants = vec!["u1".to_owned(), "u2".to_owned()];
+        let teams = vec![VirtualContestTeam {
+            id: "team".to_owned(),
+            name: "team name".to_owned(),
+            owner_user_id: "owner".to_owned(),
+            members: vec!["u1".to_owned(), "u3".to_owned()],
+        }];
+        let submissions = vec![
+            submission(1, "u1", "p1", "AC"),
+            submission(2, "u2", "p1", "AC"),
+        ];
+        let standings = compute_standings(
+            &contest(VirtualContestMode::Lockout),
+            items(&["p1"]),
+            &participants,
+            &teams,
+            &submissions,
+        );
+        assert_eq!(
+            points(&standings),
+            vec![("team", 100.0), ("u2", 0.0)],
+            "A team member should not be ranked by themselves as well."
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use actix_web::{get, post, web, HttpResponse, Responder, Result};
+use serde::Deserialize;
+use sql_client::{internal::virtual_contest_team_manager::VirtualContestTeamManager, PgPool};
+
+use crate::server::{
+    error::{ApiResult, VirtualContestApiResult},
+    middleware::github_auth::GithubToken,
+};
+
+#[derive(Deserialize)]
+pub struct CreateTeamQuery {
+    name: String,
+    members: Vec<String>,
+}
+
+#[post("/internal-api/contest/team/create")]
+pub async fn create_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<CreateTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    let team_id = pool
+        .create_team(&query.name, &user_id, &query.members)
+        .await
+        .map_virtual_contest_err()?;
+    let body = serde_json::json!({ "team_id": team_id });
+    let response = HttpResponse::Ok().json(&body);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTeamQuery {
+    team_id: String,
+    name: String,
+    members: Vec<String>,
+}
+
+#[post("/internal-api/contest/team/update")]
+pub async fn update_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<UpdateTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.update_team(&query.team_id, &user_id, &query.name, &query.members)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct SingleTeamQuery {
+    team_id: String,
+}
+
+#[post("/internal-api/contest/team/delete")]
+pub async fn delete_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<SingleTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.delete_team(&query.team_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[get("/internal-api/contest/team/my")]
+pub async fn get_my_teams(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let user_id = token.id.to_string();
+    let teams = pool
+        .get_own_teams(&user_id)
+        .await
+        .map_internal_server_err()?;
+    let response = HttpResponse::Ok().json(&teams);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct JoinTeamQuery {
+    contest_id: String,
+    team_id: String,
+    invite_token: Option<String>,
+}
+
+#[post("/internal-api/contest/team/join")]
+pub async fn join_contest_as_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<JoinTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.join_contest_as_team(
+        &query.contest_id,
+        &query.team_id,
+        &user_id,
+        query.invite_token.as_deref(),
+    )
+    .await
+    .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct LeaveTeamQuery {
+    contest_id: String,
+    team_id: String,
+}
+
+#[post("/internal-api/contest/team/leave")]
+pub async fn leave_contest_as_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<LeaveTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.leave_contest_as_team(&query.contest_id, &query.team_id, &user_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub struct KickTeamQuery {
+    contest_id: String,
+    team_id: String,
+}
+
+#[post("/internal-api/contest/team/kick")]
+pub async fn kick_team(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+    query: web::Json<KickTeamQuery>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.kick_team(&query.contest_id, &user_id, &query.team_id)
+        .await
+        .map_virtual_contest_err()?;
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
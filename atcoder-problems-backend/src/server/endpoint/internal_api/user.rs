@@ -34,3 +34,44 @@ pub async fn update(
         .map_internal_server_err()?;
     Ok(HttpResponse::Ok().finish())
 }
+
+/// Issues a new token for the calendar feed of the user, which invalidates the previous one.
+#[post("/internal-api/user/calendar_token/create")]
+pub async fn create_calendar_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    let calendar_token = pool
+        .create_calendar_token(&user_id)
+        .await
+        .map_internal_server_err()?;
+    let body = serde_json::json!({ "calendar_token": calendar_token });
+    Ok(HttpResponse::Ok().json(&body))
+}
+
+#[get("/internal-api/user/calendar_token")]
+pub async fn get_calendar_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    let calendar_token = pool
+        .get_calendar_token(&user_id)
+        .await
+        .map_internal_server_err()?;
+    let body = serde_json::json!({ "calendar_token": calendar_token });
+    Ok(HttpResponse::Ok().json(&body))
+}
+
+#[post("/internal-api/user/calendar_token/delete")]
+pub async fn delete_calendar_token(
+    token: web::ReqData<GithubToken>,
+    pool: web::Data<PgPool>,
+) -> Result<impl Responder> {
+    let user_id = token.id.to_string();
+    pool.delete_calendar_token(&user_id)
+        .await
+        .map_internal_server_err()?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,3 +1,5 @@
+use sql_client::internal::virtual_contest_manager::VirtualContestError;
+
 pub trait ApiResult<T> {
     fn map_internal_server_err(self) -> actix_web::Result<T>;
 }
@@ -10,3 +12,23 @@ where
         self.map_err(actix_web::error::ErrorInternalServerError)
     }
 }
+
+pub trait VirtualContestApiResult<T> {
+    fn map_virtual_contest_err(self) -> actix_web::Result<T>;
+}
+
+impl<T> VirtualContestApiResult<T> for anyhow::Result<T> {
+    fn map_virtual_contest_err(self) -> actix_web::Result<T> {
+        self.map_err(|e| match e.downcast_ref::<VirtualContestError>() {
+            Some(VirtualContestError::ContestNotFound)
+            | Some(VirtualContestError::TeamNotFound) => actix_web::error::ErrorNotFound(e),
+            Some(VirtualContestError::NotOwner) => actix_web::error::ErrorForbidden(e),
+            Some(VirtualContestError::InvalidMode)
+            | Some(VirtualContestError::TooManyTeamMembers) => actix_web::error::ErrorBadRequest(e),
+            Some(VirtualContestError::RegistrationClosed)
+            | Some(VirtualContestError::InvalidInviteToken)
+            | Some(VirtualContestError::Kicked) => actix_web::error::ErrorForbidden(e),
+            None => actix_web::error::ErrorInternalServerError(e),
+        })
+    }
+}
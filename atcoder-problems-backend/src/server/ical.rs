@@ -0,0 +1,127 @@
+use chrono::{TimeZone, Utc};
+
+pub(crate) const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+const MAX_LINE_OCTETS: usize = 75;
+
+pub(crate) struct CalendarEvent {
+    pub(crate) uid: String,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) url: String,
+    pub(crate) start_epoch_second: i64,
+    pub(crate) end_epoch_second: i64,
+}
+
+/// Renders the events as an iCalendar (RFC 5545) document.
+pub(crate) fn render_calendar(
+    name: &str,
+    events: &[CalendarEvent],
+    now_epoch_second: i64,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//AtCoder Problems//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape_text(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", format_time(now_epoch_second)));
+        lines.push(format!("DTSTART:{}", format_time(event.start_epoch_second)));
+        lines.push(format!("DTEND:{}", format_time(event.end_epoch_second)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+        }
+        lines.push(format!("URL:{}", event.url));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .map(|line| line + "\r\n")
+        .collect()
+}
+
+fn format_time(epoch_second: i64) -> String {
+    Utc.timestamp(epoch_second, 0)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line longer than 75 octets without breaking a UTF-8 character.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_calendar() {
+        let events = vec![CalendarEvent {
+            uid: "contest-id".to_string(),
+            title: "Contest; with, special\nchars".to_string(),
+            description: "".to_string(),
+            url: "https://kenkoooo.com/atcoder/#/contest/show/contest-id".to_string(),
+            start_epoch_second: 1597988723,
+            end_epoch_second: 1597988723 + 6000,
+        }];
+        let calendar = render_calendar("Calendar", &events, 0);
+        assert_eq!(
+            calendar,
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//AtCoder Problems//EN",
+                "CALSCALE:GREGORIAN",
+                "X-WR-CALNAME:Calendar",
+                "BEGIN:VEVENT",
+                "UID:contest-id",
+                "DTSTAMP:19700101T000000Z",
+                "DTSTART:20200821T054523Z",
+                "DTEND:20200821T072523Z",
+                "SUMMARY:Contest\\; with\\, special\\nchars",
+                "URL:https://kenkoooo.com/atcoder/#/contest/show/contest-id",
+                "END:VEVENT",
+                "END:VCALENDAR",
+                "",
+            ]
+            .join("\r\n")
+        );
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("SUMMARY:{}", "あ".repeat(30));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,19 +1,28 @@
+pub(crate) mod contest_calendar;
+pub(crate) mod contest_result;
 pub mod endpoint;
 pub mod error;
+pub(crate) mod ical;
 pub(crate) mod language_count;
 pub mod middleware;
 pub(crate) mod ranking;
 pub(crate) mod services;
+pub mod submission_broadcaster;
 pub(crate) mod time_submissions;
+pub(crate) mod user_activity;
 pub(crate) mod user_info;
+pub(crate) mod user_streak;
 pub(crate) mod user_submissions;
 
 use actix_web::{http::header, web, App, HttpResponseBuilder, HttpServer};
 use anyhow::Result;
 pub use services::config_services;
+use std::time::Duration;
 
 use self::middleware::github_auth::{GithubAuthentication, GithubClient};
+use self::submission_broadcaster::SubmissionBroadcaster;
 
+const LISTEN_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 const LOG_TEMPLATE: &str = r#"{"method":"%{method}xi", "url":"%U", "status":%s, "duration":%T}"#;
 
 pub async fn run_server(
@@ -22,10 +31,26 @@ pub async fn run_server(
     port: u16,
 ) -> Result<()> {
     let host = "0.0.0.0";
+
+    let broadcaster = SubmissionBroadcaster::default();
+    actix_web::rt::spawn({
+        let broadcaster = broadcaster.clone();
+        let pg_pool = pg_pool.clone();
+        async move {
+            loop {
+                if let Err(e) = broadcaster.listen(&pg_pool).await {
+                    log::error!("Failed to listen to submission notifications: {:?}", e);
+                }
+                actix_web::rt::time::sleep(LISTEN_RETRY_INTERVAL).await;
+            }
+        }
+    });
+
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(github_client.clone()))
             .app_data(web::Data::new(pg_pool.clone()))
+            .app_data(web::Data::new(broadcaster.clone()))
             .wrap(GithubAuthentication::new(github_client.clone()))
             .wrap(
                 actix_web::middleware::Logger::new(LOG_TEMPLATE)
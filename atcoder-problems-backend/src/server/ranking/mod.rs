@@ -10,8 +10,10 @@ pub(crate) mod rated_point_sum;
 pub(crate) mod streak;
 
 pub(crate) use {
-    ac_count::AcRanking, language::LanguageRanking, rated_point_sum::RatedPointSumRanking,
-    streak::StreakRanking,
+    ac_count::AcRanking,
+    language::LanguageRanking,
+    rated_point_sum::RatedPointSumRanking,
+    streak::{CurrentStreakRanking, StreakRanking},
 };
 
 const MAX_RANKING_RANGE_LENGTH: usize = 1_000;
@@ -5,10 +5,17 @@ use super::{
 
 use actix_web::{error, web, Result};
 use async_trait::async_trait;
-use sql_client::{streak::StreakClient, PgPool};
+use chrono::Utc;
+use sql_client::{
+    streak::{jst_day, StreakClient},
+    PgPool,
+};
 
 pub(crate) struct StreakRanking;
 
+/// Ranks the users by the streaks which can still be continued today.
+pub(crate) struct CurrentStreakRanking;
+
 #[async_trait(?Send)]
 impl RankingSelector for StreakRanking {
     type Request = RankingRequest;
@@ -47,3 +54,49 @@ impl UserRankSelector for StreakRanking {
         Ok(Some(UserRankResponse { count, rank }))
     }
 }
+
+#[async_trait(?Send)]
+impl RankingSelector for CurrentStreakRanking {
+    type Request = RankingRequest;
+    type Response = RankingResponse;
+    async fn fetch(pool: web::Data<PgPool>, query: Self::Request) -> Result<Vec<Self::Response>> {
+        let today = jst_day(Utc::now().timestamp());
+        let ranking = pool
+            .load_current_streak_count_in_range(query.range(), today)
+            .await
+            .map_err(error::ErrorInternalServerError)?;
+        Ok(ranking
+            .into_iter()
+            .map(|entry| RankingResponse {
+                user_id: entry.user_id,
+                count: entry.streak,
+            })
+            .collect())
+    }
+}
+
+#[async_trait(?Send)]
+impl UserRankSelector for CurrentStreakRanking {
+    type Request = UserRankRequest;
+    type Response = UserRankResponse;
+    async fn fetch(
+        pool: web::Data<PgPool>,
+        query: Self::Request,
+    ) -> Result<Option<Self::Response>> {
+        let today = jst_day(Utc::now().timestamp());
+        let latest = pool
+            .get_users_latest_streak(&query.user)
+            .await
+            .map_err(error::ErrorInternalServerError)?;
+        let count = match latest {
+            Some(latest) if latest.is_current(today) => latest.streak(),
+            Some(_) => 0,
+            None => return Ok(None),
+        };
+        let rank = pool
+            .get_current_streak_count_rank(count, today)
+            .await
+            .map_err(error::ErrorInternalServerError)?;
+        Ok(Some(UserRankResponse { count, rank }))
+    }
+}
@@ -1,14 +1,18 @@
 use actix_web::web;
 
 use crate::server::{
+    contest_calendar::get_contest_calendar,
+    contest_result::{get_contest_standings, get_user_contest_standings, get_user_rating_history},
     endpoint,
     language_count::get_language_list,
     ranking::{
-        AcRanking, LanguageRanking, RankingSelector, RatedPointSumRanking, StreakRanking,
-        UserRankSelector,
+        AcRanking, CurrentStreakRanking, LanguageRanking, RankingSelector, RatedPointSumRanking,
+        StreakRanking, UserRankSelector,
     },
     time_submissions::get_time_submissions,
+    user_activity::get_user_daily_activity,
     user_info::get_user_info,
+    user_streak::get_user_streak,
     user_submissions::get_user_submission_count,
     user_submissions::{
         get_recent_submissions, get_user_submissions, get_user_submissions_from_time,
@@ -28,16 +32,40 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
         .service(endpoint::internal_api::list::item::update_item)
         .service(endpoint::internal_api::list::item::delete_item)
         .service(endpoint::internal_api::contest::create_contest)
+        .service(endpoint::internal_api::contest::clone_contest)
         .service(endpoint::internal_api::contest::update_contest)
         .service(endpoint::internal_api::contest::item::update_items)
         .service(endpoint::internal_api::contest::get_single_contest)
+        .service(endpoint::internal_api::contest::standings::get_standings)
+        .service(endpoint::internal_api::contest::events::get_events)
         .service(endpoint::internal_api::contest::join_contest)
         .service(endpoint::internal_api::contest::leave_contest)
+        .service(endpoint::internal_api::contest::delete_contest)
+        .service(endpoint::internal_api::contest::archive_contest)
+        .service(endpoint::internal_api::contest::unarchive_contest)
+        .service(endpoint::internal_api::contest::create_invite_token)
+        .service(endpoint::internal_api::contest::get_invite_token)
+        .service(endpoint::internal_api::contest::delete_invite_token)
+        .service(endpoint::internal_api::contest::close_registration)
+        .service(endpoint::internal_api::contest::open_registration)
+        .service(endpoint::internal_api::contest::kick_participant)
+        .service(endpoint::internal_api::contest::team::create_team)
+        .service(endpoint::internal_api::contest::team::update_team)
+        .service(endpoint::internal_api::contest::team::delete_team)
+        .service(endpoint::internal_api::contest::team::get_my_teams)
+        .service(endpoint::internal_api::contest::team::join_contest_as_team)
+        .service(endpoint::internal_api::contest::team::leave_contest_as_team)
+        .service(endpoint::internal_api::contest::team::kick_team)
         .service(endpoint::internal_api::contest::get_my_contests)
         .service(endpoint::internal_api::contest::get_participated)
         .service(endpoint::internal_api::contest::get_recent_contests)
+        .service(endpoint::internal_api::contest::calendar::get_user_calendar)
+        .service(endpoint::internal_api::contest::calendar::get_recent_calendar)
         .service(endpoint::internal_api::user::get)
         .service(endpoint::internal_api::user::update)
+        .service(endpoint::internal_api::user::create_calendar_token)
+        .service(endpoint::internal_api::user::get_calendar_token)
+        .service(endpoint::internal_api::user::delete_calendar_token)
         .service(endpoint::internal_api::progress_reset::get_progress_reset_list)
         .service(endpoint::internal_api::progress_reset::add_progress_reset_item)
         .service(endpoint::internal_api::progress_reset::delete_progress_reset_item)
@@ -63,6 +91,9 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
                                 web::get().to(<StreakRanking as RankingSelector>::get_ranking),
                             ),
                         )
+                        .service(web::resource("/current_streak_ranking").route(
+                            web::get().to(<CurrentStreakRanking as RankingSelector>::get_ranking),
+                        ))
                         .service(web::resource("/language_ranking").route(
                             web::get().to(<LanguageRanking as RankingSelector>::get_ranking),
                         ))
@@ -83,6 +114,14 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
                                     web::resource("/submissions")
                                         .route(web::get().to(get_user_submissions_from_time)),
                                 )
+                                .service(
+                                    web::resource("/rating_history")
+                                        .route(web::get().to(get_user_rating_history)),
+                                )
+                                .service(
+                                    web::resource("/contest_standings")
+                                        .route(web::get().to(get_user_contest_standings)),
+                                )
                                 .service(
                                     web::resource("/submission_count")
                                         .route(web::get().to(get_user_submission_count)),
@@ -97,6 +136,18 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
                                         ),
                                     ),
                                 )
+                                .service(web::resource("/current_streak_rank").route(
+                                    web::get().to(
+                                        <CurrentStreakRanking as UserRankSelector>::get_users_rank,
+                                    ),
+                                ))
+                                .service(
+                                    web::resource("/streak").route(web::get().to(get_user_streak)),
+                                )
+                                .service(
+                                    web::resource("/daily_activity")
+                                        .route(web::get().to(get_user_daily_activity)),
+                                )
                                 .service(
                                     web::resource("/language_rank").route(
                                         web::get().to(
@@ -112,6 +163,14 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
                         )
                         .service(
                             web::resource("language_list").route(web::get().to(get_language_list)),
+                        )
+                        .service(
+                            web::resource("/contest_standings")
+                                .route(web::get().to(get_contest_standings)),
+                        )
+                        .service(
+                            web::resource("/contest_calendar")
+                                .route(web::get().to(get_contest_calendar)),
                         ),
                 ),
         )
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use sql_client::{models::Submission, submission_listener::SubmissionListener, PgPool};
+use tokio::sync::broadcast;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Fans out the submissions notified by Postgres to the open event streams.
+#[derive(Clone)]
+pub struct SubmissionBroadcaster {
+    sender: broadcast::Sender<Arc<Vec<Submission>>>,
+}
+
+impl Default for SubmissionBroadcaster {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl SubmissionBroadcaster {
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Vec<Submission>>> {
+        self.sender.subscribe()
+    }
+
+    pub fn send(&self, submissions: Vec<Submission>) {
+        // It fails only when nobody is subscribing, and then nobody misses it.
+        let _ = self.sender.send(Arc::new(submissions));
+    }
+
+    /// Forwards the notifications until the listener fails.
+    pub async fn listen(&self, pool: &PgPool) -> Result<()> {
+        let mut listener = SubmissionListener::connect(pool).await?;
+        loop {
+            let ids = listener.recv_ids().await?;
+            // Nobody would receive them, so skip loading them.
+            if self.sender.receiver_count() == 0 {
+                continue;
+            }
+            let submissions = listener.load(&ids).await?;
+            self.send(submissions);
+        }
+    }
+}
@@ -1,22 +1,37 @@
+use crate::server::user_submissions::{link_next_page, parse_cursor};
 use crate::server::MakeCors;
-use actix_web::{error, web, HttpResponse, Result};
+use actix_web::{error, web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
 use sql_client::{
     submission_client::{SubmissionClient, SubmissionRequest},
     PgPool,
 };
 
+const TIME_SUBMISSION_LIMIT: usize = 1000;
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GetTimeSubmissionQuery {
+    cursor: Option<String>,
+}
+
 pub(crate) async fn get_time_submissions(
+    request: HttpRequest,
     pool: web::Data<PgPool>,
     from: web::Path<i64>,
+    query: web::Query<GetTimeSubmissionQuery>,
 ) -> Result<HttpResponse> {
     let from_epoch_second = from.into_inner();
+    let after = parse_cursor(query.cursor.as_deref())?;
     let submissions: Vec<_> = pool
         .get_submissions(SubmissionRequest::FromTime {
             from_second: from_epoch_second,
-            count: 1000,
+            after,
+            count: TIME_SUBMISSION_LIMIT as i64,
         })
         .await
         .map_err(error::ErrorInternalServerError)?;
-    let response = HttpResponse::Ok().make_cors().json(&submissions);
-    Ok(response)
+    let mut response = HttpResponse::Ok();
+    response.make_cors();
+    link_next_page(&mut response, &request, &submissions, TIME_SUBMISSION_LIMIT);
+    Ok(response.json(&submissions))
 }
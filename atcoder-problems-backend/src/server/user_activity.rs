@@ -0,0 +1,57 @@
+use crate::server::user_streak::to_date;
+use crate::server::MakeCors;
+
+use actix_web::http::header::CACHE_CONTROL;
+use actix_web::{error, web, HttpResponse, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sql_client::activity::ActivityClient;
+use sql_client::PgPool;
+
+#[derive(Deserialize)]
+pub(crate) struct Query {
+    user: String,
+    from_date: String,
+    to_date: String,
+}
+
+#[derive(Serialize)]
+struct DailyActivityResponse {
+    date: String,
+    submission_count: i64,
+    accepted_count: i64,
+    new_accepted_count: i64,
+}
+
+/// Returns the activities of the user on the days in JST from `from_date` to `to_date` inclusive.
+pub(crate) async fn get_user_daily_activity(
+    pool: web::Data<PgPool>,
+    query: web::Query<Query>,
+) -> Result<HttpResponse> {
+    let from_day = parse_day(&query.from_date)?;
+    let to_day = parse_day(&query.to_date)?;
+    let activities = pool
+        .load_daily_activities(&query.user, from_day..(to_day + 1))
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let activities = activities
+        .into_iter()
+        .map(|activity| DailyActivityResponse {
+            date: to_date(activity.day),
+            submission_count: activity.submission_count,
+            accepted_count: activity.accepted_count,
+            new_accepted_count: activity.new_accepted_count,
+        })
+        .collect::<Vec<_>>();
+    let response = HttpResponse::Ok()
+        .make_cors()
+        .insert_header((CACHE_CONTROL, "max-age=300"))
+        .json(&activities);
+    Ok(response)
+}
+
+/// Parses the date in JST into the number of the days since the epoch.
+fn parse_day(date: &str) -> Result<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(error::ErrorBadRequest)?;
+    Ok(date.and_hms(0, 0, 0).timestamp().div_euclid(24 * 3600))
+}
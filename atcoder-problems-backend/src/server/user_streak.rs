@@ -0,0 +1,71 @@
+use crate::server::MakeCors;
+
+use actix_web::{error, web, HttpResponse, Result};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sql_client::models::StreakInterval;
+use sql_client::streak::{jst_day, StreakClient};
+use sql_client::PgPool;
+
+#[derive(Deserialize)]
+pub(crate) struct Query {
+    user: String,
+}
+
+#[derive(Serialize)]
+struct StreakResponse {
+    start_date: String,
+    last_date: String,
+    streak: i64,
+}
+
+#[derive(Serialize)]
+struct UserStreakResponse {
+    max_streak: i64,
+    current_streak: i64,
+    current_streak_start_date: Option<String>,
+    last_accepted_date: Option<String>,
+    streaks: Vec<StreakResponse>,
+}
+
+pub(crate) async fn get_user_streak(
+    pool: web::Data<PgPool>,
+    query: web::Query<Query>,
+) -> Result<HttpResponse> {
+    let intervals = pool
+        .load_streak_intervals(&query.user)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let today = jst_day(Utc::now().timestamp());
+    let current = intervals
+        .last()
+        .filter(|interval| interval.is_current(today));
+
+    let user_streak = UserStreakResponse {
+        max_streak: intervals
+            .iter()
+            .map(StreakInterval::streak)
+            .max()
+            .unwrap_or(0),
+        current_streak: current.map(StreakInterval::streak).unwrap_or(0),
+        current_streak_start_date: current.map(|interval| to_date(interval.start_day)),
+        last_accepted_date: intervals.last().map(|interval| to_date(interval.last_day)),
+        streaks: intervals
+            .iter()
+            .map(|interval| StreakResponse {
+                start_date: to_date(interval.start_day),
+                last_date: to_date(interval.last_day),
+                streak: interval.streak(),
+            })
+            .collect(),
+    };
+    let response = HttpResponse::Ok().make_cors().json(&user_streak);
+    Ok(response)
+}
+
+/// Formats the day in JST counted from the epoch.
+pub(crate) fn to_date(day: i64) -> String {
+    Utc.timestamp(day * 24 * 3600, 0)
+        .format("%Y-%m-%d")
+        .to_string()
+}
@@ -1,60 +1,211 @@
 use crate::server::MakeCors;
-use actix_web::http::header::CACHE_CONTROL;
-use actix_web::{error, web, HttpRequest, HttpResponse, Result};
+use actix_web::http::header::{ContentType, CACHE_CONTROL, LINK};
+use actix_web::web::Bytes;
+use actix_web::{error, web, HttpRequest, HttpResponse, HttpResponseBuilder, Result};
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use sql_client::submission_client::{SubmissionClient, SubmissionRequest};
+use sql_client::models::Submission;
+use sql_client::submission_client::{SubmissionClient, SubmissionCursor, SubmissionRequest};
 use sql_client::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
 
 const USER_SUBMISSION_LIMIT: usize = 500;
+const STREAM_PAGE_SIZE: usize = 1000;
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+const STREAM_CHANNEL_SIZE: usize = 4;
+const STREAM_SEND_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Deserialize, Debug)]
 pub(crate) struct GetUserSubmissionQuery {
     user: String,
     from_second: Option<i64>,
     to_second: Option<i64>,
+    cursor: Option<String>,
 }
 
+/// Streams the JSON array of all the submissions of the user, since a heavy user has too many
+/// submissions to hold in memory.
+///
+/// The submissions are loaded page by page, so that no connection is held while the client reads.
+/// The first page is loaded before responding, so that its error is still reported as the status.
 pub(crate) async fn get_user_submissions(
     _request: HttpRequest,
     pool: web::Data<PgPool>,
     query: web::Query<GetUserSubmissionQuery>,
 ) -> Result<HttpResponse> {
-    let user_id = &query.user;
-    let submissions = pool
-        .get_submissions(SubmissionRequest::UserAll { user_id })
+    let pool = pool.into_inner();
+    let user_id = query.into_inner().user;
+    let first_page = load_user_submissions_page(&pool, &user_id, None)
         .await
         .map_err(error::ErrorInternalServerError)?;
+    let (sender, receiver) = mpsc::channel(STREAM_CHANNEL_SIZE);
+    actix_web::rt::spawn(async move {
+        let submissions = page_user_submissions(pool, user_id, first_page);
+        send_json_array(submissions, sender).await;
+    });
+
+    let body = stream::unfold(receiver, |mut receiver| async move {
+        let chunk = receiver.recv().await?;
+        Some((chunk, receiver))
+    });
     let response = HttpResponse::Ok()
         .make_cors()
         .insert_header((CACHE_CONTROL, "max-age=300"))
-        .json(&submissions);
+        .content_type(ContentType::json())
+        .streaming(body);
     Ok(response)
 }
 
+async fn load_user_submissions_page(
+    pool: &PgPool,
+    user_id: &str,
+    after: Option<SubmissionCursor>,
+) -> anyhow::Result<Vec<Submission>> {
+    pool.get_submissions(SubmissionRequest::FromUserAndTime {
+        user_id,
+        from_second: i64::MIN,
+        after,
+        count: STREAM_PAGE_SIZE,
+    })
+    .await
+}
+
+/// Returns the submissions on `first_page` followed by the ones on the next pages.
+fn page_user_submissions(
+    pool: Arc<PgPool>,
+    user_id: String,
+    first_page: Vec<Submission>,
+) -> impl Stream<Item = anyhow::Result<Submission>> + Unpin {
+    let next_pages = stream::try_unfold(next_page_cursor(&first_page), move |after| {
+        let pool = pool.clone();
+        let user_id = user_id.clone();
+        async move {
+            let after = match after {
+                Some(after) => after,
+                None => return Ok::<_, anyhow::Error>(None),
+            };
+            let page = load_user_submissions_page(&pool, &user_id, Some(after)).await?;
+            let next = next_page_cursor(&page);
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        }
+    })
+    .try_flatten();
+    stream::iter(first_page.into_iter().map(Ok))
+        .chain(next_pages)
+        .boxed()
+}
+
+/// Returns the cursor of the next page if the page is full.
+fn next_page_cursor(page: &[Submission]) -> Option<SubmissionCursor> {
+    match page.last() {
+        Some(last) if page.len() >= STREAM_PAGE_SIZE => Some(SubmissionCursor::after(last)),
+        _ => None,
+    }
+}
+
+/// Sends the items as the chunks of a JSON array until the receiver is dropped, or stops reading
+/// for `STREAM_SEND_TIMEOUT`.
+///
+/// An error after the first chunk cannot change the status any more, so it aborts the body.
+async fn send_json_array<T, S>(mut items: S, sender: mpsc::Sender<Result<Bytes>>)
+where
+    T: Serialize,
+    S: Stream<Item = anyhow::Result<T>> + Unpin,
+{
+    let mut buffer = b"[".to_vec();
+    let mut is_first = true;
+    while let Some(item) = items.next().await {
+        let item = match item.and_then(|item| Ok(serde_json::to_vec(&item)?)) {
+            Ok(item) => item,
+            Err(e) => {
+                log::error!("Failed to stream the submissions: {:?}", e);
+                let _ = sender.send(Err(error::ErrorInternalServerError(e))).await;
+                return;
+            }
+        };
+        if !is_first {
+            buffer.push(b',');
+        }
+        is_first = false;
+        buffer.extend(item);
+        if buffer.len() >= STREAM_CHUNK_SIZE {
+            let chunk = Bytes::from(std::mem::take(&mut buffer));
+            if !send_chunk(&sender, chunk).await {
+                return;
+            }
+        }
+    }
+    buffer.push(b']');
+    send_chunk(&sender, Bytes::from(buffer)).await;
+}
+
+/// Returns false if the receiver is dropped or too slow.
+async fn send_chunk(sender: &mpsc::Sender<Result<Bytes>>, chunk: Bytes) -> bool {
+    match actix_web::rt::time::timeout(STREAM_SEND_TIMEOUT, sender.send(Ok(chunk))).await {
+        Ok(sent) => sent.is_ok(),
+        Err(_) => {
+            log::warn!("Gave up streaming to a slow client");
+            false
+        }
+    }
+}
+
 pub(crate) async fn get_user_submissions_from_time(
-    _request: HttpRequest,
+    request: HttpRequest,
     pool: web::Data<PgPool>,
     query: web::Query<GetUserSubmissionQuery>,
 ) -> Result<HttpResponse> {
-    if let GetUserSubmissionQuery {
-        user: ref user_id,
-        from_second: Some(from_second),
-        ..
-    } = *query
-    {
-        let submissions = pool
-            .get_submissions(SubmissionRequest::FromUserAndTime {
-                user_id,
-                from_second,
-                count: USER_SUBMISSION_LIMIT,
-            })
-            .await
-            .map_err(error::ErrorInternalServerError)?;
-        let response = HttpResponse::Ok().make_cors().json(&submissions);
-        Ok(response)
-    } else {
-        Ok(HttpResponse::BadRequest().finish())
-    }
+    let after = parse_cursor(query.cursor.as_deref())?;
+    let from_second = match (query.from_second, after) {
+        (Some(from_second), _) => from_second,
+        (None, Some(_)) => i64::MIN,
+        (None, None) => return Ok(HttpResponse::BadRequest().finish()),
+    };
+    let submissions = pool
+        .get_submissions(SubmissionRequest::FromUserAndTime {
+            user_id: &query.user,
+            from_second,
+            after,
+            count: USER_SUBMISSION_LIMIT,
+        })
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let mut response = HttpResponse::Ok();
+    response.make_cors();
+    link_next_page(&mut response, &request, &submissions, USER_SUBMISSION_LIMIT);
+    Ok(response.json(&submissions))
+}
+
+pub(crate) fn parse_cursor(cursor: Option<&str>) -> Result<Option<SubmissionCursor>> {
+    cursor
+        .map(|cursor| cursor.parse().map_err(error::ErrorBadRequest))
+        .transpose()
+}
+
+/// Adds the `Link` header to the page after the last submission if the page is full, keeping the
+/// other query parameters of the request.
+pub(crate) fn link_next_page(
+    response: &mut HttpResponseBuilder,
+    request: &HttpRequest,
+    submissions: &[Submission],
+    limit: usize,
+) {
+    let last = match submissions.last() {
+        Some(last) if submissions.len() >= limit => last,
+        _ => return,
+    };
+    let cursor = format!("cursor={}", SubmissionCursor::after(last));
+    let query = request
+        .query_string()
+        .split('&')
+        .filter(|param| !param.is_empty() && !param.starts_with("cursor="))
+        .chain(std::iter::once(cursor.as_str()))
+        .collect::<Vec<_>>()
+        .join("&");
+    let link = format!("<{}?{}>; rel=\"next\"", request.path(), query);
+    response.insert_header((LINK, link));
 }
 
 pub(crate) async fn get_user_submission_count(
@@ -71,7 +222,7 @@ pub(crate) async fn get_user_submission_count(
     {
         let range = from_second..to_second;
         let count = pool
-            .get_user_submission_count(&user_id, range)
+            .get_user_submission_count(user_id, range)
             .await
             .map_err(error::ErrorInternalServerError)?;
 
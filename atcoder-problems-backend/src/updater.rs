@@ -0,0 +1,73 @@
+use crate::utils::EXCLUDED_USERS;
+use anyhow::Result;
+use log::info;
+use sql_client::aggregate::AggregateClient;
+use sql_client::problem_info::ProblemInfoUpdater;
+use sql_client::problems_submissions::ProblemsSubmissionUpdater;
+use sql_client::rejudge::RejudgeClient;
+use sql_client::PgPool;
+
+/// The number of submission stamps processed in a transaction.
+const AGGREGATE_BATCH_SIZE: i64 = 10_000;
+
+/// Recomputes all the aggregated tables from all the accepted submissions.
+pub async fn batch_update(pool: &PgPool) -> Result<()> {
+    info!("Executing rebuild_all_aggregates...");
+    pool.rebuild_all_aggregates(&EXCLUDED_USERS).await?;
+
+    info!("Executing update_problem_solver_count...");
+    pool.update_solver_count().await?;
+
+    info!("Executing update_submissions_of_problems...");
+    pool.update_submissions_of_problems().await?;
+
+    info!("Executing update_problem_points...");
+    pool.update_problem_points().await?;
+
+    Ok(())
+}
+
+/// Updates the aggregated tables of the users whose submissions have been stored or changed since
+/// the last update, and of the users whose submissions have been rejudged.
+pub async fn delta_update(pool: &PgPool) -> Result<()> {
+    info!("Executing update_aggregates...");
+    let processed = pool
+        .update_aggregates(&EXCLUDED_USERS, AGGREGATE_BATCH_SIZE)
+        .await?;
+    info!("Processed {} submission stamps.", processed);
+
+    info!("Loading users to recompute ...");
+    let rejudged_users = pool.load_users_to_recompute().await?;
+    let marked_epoch_second = match rejudged_users.iter().map(|&(_, marked)| marked).max() {
+        Some(marked_epoch_second) => marked_epoch_second,
+        None => return Ok(()),
+    };
+    let rejudged_user_ids = rejudged_users
+        .into_iter()
+        .map(|(user_id, _)| user_id)
+        .collect::<Vec<_>>();
+    let user_ids = rejudged_user_ids
+        .iter()
+        .filter(|user_id| !EXCLUDED_USERS.contains(&user_id.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    info!(
+        "Executing rebuild_user_aggregates for {} users...",
+        user_ids.len()
+    );
+    pool.rebuild_user_aggregates(&user_ids).await?;
+
+    // A rejudge can also change the first, fastest and shortest submissions of a problem.
+    info!("Executing update_submissions_of_problems...");
+    pool.update_submissions_of_problems().await?;
+
+    info!("Executing update_solver_count...");
+    pool.update_solver_count().await?;
+
+    info!("Unmarking {} rejudged users ...", rejudged_user_ids.len());
+    pool.unmark_users_to_recompute(&rejudged_user_ids, marked_epoch_second)
+        .await?;
+
+    Ok(())
+}
@@ -1,7 +1,17 @@
+use atcoder_client::{AtCoderClient, RateLimiter, SharedTokenBucket, DEFAULT_REQUESTS_PER_SECOND};
 use fern;
 use log::LevelFilter;
+use sql_client::rate_limit::RateLimitClient;
+use sql_client::PgPool;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+const ATCODER_BUCKET_NAME: &str = "atcoder";
 
 pub const EXCLUDED_USERS: [&str; 17] = [
     "vjudge1",
@@ -23,6 +33,52 @@ pub const EXCLUDED_USERS: [&str; 17] = [
     "luogu_bot6",
 ];
 
+/// The bucket in Postgres which bounds the total request rate of the crawlers in every process.
+#[derive(Debug)]
+struct AtCoderTokenBucket {
+    pool: PgPool,
+    requests_per_second: f64,
+}
+
+impl SharedTokenBucket for AtCoderTokenBucket {
+    fn reserve(&self) -> Pin<Box<dyn Future<Output = Result<Duration>> + Send + '_>> {
+        Box::pin(self.pool.reserve_rate_limit_token(
+            ATCODER_BUCKET_NAME,
+            self.requests_per_second,
+            1.0,
+        ))
+    }
+
+    fn pause(&self, duration: Duration) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(self.pool.pause_rate_limit_bucket(
+            ATCODER_BUCKET_NAME,
+            self.requests_per_second,
+            1.0,
+            duration,
+        ))
+    }
+}
+
+/// Builds the client for the crawlers. `ATCODER_REQUESTS_PER_SECOND` overrides the request rate.
+///
+/// The rate is shared through `pool` by every process connected to the same database.
+pub fn init_atcoder_client(pool: &PgPool) -> Result<AtCoderClient> {
+    let requests_per_second = match env::var("ATCODER_REQUESTS_PER_SECOND") {
+        Ok(value) => value
+            .parse::<f64>()
+            .ok()
+            .filter(|&rate| rate > 0.0)
+            .context("ATCODER_REQUESTS_PER_SECOND must be a positive number.")?,
+        Err(_) => DEFAULT_REQUESTS_PER_SECOND,
+    };
+    let shared = AtCoderTokenBucket {
+        pool: pool.clone(),
+        requests_per_second,
+    };
+    let rate_limiter = RateLimiter::new(requests_per_second).with_shared_bucket(Arc::new(shared));
+    AtCoderClient::builder().rate_limiter(rate_limiter).build()
+}
+
 pub fn init_log_config() -> Result<()> {
     fern::Dispatch::new()
         .format(|out, message, _record| out.finish(format_args!("{}", message)))
@@ -0,0 +1,58 @@
+use anyhow::anyhow;
+use atcoder_problems_backend::scheduler::run_exclusively;
+use sql_client::scheduler_job::{JobLock, JobStatusClient};
+use std::time::Duration;
+
+pub mod utils;
+
+#[actix_web::test]
+async fn test_run_exclusively() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+
+    let ran = run_exclusively(&pool, &pool, "job", "job", || async { Ok(()) })
+        .await
+        .unwrap();
+    assert!(ran);
+    let status = &pool.load_job_statuses().await.unwrap()[0];
+    assert!(status.last_success_epoch_second.is_some());
+    assert!(status.last_error.is_none());
+
+    let ran = run_exclusively(&pool, &pool, "job", "job", || async {
+        Err(anyhow!("failure"))
+    })
+    .await
+    .unwrap();
+    assert!(ran);
+    let status = &pool.load_job_statuses().await.unwrap()[0];
+    assert!(status.last_error.as_ref().unwrap().contains("failure"));
+
+    let lock = JobLock::try_acquire(&pool, "job").await.unwrap().unwrap();
+    let ran = run_exclusively(&pool, &pool, "job", "job", || async {
+        panic!("The job should not run while it is locked.");
+    })
+    .await
+    .unwrap();
+    assert!(!ran);
+    lock.release().await.unwrap();
+
+    let pool_for_job = pool.clone();
+    let result = actix_web::rt::spawn(async move {
+        run_exclusively(&pool_for_job, &pool_for_job, "job", "job", || async {
+            panic!("The job panics.");
+        })
+        .await
+    })
+    .await;
+    assert!(result.is_err());
+    let other_pool = sql_client::initialize_pool(std::env::var("SQL_URL").unwrap())
+        .await
+        .unwrap();
+    for _ in 0..50 {
+        if let Some(lock) = JobLock::try_acquire(&other_pool, "job").await.unwrap() {
+            lock.release().await.unwrap();
+            return;
+        }
+        actix_web::rt::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("The lock of the panicked job should be released.");
+}
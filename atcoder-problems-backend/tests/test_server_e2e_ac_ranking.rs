@@ -21,7 +21,7 @@ async fn test_ac_ranking() {
     .await
     .unwrap();
 
-    let mut app = test::init_service(
+    let app = test::init_service(
         App::new()
             .app_data(web::Data::new(pg_pool.clone()))
             .configure(config_services),
@@ -31,7 +31,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/ac_ranking?from=0&to=10")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -46,7 +46,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/ac_ranking?from=1&to=3")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -60,7 +60,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/ac_ranking?from=10&to=0")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response.as_array().unwrap().len(), 0);
@@ -68,19 +68,19 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/ac_ranking?from=0&to=2000")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/ac_ranking?from=-1&to=10")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=u1")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 1, "rank": 1}));
@@ -88,7 +88,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=u2")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 2, "rank": 0}));
@@ -96,7 +96,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=u3")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 1, "rank": 1}));
@@ -104,7 +104,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=U1")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 1, "rank": 1}));
@@ -112,7 +112,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=U2")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 2, "rank": 0}));
@@ -120,7 +120,7 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=U3")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!({"count": 1, "rank": 1}));
@@ -128,6 +128,6 @@ async fn test_ac_ranking() {
     let request = test::TestRequest::get()
         .uri("/atcoder-api/v3/user/ac_rank?user=does_not_exist")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
@@ -0,0 +1,78 @@
+use actix_web::{http::StatusCode, test, web, App};
+use atcoder_problems_backend::server::config_services;
+use sql_client::PgPool;
+
+pub mod utils;
+
+async fn insert_contests(conn: &PgPool) {
+    sql_client::query(
+        r"INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change)
+         VALUES
+         ('abc999', 4102444800, 6000, 'ABC 999', ' ~ 1999'),
+         ('arc999', 4102531200, 7200, 'ARC 999', ' ~ 2799'),
+         ('agc999', 4102617600, 9000, 'AGC 999', '1200 ~ '),
+         ('practice', 4102704000, 3600, 'Practice', '-'),
+         ('abc001', 1381579200, 7200, 'ABC 001', '-')",
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+fn summaries(body: &[u8]) -> Vec<&str> {
+    std::str::from_utf8(body)
+        .unwrap()
+        .split("\r\n")
+        .filter_map(|line| line.strip_prefix("SUMMARY:"))
+        .collect()
+}
+
+#[actix_web::test]
+async fn test_contest_calendar() {
+    let conn = utils::initialize_and_connect_to_test_sql().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(conn.clone()))
+            .configure(config_services),
+    )
+    .await;
+    insert_contests(&conn).await;
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/contest_calendar")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let body = test::read_body(response).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("UID:abc999@atcoder.jp\r\nDTSTAMP:",));
+    assert!(body.contains("DTSTART:21000101T000000Z\r\nDTEND:21000101T014000Z\r\n"));
+    assert!(body.contains("URL:https://atcoder.jp/contests/abc999\r\n"));
+
+    let cases = [
+        ("", vec!["ABC 999", "ARC 999", "AGC 999", "Practice"]),
+        ("?rated=true", vec!["ABC 999", "ARC 999", "AGC 999"]),
+        ("?rated=false", vec!["Practice"]),
+        ("?series=abc,agc", vec!["ABC 999", "AGC 999"]),
+        ("?series=other&rated=false", vec!["Practice"]),
+    ];
+    for (query, expected) in cases {
+        let request = test::TestRequest::get()
+            .uri(&format!("/atcoder-api/v3/contest_calendar{}", query))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = test::read_body(response).await;
+        assert_eq!(summaries(&body), expected, "query: {}", query);
+    }
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/contest_calendar?series=unknown")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,123 @@
+use actix_web::{test, web, App};
+use atcoder_problems_backend::server::config_services;
+use serde_json::{json, Value};
+use sql_client::PgPool;
+
+pub mod utils;
+
+async fn insert_data_set(conn: &PgPool) {
+    sql_client::query(
+        r"INSERT INTO contest_standings
+            (contest_id, user_id, rank, score, penalty, elapsed_second, is_rated, old_rating)
+         VALUES
+         ('contest1', 'user1', 2, 100, 1, 360, TRUE, 1200),
+         ('contest1', 'user2', 1, 300, 0, 120, FALSE, 2900),
+         ('contest2', 'user1', 5, 50.5, 0, 60, TRUE, 1250)",
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+    sql_client::query(
+        r"INSERT INTO rating_history
+            (user_id, contest_id, is_rated, place, old_rating, new_rating,
+             performance, inner_performance, end_epoch_second)
+         VALUES
+         ('user1', 'contest2', TRUE, 5, 1250, 1300, 1700, 1700, 200),
+         ('user1', 'contest1', TRUE, 2, 1200, 1250, 1600, 1600, 100)",
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+async fn test_contest_result() {
+    let conn = utils::initialize_and_connect_to_test_sql().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(conn.clone()))
+            .configure(config_services),
+    )
+    .await;
+    insert_data_set(&conn).await;
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/rating_history?user=user1")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(
+        response,
+        json!([
+            {
+                "user_id": "user1",
+                "contest_id": "contest1",
+                "is_rated": true,
+                "place": 2,
+                "old_rating": 1200,
+                "new_rating": 1250,
+                "performance": 1600,
+                "inner_performance": 1600,
+                "end_epoch_second": 100
+            },
+            {
+                "user_id": "user1",
+                "contest_id": "contest2",
+                "is_rated": true,
+                "place": 5,
+                "old_rating": 1250,
+                "new_rating": 1300,
+                "performance": 1700,
+                "inner_performance": 1700,
+                "end_epoch_second": 200
+            }
+        ])
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/contest_standings?contest=contest1")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    let users = response
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| (s["user_id"].as_str().unwrap(), s["rank"].as_i64().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(users, vec![("user2", 1), ("user1", 2)]);
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/contest_standings?user=user1")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(
+        response,
+        json!([
+            {
+                "contest_id": "contest1",
+                "user_id": "user1",
+                "rank": 2,
+                "score": 100.0,
+                "penalty": 1,
+                "elapsed_second": 360,
+                "is_rated": true,
+                "old_rating": 1200
+            },
+            {
+                "contest_id": "contest2",
+                "user_id": "user1",
+                "rank": 5,
+                "score": 50.5,
+                "penalty": 0,
+                "elapsed_second": 60,
+                "is_rated": true,
+                "old_rating": 1250
+            }
+        ])
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/rating_history?user=unknown")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(response, json!([]));
+}
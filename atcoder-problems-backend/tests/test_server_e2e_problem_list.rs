@@ -71,8 +71,7 @@ async fn test_list() {
         .next()
         .unwrap()
         .split("=")
-        .skip(1)
-        .next()
+        .nth(1)
         .unwrap();
     assert_eq!(token, VALID_TOKEN);
 
@@ -1,5 +1,7 @@
+use chrono::Utc;
 use rand::Rng;
 use serde_json::{json, Value};
+use sql_client::streak::jst_day;
 use sql_client::PgPool;
 
 pub mod utils;
@@ -126,3 +128,173 @@ async fn test_streak_ranking() {
     server.abort();
     server.await.unwrap_err();
 }
+
+async fn start_server(port: u16) -> actix_web::rt::task::JoinHandle<()> {
+    let server = actix_web::rt::spawn(async move {
+        let pg_pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+            .await
+            .unwrap();
+        actix_web::HttpServer::new(move || {
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(pg_pool.clone()))
+                .configure(atcoder_problems_backend::server::config_services)
+        })
+        .bind(("0.0.0.0", port))
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+    });
+    actix_web::rt::time::sleep(std::time::Duration::from_millis(1000)).await;
+    server
+}
+
+#[actix_web::test]
+async fn test_current_streak_ranking() {
+    let port = setup().await;
+    let pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+        .await
+        .unwrap();
+    let today = jst_day(Utc::now().timestamp());
+    sql_client::query(
+        r"
+        INSERT INTO latest_streaks (user_id, streak, start_day, last_day) VALUES
+        ('u1', 3, $1 - 2, $1),
+        ('u2', 5, $1 - 5, $1 - 1),
+        ('u3', 9, $1 - 10, $1 - 2)
+        ",
+    )
+    .bind(today)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let server = start_server(port).await;
+
+    let response = reqwest::get(url(
+        "/atcoder-api/v3/current_streak_ranking?from=0&to=10",
+        port,
+    ))
+    .await
+    .unwrap()
+    .json::<Value>()
+    .await
+    .unwrap();
+    assert_eq!(
+        response,
+        json!([
+            {"user_id": "u2", "count": 5},
+            {"user_id": "u1", "count": 3}
+        ])
+    );
+
+    let response = reqwest::get(url(
+        "/atcoder-api/v3/user/current_streak_rank?user=u1",
+        port,
+    ))
+    .await
+    .unwrap()
+    .json::<Value>()
+    .await
+    .unwrap();
+    assert_eq!(response, json!({"count":3,"rank":1}));
+
+    let response = reqwest::get(url(
+        "/atcoder-api/v3/user/current_streak_rank?user=u3",
+        port,
+    ))
+    .await
+    .unwrap()
+    .json::<Value>()
+    .await
+    .unwrap();
+    assert_eq!(response, json!({"count":0,"rank":2}));
+
+    let response = reqwest::get(url(
+        "/atcoder-api/v3/user/current_streak_rank?user=does_not_exist",
+        port,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.abort();
+    server.await.unwrap_err();
+}
+
+#[actix_web::test]
+async fn test_user_streak() {
+    let port = setup().await;
+    let pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+        .await
+        .unwrap();
+    let today = jst_day(Utc::now().timestamp());
+    // Noon in JST of the days before today.
+    let noon = |days_ago: i64| (today - days_ago) * 86400 + 3 * 3600;
+    sql_client::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, $1, 'p1', 'c1', 'u1', 'Rust', 0, 0, 'AC'),
+            (2, $2, 'p2', 'c1', 'u1', 'Rust', 0, 0, 'AC'),
+            (3, $3, 'p3', 'c1', 'u1', 'Rust', 0, 0, 'AC'),
+            (4, $4, 'p4', 'c1', 'u1', 'Rust', 0, 0, 'AC'),
+            (5, $4, 'p1', 'c1', 'u1', 'Rust', 0, 0, 'AC'),
+            (6, $5, 'p5', 'c1', 'u1', 'Rust', 0, 0, 'WA')
+        ",
+    )
+    .bind(noon(5))
+    .bind(noon(4))
+    .bind(noon(2))
+    .bind(noon(1))
+    .bind(noon(0))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let server = start_server(port).await;
+
+    let date = |days_ago: i64| {
+        chrono::TimeZone::timestamp(&Utc, (today - days_ago) * 86400, 0)
+            .format("%Y-%m-%d")
+            .to_string()
+    };
+    let response = reqwest::get(url("/atcoder-api/v3/user/streak?user=U1", port))
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        json!({
+            "max_streak": 2,
+            "current_streak": 2,
+            "current_streak_start_date": date(2),
+            "last_accepted_date": date(1),
+            "streaks": [
+                {"start_date": date(5), "last_date": date(4), "streak": 2},
+                {"start_date": date(2), "last_date": date(1), "streak": 2}
+            ]
+        })
+    );
+
+    let response = reqwest::get(url("/atcoder-api/v3/user/streak?user=u2", port))
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        json!({
+            "max_streak": 0,
+            "current_streak": 0,
+            "current_streak_start_date": null,
+            "last_accepted_date": null,
+            "streaks": []
+        })
+    );
+
+    server.abort();
+    server.await.unwrap_err();
+}
@@ -81,6 +81,72 @@ async fn test_user_submissions() {
     assert_eq!(submissions.len(), 5);
     assert!(submissions.iter().all(|s| s.user_id.as_str() == "u2"));
 
+    let response = reqwest::get(url("/atcoder-api/results?user=nobody", port))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "[]");
+
+    server.abort();
+    server.await.unwrap_err();
+}
+
+#[actix_web::test]
+async fn test_user_submissions_pages() {
+    let port = setup().await;
+    let pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+        .await
+        .unwrap();
+    sql_client::query(
+        r"
+        INSERT INTO submissions
+            (epoch_second, problem_id, contest_id, user_id, result, id, language, point, length)
+        SELECT i / 2, 'p1', 'c1', 'heavy', 'AC', i, 'Rust', 0.0, 0
+        FROM generate_series(101, 2600) AS i
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let server_pool = pool.clone();
+    let server = actix_web::rt::spawn(async move {
+        actix_web::HttpServer::new(move || {
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(server_pool.clone()))
+                .configure(atcoder_problems_backend::server::config_services)
+        })
+        .bind(("0.0.0.0", port))
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+    });
+    actix_web::rt::time::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let submissions: Vec<Submission> = reqwest::get(url("/atcoder-api/results?user=heavy", port))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        (101..=2600).collect::<Vec<_>>()
+    );
+
+    sql_client::query("ALTER TABLE submissions RENAME TO broken_submissions")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let response = reqwest::get(url("/atcoder-api/results?user=heavy", port))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        500,
+        "An error before the first chunk should be reported as the status."
+    );
+
     server.abort();
     server.await.unwrap_err();
 }
@@ -390,3 +456,95 @@ async fn test_users_and_time() {
     server.abort();
     server.await.unwrap_err();
 }
+
+/// Follows the `Link` headers from the path, and returns the ids and the number of the pages.
+async fn follow_next_links(path: &str, port: u16) -> (Vec<i64>, usize) {
+    let mut ids = vec![];
+    let mut pages = 0;
+    let mut next = Some(url(path, port));
+    while let Some(current) = next {
+        let response = reqwest::get(current).await.unwrap();
+        assert_eq!(response.status(), 200);
+        next = response.headers().get("link").map(|link| {
+            let link = link.to_str().unwrap();
+            let path = link
+                .strip_prefix('<')
+                .and_then(|link| link.strip_suffix(">; rel=\"next\""))
+                .unwrap();
+            url(path, port)
+        });
+        let submissions: Vec<Submission> = response.json().await.unwrap();
+        ids.extend(submissions.iter().map(|s| s.id));
+        pages += 1;
+    }
+    (ids, pages)
+}
+
+#[actix_web::test]
+async fn test_submissions_cursor() {
+    let port = setup().await;
+    let pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+        .await
+        .unwrap();
+    // Many submissions share a second, which is more than a page.
+    sql_client::query(
+        r"
+        INSERT INTO submissions
+            (epoch_second, problem_id, contest_id, user_id, result, id, language, point, length)
+        SELECT 300, 'p1', 'c1', 'u3', 'AC', 2000 - i, 'Rust', 0.0, 0
+        FROM generate_series(1, 1200) AS i
+        ",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let server = actix_web::rt::spawn(async move {
+        let pg_pool = sql_client::initialize_pool(utils::get_sql_url_from_env())
+            .await
+            .unwrap();
+        actix_web::HttpServer::new(move || {
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(pg_pool.clone()))
+                .configure(atcoder_problems_backend::server::config_services)
+        })
+        .bind(("0.0.0.0", port))
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+    });
+    actix_web::rt::time::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let (ids, pages) = follow_next_links("/atcoder-api/v3/from/200", port).await;
+    assert_eq!(pages, 2);
+    assert_eq!(ids[0], 10);
+    assert_eq!(ids[1..], (800..2000).collect::<Vec<_>>()[..]);
+
+    let (ids, pages) = follow_next_links(
+        "/atcoder-api/v3/user/submissions?user=U3&from_second=0",
+        port,
+    )
+    .await;
+    assert_eq!(pages, 3);
+    assert_eq!(ids, (800..2000).collect::<Vec<_>>());
+
+    let response = reqwest::get(url(
+        "/atcoder-api/v3/user/submissions?user=u3&cursor=300_1500",
+        port,
+    ))
+    .await
+    .unwrap();
+    assert!(response.headers().get("link").is_none());
+    let submissions: Vec<Submission> = response.json().await.unwrap();
+    assert_eq!(submissions.len(), 499);
+    assert_eq!(submissions[0].id, 1501);
+
+    let response = reqwest::get(url("/atcoder-api/v3/from/0?cursor=invalid", port))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.abort();
+    server.await.unwrap_err();
+}
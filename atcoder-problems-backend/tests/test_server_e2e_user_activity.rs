@@ -0,0 +1,69 @@
+use actix_web::{http::StatusCode, test, web, App};
+use atcoder_problems_backend::server::config_services;
+use serde_json::{json, Value};
+use sql_client::PgPool;
+
+pub mod utils;
+
+async fn insert_data_set(conn: &PgPool) {
+    sql_client::query(
+        r"INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+         VALUES
+         (1, 1570114799, 'p1', 'c1', 'u1', 'Rust', 0, 0, 'WA'), -- 2019-10-03T23:59:59+09:00
+         (2, 1570114800, 'p1', 'c1', 'u1', 'Rust', 0, 0, 'AC'), -- 2019-10-04T00:00:00+09:00
+         (3, 1570150800, 'p1', 'c1', 'u1', 'Rust', 0, 0, 'AC'), -- 2019-10-04T10:00:00+09:00
+         (4, 1570287600, 'p2', 'c1', 'u1', 'Rust', 0, 0, 'AC')  -- 2019-10-06T00:00:00+09:00",
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+async fn test_user_daily_activity() {
+    let conn = utils::initialize_and_connect_to_test_sql().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(conn.clone()))
+            .configure(config_services),
+    )
+    .await;
+    insert_data_set(&conn).await;
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/daily_activity?user=u1&from_date=2019-10-01&to_date=2019-10-06")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(
+        response,
+        json!([
+            {"date": "2019-10-03", "submission_count": 1, "accepted_count": 0, "new_accepted_count": 0},
+            {"date": "2019-10-04", "submission_count": 2, "accepted_count": 2, "new_accepted_count": 1},
+            {"date": "2019-10-06", "submission_count": 1, "accepted_count": 1, "new_accepted_count": 1}
+        ])
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/daily_activity?user=u1&from_date=2019-10-04&to_date=2019-10-05")
+        .to_request();
+    let response: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(
+        response,
+        json!([
+            {"date": "2019-10-04", "submission_count": 2, "accepted_count": 2, "new_accepted_count": 1}
+        ])
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/daily_activity?user=u1&from_date=2019-10-04&to_date=10/05")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::get()
+        .uri("/atcoder-api/v3/user/daily_activity?user=u1")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -1,29 +1,64 @@
-use actix_web::{http::StatusCode, test, App};
+use std::pin::Pin;
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, App,
+};
 use atcoder_problems_backend::server::{
     config_services,
     middleware::github_auth::{GithubAuthentication, GithubClient, GithubToken},
+    submission_broadcaster::SubmissionBroadcaster,
 };
+use futures_util::future;
+use httpmock::MockServer;
 use serde_json::{json, Value};
+use sql_client::{
+    internal::{user_manager::UserManager, virtual_contest_team_manager::MAX_MEMBER_NUM_PER_TEAM},
+    models::Submission,
+    PgPool,
+};
 
 pub mod utils;
 
 const VALID_CODE: &str = "VALID-CODE";
 const VALID_TOKEN: &str = "VALID-TOKEN";
 
-#[actix_web::test]
-async fn test_virtual_contest() {
-    let mock_server = utils::start_mock_github_server(VALID_TOKEN);
-    let mock_server_base_url = mock_server.base_url();
-    let mock_api_server = utils::start_mock_github_api_server(VALID_TOKEN, GithubToken { id: 0 });
-    let mock_api_server_base_url = mock_api_server.base_url();
+struct TestContext {
+    pg_pool: PgPool,
+    broadcaster: SubmissionBroadcaster,
+    github_api: MockServer,
+    _github: MockServer,
+}
+
+/// Starts the app against mock GitHub servers and signs in with `VALID_TOKEN` as the GitHub user 0.
+async fn setup() -> (
+    impl Service<
+        Request,
+        Response = ServiceResponse<impl MessageBody<Error = impl std::fmt::Debug>>,
+        Error = actix_web::Error,
+    >,
+    TestContext,
+) {
+    let github_server = utils::start_mock_github_server(VALID_TOKEN);
+    let github_api_server = utils::start_mock_github_api_server(VALID_TOKEN, GithubToken { id: 0 });
     let pg_pool = utils::initialize_and_connect_to_test_sql().await;
-    let github =
-        GithubClient::new("", "", &mock_server_base_url, &mock_api_server_base_url).unwrap();
-    let mut app = test::init_service(
+    let github = GithubClient::new(
+        "",
+        "",
+        &github_server.base_url(),
+        &github_api_server.base_url(),
+    )
+    .unwrap();
+    let broadcaster = SubmissionBroadcaster::default();
+    let app = test::init_service(
         App::new()
             .wrap(GithubAuthentication::new(github.clone()))
-            .app_data(actix_web::web::Data::new(github.clone()))
+            .app_data(actix_web::web::Data::new(github))
             .app_data(actix_web::web::Data::new(pg_pool.clone()))
+            .app_data(actix_web::web::Data::new(broadcaster.clone()))
             .configure(config_services),
     )
     .await;
@@ -31,9 +66,22 @@ async fn test_virtual_contest() {
     let request = test::TestRequest::get()
         .uri(&format!("/internal-api/authorize?code={}", VALID_CODE))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::FOUND);
 
+    let ctx = TestContext {
+        pg_pool,
+        broadcaster,
+        github_api: github_api_server,
+        _github: github_server,
+    };
+    (app, ctx)
+}
+
+#[actix_web::test]
+async fn test_virtual_contest() {
+    let (app, _ctx) = setup().await;
+
     let cookie_header = format!("token={}", VALID_TOKEN);
 
     let request = test::TestRequest::post()
@@ -43,7 +91,7 @@ async fn test_virtual_contest() {
             "atcoder_user_id": "atcoder_user1"
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::post()
@@ -57,7 +105,7 @@ async fn test_virtual_contest() {
             "penalty_second": 0,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     let contest_id = response["contest_id"].as_str().unwrap();
@@ -69,7 +117,7 @@ async fn test_virtual_contest() {
             "atcoder_user_id": "atcoder_user1"
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::post()
@@ -84,14 +132,14 @@ async fn test_virtual_contest() {
             "penalty_second": 300,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/my")
         .append_header(("Cookie", cookie_header.clone()))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -107,6 +155,9 @@ async fn test_virtual_contest() {
                 "mode": null,
                 "is_public": true,
                 "penalty_second": 300,
+                "is_archived": false,
+                "is_invitation_required": false,
+                "is_registration_closed": false,
             }
         ])
     );
@@ -115,7 +166,7 @@ async fn test_virtual_contest() {
         .uri("/internal-api/contest/joined")
         .append_header(("Cookie", cookie_header.clone()))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!([]));
@@ -127,14 +178,14 @@ async fn test_virtual_contest() {
             "contest_id": contest_id,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/joined")
         .append_header(("Cookie", cookie_header.clone()))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -150,6 +201,9 @@ async fn test_virtual_contest() {
                 "mode": null,
                 "is_public": true,
                 "penalty_second": 300,
+                "is_archived": false,
+                "is_invitation_required": false,
+                "is_registration_closed": false,
             }
         ])
     );
@@ -161,14 +215,14 @@ async fn test_virtual_contest() {
             "contest_id": contest_id,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/joined")
         .append_header(("Cookie", cookie_header.clone()))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response, json!([]));
@@ -181,7 +235,7 @@ async fn test_virtual_contest() {
             "problems": [{ "id": "problem_1", "point": 100 }],
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::post()
@@ -192,7 +246,7 @@ async fn test_virtual_contest() {
             "problems": [{ "id": "problem_1", "point": 100 }, { "id": "problem_2" }],
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::post()
@@ -202,13 +256,13 @@ async fn test_virtual_contest() {
             "contest_id": contest_id,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri(&format!("/internal-api/contest/get/{}", contest_id))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -224,16 +278,20 @@ async fn test_virtual_contest() {
                 "mode": null,
                 "is_public": true,
                 "penalty_second": 300,
+                "is_archived": false,
+                "is_invitation_required": false,
+                "is_registration_closed": false,
             },
             "problems": [{ "id": "problem_1", "point": 100, "order": null }, { "id": "problem_2", "point": null, "order": null }],
             "participants": ["atcoder_user1"],
+            "teams": [],
         })
     );
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/recent")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(
@@ -249,6 +307,9 @@ async fn test_virtual_contest() {
                 "id": contest_id,
                 "mode": null,
                 "penalty_second": 300,
+                "is_archived": false,
+                "is_invitation_required": false,
+                "is_registration_closed": false,
             }
         ])
     );
@@ -256,27 +317,7 @@ async fn test_virtual_contest() {
 
 #[actix_web::test]
 async fn test_virtual_contest_visibility() {
-    let mock_server = utils::start_mock_github_server(VALID_TOKEN);
-    let mock_server_base_url = mock_server.base_url();
-    let mock_api_server = utils::start_mock_github_api_server(VALID_TOKEN, GithubToken { id: 0 });
-    let mock_api_server_base_url = mock_api_server.base_url();
-    let pg_pool = utils::initialize_and_connect_to_test_sql().await;
-    let github =
-        GithubClient::new("", "", &mock_server_base_url, &mock_api_server_base_url).unwrap();
-    let mut app = test::init_service(
-        App::new()
-            .wrap(GithubAuthentication::new(github.clone()))
-            .app_data(actix_web::web::Data::new(github.clone()))
-            .app_data(actix_web::web::Data::new(pg_pool.clone()))
-            .configure(config_services),
-    )
-    .await;
-
-    let request = test::TestRequest::get()
-        .uri(&format!("/internal-api/authorize?code={}", VALID_CODE))
-        .to_request();
-    let response = test::call_service(&mut app, request).await;
-    assert_eq!(response.status(), StatusCode::FOUND);
+    let (app, _ctx) = setup().await;
 
     let cookie_header = format!("token={}", VALID_TOKEN);
 
@@ -291,7 +332,7 @@ async fn test_virtual_contest_visibility() {
             "penalty_second": 300,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     let contest_id = response["contest_id"].as_str().unwrap();
@@ -299,7 +340,7 @@ async fn test_virtual_contest_visibility() {
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/recent")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response[0]["id"].as_str().unwrap(), contest_id);
@@ -318,13 +359,13 @@ async fn test_virtual_contest_visibility() {
             "penalty_second": 300,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/recent")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response.as_array().unwrap().len(), 0);
@@ -341,7 +382,7 @@ async fn test_virtual_contest_visibility() {
             "penalty_second": 300,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     let contest_id = response["contest_id"].as_str().unwrap();
@@ -349,7 +390,7 @@ async fn test_virtual_contest_visibility() {
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/recent")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response.as_array().unwrap().len(), 0);
@@ -367,15 +408,991 @@ async fn test_virtual_contest_visibility() {
             "penalty_second": 300,
         }))
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = test::TestRequest::get()
         .uri("/internal-api/contest/recent")
         .to_request();
-    let response = test::call_service(&mut app, request).await;
+    let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::OK);
     let response: Value = test::read_body_json(response).await;
     assert_eq!(response.as_array().unwrap().len(), 1);
     assert_eq!(response[0]["id"].as_str().unwrap(), contest_id);
 }
+
+#[actix_web::test]
+async fn test_virtual_contest_standings() {
+    let (app, ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/user/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "atcoder_user_id": "atcoder_user1"
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 100,
+            "duration_second": 1000,
+            "penalty_second": 300,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/item/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "contest_id": contest_id,
+            "problems": [{ "id": "problem_2", "order": 2 }, { "id": "problem_1", "point": 100, "order": 1 }],
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/join")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "contest_id": contest_id,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    sql_client::query(
+        r"INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES ('1', 'atcoder_user2')",
+    )
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+    sql_client::query(
+        r"INSERT INTO internal_virtual_contest_participants (internal_virtual_contest_id, internal_user_id) VALUES ($1, '1')",
+    )
+    .bind(contest_id)
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+    sql_client::query(
+        r"
+    INSERT INTO
+        submissions (epoch_second, problem_id, contest_id, user_id, result, id, language, point, length)
+        VALUES
+            (110,  'problem_1', 'c1', 'atcoder_user1', 'WA', 1, 'Rust', 0.0,   0),
+            (120,  'problem_1', 'c1', 'atcoder_user1', 'CE', 2, 'Rust', 0.0,   0),
+            (130,  'problem_1', 'c1', 'atcoder_user1', 'AC', 3, 'Rust', 400.0, 0),
+            (140,  'problem_2', 'c1', 'atcoder_user1', 'AC', 4, 'Rust', 200.0, 0),
+            (2000, 'problem_2', 'c1', 'atcoder_user1', 'AC', 5, 'Rust', 200.0, 0),
+            (150,  'problem_2', 'c1', 'atcoder_user2', 'WA', 6, 'Rust', 0.0,   0),
+            (160,  'problem_3', 'c1', 'atcoder_user2', 'AC', 7, 'Rust', 300.0, 0),
+            (170,  'problem_1', 'c1', 'atcoder_user3', 'AC', 8, 'Rust', 100.0, 0)",
+    )
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/standings/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response,
+        json!({
+            "mode": null,
+            "problems": [{ "id": "problem_1", "point": 100, "order": 1 }, { "id": "problem_2", "point": null, "order": 2 }],
+            "standings": [
+                {
+                    "rank": 1,
+                    "user_id": "atcoder_user1",
+                    "point": 300.0,
+                    "penalties": 1,
+                    "time_second": 340,
+                    "results": {
+                        "problem_1": {
+                            "point": 100.0,
+                            "accepted": true,
+                            "trials": 2,
+                            "penalties": 1,
+                            "first_accepted_epoch_second": 130,
+                            "last_updated_epoch_second": 130,
+                        },
+                        "problem_2": {
+                            "point": 200.0,
+                            "accepted": true,
+                            "trials": 1,
+                            "penalties": 0,
+                            "first_accepted_epoch_second": 140,
+                            "last_updated_epoch_second": 140,
+                        },
+                    },
+                },
+                {
+                    "rank": 2,
+                    "user_id": "atcoder_user2",
+                    "point": 0.0,
+                    "penalties": 0,
+                    "time_second": 0,
+                    "results": {
+                        "problem_2": {
+                            "point": 0.0,
+                            "accepted": false,
+                            "trials": 1,
+                            "penalties": 0,
+                            "first_accepted_epoch_second": null,
+                            "last_updated_epoch_second": 150,
+                        },
+                    },
+                },
+            ],
+        })
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/standings/unknown-contest")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/get/unknown-contest")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_update_by_non_owner() {
+    const OTHER_TOKEN: &str = "OTHER-TOKEN";
+
+    let (app, ctx) = setup().await;
+    ctx.github_api.mock(|when, then| {
+        when.method("GET")
+            .path("/user")
+            .header("Authorization", format!("token {}", OTHER_TOKEN));
+        then.status(200).json_body_obj(&GithubToken { id: 1 });
+    });
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let other_cookie_header = format!("token={}", OTHER_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/update")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({
+            "id": contest_id,
+            "title": "hijacked title",
+            "memo": "hijacked memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/item/update")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({
+            "contest_id": contest_id,
+            "problems": [{ "id": "problem_1" }],
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "id": "THIS_IS_FAKE_CONTEST_ID",
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["title"], "contest title");
+    assert_eq!(response["info"]["memo"], "contest memo");
+    assert_eq!(response["problems"], json!([]));
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_delete_and_archive() {
+    let (app, _ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/archive")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/recent")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response, json!([]));
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["is_archived"], true);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/unarchive")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/recent")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response[0]["id"].as_str().unwrap(), contest_id);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/delete")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/my")
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response, json!([]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/delete")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_mode() {
+    let (app, _ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "mode": "unknown",
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "mode": "lockout",
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["mode"], "lockout");
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "id": contest_id,
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "mode": "unknown",
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "id": contest_id,
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "mode": null,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/standings/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["mode"], Value::Null);
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_invitation() {
+    const OTHER_TOKEN: &str = "OTHER-TOKEN";
+
+    let (app, ctx) = setup().await;
+    ctx.github_api.mock(|when, then| {
+        when.method("GET")
+            .path("/user")
+            .header("Authorization", format!("token {}", OTHER_TOKEN));
+        then.status(200).json_body_obj(&GithubToken { id: 1 });
+    });
+    ctx.pg_pool.register_user("1").await.unwrap();
+    ctx.pg_pool
+        .update_internal_user_info("1", "other_atcoder_id")
+        .await
+        .unwrap();
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let other_cookie_header = format!("token={}", OTHER_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/invitation/create")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/invitation/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let invite_token = response["invite_token"].as_str().unwrap().to_owned();
+
+    let request = test::TestRequest::get()
+        .uri(&format!(
+            "/internal-api/contest/invitation/get/{}",
+            contest_id
+        ))
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response, json!({ "invite_token": invite_token }));
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/recent")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response, json!([]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/join")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/join")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "invite_token": invite_token }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["is_invitation_required"], true);
+    assert_eq!(response["participants"], json!(["other_atcoder_id"]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/participant/kick")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "atcoder_user_id": "other_atcoder_id" }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/registration/close")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/join")
+        .append_header(("Cookie", other_cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "invite_token": invite_token }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["is_registration_closed"], true);
+    assert_eq!(response["participants"], json!([]));
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_clone() {
+    let (app, _ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "mode": "training",
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/item/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "contest_id": contest_id,
+            "problems": [{ "id": "problem_1", "point": 100 }],
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/clone")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "start_epoch_second": 100 }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let cloned_id = response["contest_id"].as_str().unwrap();
+    assert_ne!(cloned_id, contest_id);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", cloned_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["info"]["title"], "contest title");
+    assert_eq!(response["info"]["start_epoch_second"], 100);
+    assert_eq!(response["info"]["mode"], "training");
+    assert_eq!(
+        response["problems"],
+        json!([{ "id": "problem_1", "point": 100, "order": null }])
+    );
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/clone")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": "unknown-contest", "start_epoch_second": 100 }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_team() {
+    let (app, ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 1000,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/item/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "contest_id": contest_id,
+            "problems": [{ "id": "problem_1" }],
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "name": "team name", "members": ["user1", "user2"] }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let team_id = response["team_id"].as_str().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "name": "too large",
+            "members": (0..=MAX_MEMBER_NUM_PER_TEAM)
+                .map(|i| format!("user{}", i))
+                .collect::<Vec<_>>(),
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/team/my")
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let team = json!({
+        "id": team_id,
+        "name": "team name",
+        "owner_user_id": "0",
+        "members": ["user1", "user2"],
+    });
+    assert_eq!(response, json!([team]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/join")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "team_id": team_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    sql_client::query(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 10, 'problem_1', 'contest', 'user1', 'Rust', 0, 0, 'WA'),
+            (2, 20, 'problem_1', 'contest', 'user2', 'Rust', 100, 0, 'AC')
+        ",
+    )
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/standings/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["standings"][0]["user_id"], team_id);
+    assert_eq!(response["standings"][0]["team"]["name"], "team name");
+    assert_eq!(response["standings"][0]["team"], team);
+    assert_eq!(response["standings"][0]["point"], 100.0);
+    assert_eq!(response["standings"][0]["penalties"], 1);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["teams"], json!([team]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/leave")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "team_id": team_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/join")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "team_id": team_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/kick")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_id, "team_id": team_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/get/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["teams"], json!([]));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/team/delete")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "team_id": "unknown-team" }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_calendar() {
+    let (app, _ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut contest_ids = vec![];
+    for (title, start_epoch_second) in [
+        ("past contest", 1i64),
+        ("future contest", 4102444800),
+        ("archived contest", 4102444800),
+    ] {
+        let request = test::TestRequest::post()
+            .uri("/internal-api/contest/create")
+            .append_header(("Cookie", cookie_header.clone()))
+            .set_json(json!({
+                "title": title,
+                "memo": "",
+                "start_epoch_second": start_epoch_second,
+                "duration_second": 3600,
+                "penalty_second": 0,
+            }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: Value = test::read_body_json(response).await;
+        contest_ids.push(response["contest_id"].as_str().unwrap().to_owned());
+    }
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/archive")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "contest_id": contest_ids[2] }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/calendar/user/0")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "The feed should not be reachable by the user id."
+    );
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/user/calendar_token/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let calendar_token = response["calendar_token"].as_str().unwrap().to_owned();
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/user/calendar_token")
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response: Value = test::read_body_json(response).await;
+    assert_eq!(response["calendar_token"], calendar_token);
+
+    let request = test::TestRequest::get()
+        .uri(&format!(
+            "/internal-api/contest/calendar/user/{}",
+            calendar_token
+        ))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let body = test::read_body(response).await;
+    // Unfold the long content lines.
+    let body = String::from_utf8(body.to_vec())
+        .unwrap()
+        .replace("\r\n ", "");
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(
+        body.matches("BEGIN:VEVENT").count(),
+        2,
+        "Archived contests should not be listed."
+    );
+    assert!(body.contains("SUMMARY:past contest\r\n"));
+    assert!(body.contains("DTSTART:19700101T000001Z\r\nDTEND:19700101T010001Z\r\n"));
+    assert!(body.contains(&format!(
+        "URL:https://kenkoooo.com/atcoder/#/contest/show/{}\r\n",
+        contest_ids[0]
+    )));
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/user/calendar_token/delete")
+        .append_header(("Cookie", cookie_header.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri(&format!(
+            "/internal-api/contest/calendar/user/{}",
+            calendar_token
+        ))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/calendar/recent")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = test::read_body(response).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(body.matches("BEGIN:VEVENT").count(), 1);
+    assert!(body.contains("SUMMARY:future contest\r\n"));
+}
+
+#[actix_web::test]
+async fn test_virtual_contest_events() {
+    let (app, ctx) = setup().await;
+
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/user/update")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({ "atcoder_user_id": "atcoder_user1" }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/internal-api/contest/create")
+        .append_header(("Cookie", cookie_header.clone()))
+        .set_json(json!({
+            "title": "contest title",
+            "memo": "contest memo",
+            "start_epoch_second": 1,
+            "duration_second": 4102444800i64,
+            "penalty_second": 0,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response: Value = test::read_body_json(response).await;
+    let contest_id = response["contest_id"].as_str().unwrap();
+
+    for request in [
+        test::TestRequest::post()
+            .uri("/internal-api/contest/item/update")
+            .set_json(json!({ "contest_id": contest_id, "problems": [{ "id": "problem_1" }] })),
+        test::TestRequest::post()
+            .uri("/internal-api/contest/join")
+            .set_json(json!({ "contest_id": contest_id })),
+    ] {
+        let request = request
+            .append_header(("Cookie", cookie_header.clone()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/internal-api/contest/events/{}", contest_id))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+
+    let submission = |id: i64, user_id: &str, problem_id: &str| Submission {
+        id,
+        epoch_second: 100,
+        user_id: user_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+        result: "AC".to_owned(),
+        ..Default::default()
+    };
+    ctx.broadcaster.send(vec![
+        submission(1, "other_user", "problem_1"),
+        submission(2, "atcoder_user1", "other_problem"),
+        submission(3, "atcoder_user1", "problem_1"),
+    ]);
+
+    let mut body = response.into_body();
+    let mut events = String::new();
+    while !events.contains("event: submission") {
+        let chunk = future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        events.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    let data = events
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str::<Value>(data).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], 3);
+
+    let request = test::TestRequest::get()
+        .uri("/internal-api/contest/events/unknown-contest")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,53 @@
+use atcoder_problems_backend::updater::delta_update;
+use sql_client::accepted_count::AcceptedCountClient;
+use sql_client::models::Submission;
+use sql_client::rejudge::{Rejudge, RejudgeClient};
+use sql_client::submission_client::SubmissionClient;
+
+pub mod utils;
+
+#[actix_web::test]
+async fn test_delta_update_recomputes_rejudged_users() {
+    let pool = utils::initialize_and_connect_to_test_sql().await;
+    pool.update_submissions(&[
+        Submission {
+            id: 1,
+            problem_id: "problem1".to_string(),
+            contest_id: "contest1".to_string(),
+            user_id: "user1".to_string(),
+            result: "AC".to_string(),
+            ..Default::default()
+        },
+        Submission {
+            id: 2,
+            problem_id: "problem2".to_string(),
+            contest_id: "contest1".to_string(),
+            user_id: "user1".to_string(),
+            result: "WA".to_string(),
+            ..Default::default()
+        },
+    ])
+    .await
+    .unwrap();
+    sql_client::query("INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 2)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.record_rejudges(&[Rejudge {
+        submission_id: 2,
+        detected_epoch_second: 100,
+        contest_id: "contest1".to_string(),
+        problem_id: "problem2".to_string(),
+        user_id: "user1".to_string(),
+        old_result: "AC".to_string(),
+        new_result: "WA".to_string(),
+        old_point: 0.0,
+        new_point: 0.0,
+    }])
+    .await
+    .unwrap();
+
+    delta_update(&pool).await.unwrap();
+    assert_eq!(pool.get_users_accepted_count("user1").await, Some(1));
+    assert!(pool.load_users_to_recompute().await.unwrap().is_empty());
+}
@@ -1,10 +1,8 @@
 use atcoder_problems_backend::server::middleware::github_auth::GithubToken;
 use httpmock::MockServer;
 use serde_json::json;
-use sql_client::{initialize_pool, PgPool};
-use std::fs::read_to_string;
+use sql_client::{initialize_pool, run_migrations, PgPool};
 
-const SQL_FILE: &str = "../config/database-definition.sql";
 const SQL_URL_ENV_KEY: &str = "SQL_URL";
 
 pub fn get_sql_url_from_env() -> String {
@@ -14,12 +12,19 @@ pub fn get_sql_url_from_env() -> String {
 pub async fn initialize_and_connect_to_test_sql() -> PgPool {
     let conn = initialize_pool(get_sql_url_from_env()).await.unwrap();
 
-    for query_str in read_to_string(SQL_FILE).unwrap().split(";") {
-        sql_client::query(query_str).execute(&conn).await.unwrap();
-    }
+    sql_client::query("DROP SCHEMA public CASCADE")
+        .execute(&conn)
+        .await
+        .unwrap();
+    sql_client::query("CREATE SCHEMA public")
+        .execute(&conn)
+        .await
+        .unwrap();
+    run_migrations(&conn).await.unwrap();
     conn
 }
 
+#[allow(dead_code)]
 pub fn start_mock_github_server(access_token: &str) -> MockServer {
     let server = MockServer::start();
     let token = access_token.to_string();
@@ -32,6 +37,7 @@ pub fn start_mock_github_server(access_token: &str) -> MockServer {
     server
 }
 
+#[allow(dead_code)]
 pub fn start_mock_github_api_server(access_token: &str, token: GithubToken) -> MockServer {
     let server = MockServer::start();
     let token_header = format!("token {}", access_token);